@@ -1,9 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{
-    entity::soldier::WeaponClass,
-    types::{Precision, SoldierIndex, WorldPoint},
-};
+use crate::{entity::soldier::WeaponClass, types::WorldPoint};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Gesture {
@@ -33,5 +30,5 @@ impl Gesture {
 
 pub enum GestureContext {
     Idle,
-    Firing(WorldPoint, Option<(SoldierIndex, Precision)>),
+    Firing(WorldPoint),
 }
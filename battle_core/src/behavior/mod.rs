@@ -57,6 +57,8 @@ pub enum Behavior {
     // Combat
     SuppressFire(WorldPoint),
     EngageSoldier(SoldierIndex),
+    EngageVehicle(VehicleIndex),
+    ThrowSmoke(WorldPoint),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -84,6 +86,7 @@ impl Behavior {
             // default_behavior should never be called for EngageSquad & SuppressFire
             Order::EngageSquad(_squad_id) => unreachable!(),
             Order::SuppressFire(_point) => unreachable!(),
+            Order::SmokeScreen(_point) => unreachable!(),
         }
     }
 
@@ -101,6 +104,8 @@ impl Behavior {
             Behavior::Dead => BehaviorPropagation::Never,
             Behavior::Unconscious => BehaviorPropagation::Never,
             Behavior::EngageSoldier(_) => BehaviorPropagation::OnChange,
+            Behavior::EngageVehicle(_) => BehaviorPropagation::OnChange,
+            Behavior::ThrowSmoke(_) => BehaviorPropagation::OnChange,
         }
     }
 
@@ -124,6 +129,8 @@ impl Behavior {
             Behavior::Unconscious => {}
             Behavior::SuppressFire(_) => {}
             Behavior::EngageSoldier(_) => {}
+            Behavior::EngageVehicle(_) => {}
+            Behavior::ThrowSmoke(_) => {}
         }
 
         false
@@ -142,14 +149,19 @@ impl Behavior {
             | Behavior::Dead
             | Behavior::Unconscious
             | Behavior::SuppressFire(_)
-            | Behavior::EngageSoldier(_) => None,
+            | Behavior::EngageSoldier(_)
+            | Behavior::EngageVehicle(_)
+            | Behavior::ThrowSmoke(_) => None,
         }
     }
 
     pub fn posture(&self) -> Posture {
         // TODO : posture can be different on same behavior (like with SuppressFire, EngageSoldier)
         match self {
-            Behavior::MoveTo(_) | Behavior::MoveFastTo(_) | Behavior::Idle(_) => Posture::StandUp,
+            Behavior::MoveTo(_)
+            | Behavior::MoveFastTo(_)
+            | Behavior::Idle(_)
+            | Behavior::ThrowSmoke(_) => Posture::StandUp,
             Behavior::Defend(_)
             | Behavior::SneakTo(_)
             | Behavior::DriveTo(_)
@@ -158,7 +170,8 @@ impl Behavior {
             | Behavior::Dead
             | Behavior::Unconscious
             | Behavior::SuppressFire(_)
-            | Behavior::EngageSoldier(_) => Posture::Flat,
+            | Behavior::EngageSoldier(_)
+            | Behavior::EngageVehicle(_) => Posture::Flat,
         }
     }
 
@@ -198,6 +211,8 @@ impl Display for Behavior {
             Behavior::Unconscious => f.write_str("Unconscious"),
             Behavior::SuppressFire(_) => f.write_str("SuppressFire"),
             Behavior::EngageSoldier(_) => f.write_str("EngageSquad"),
+            Behavior::EngageVehicle(_) => f.write_str("EngageVehicle"),
+            Behavior::ThrowSmoke(_) => f.write_str("ThrowSmoke"),
         }
     }
 }
@@ -11,6 +11,18 @@ use strum::IntoEnumIterator;
 
 pub const DEFAULT_SERVER_REP_ADDRESS: &str = "tcp://0.0.0.0:4255";
 pub const DEFAULT_SERVER_PUB_ADDRESS: &str = "tcp://0.0.0.0:4256";
+// Network threads wake up at least at this frequency to check if they must stop
+pub const NETWORK_POLL_TIMEOUT_MS: i32 = 100;
+// Maximum duration to try to send pending messages when closing a socket
+pub const NETWORK_LINGER_MS: i32 = 1000;
+// Maximum duration to wait server acknowledgement of sent messages
+pub const NETWORK_ACK_TIMEOUT_MS: i32 = 5000;
+// Lockstep peers exchange their orders by turn of this frames count
+pub const LOCKSTEP_TURN_FRAMES: u64 = 6;
+// Orders given during a turn are applied this turns count later
+pub const LOCKSTEP_INPUT_DELAY_TURNS: u64 = 2;
+// Lockstep peers compare their battle state checksum at this frequency
+pub const LOCKSTEP_CHECKSUM_FREQ: u64 = 120;
 ///
 pub const TARGET_FPS: u64 = 60;
 pub const SOLDIER_UPDATE_FREQ: u64 = 1;
@@ -19,12 +31,46 @@ pub const SOLDIER_ANIMATE_FREQ: u64 = 20;
 pub const SQUAD_LEADERS_UPDATE_FREQ: u64 = 120;
 pub const INTERIORS_UPDATE_FREQ: u64 = 60;
 pub const VISIBILITY_UPDATE_FREQ: u64 = 60;
+// Maximum count of sight lines kept in map line of sight cache
+pub const LOS_CACHE_CAPACITY: usize = 100_000;
+// Count of independently locked parts of map line of sight cache
+pub const LOS_CACHE_SHARDS: usize = 64;
+// Size (in world pixels) of spatial index cells used to find soldiers around
+pub const VISIBILITY_INDEX_CELL_SIZE: f32 = 100.;
 pub const MORALE_UPDATE_FREQ: u64 = 300;
 pub const VICTORY_UPDATE_FREQ: u64 = 300;
 pub const FEELING_DECREASING_FREQ: u64 = 60;
 pub const PHYSICS_UPDATE_FREQ: u64 = 1;
 //
 pub const END_MORALE: f32 = 0.2;
+// Frequency of AI players decisions
+pub const AI_UPDATE_FREQ: u64 = 120;
+// AI squad retreat to cover when its health (ready members ratio) is under this value
+pub const AI_RETREAT_HEALTH: f32 = 0.5;
+// AI squads retreat to cover when their side morale is under this value
+pub const AI_RETREAT_MORALE: f32 = 0.4;
+// AI squads engage visible opponents under this distance
+pub const AI_ENGAGE_DISTANCE_METERS: i64 = 150;
+// AI squad is considered on its objective under this distance
+pub const AI_OBJECTIVE_DISTANCE_METERS: i64 = 15;
+// Narrowest fire sector (radians)
+pub const FIRE_SECTOR_MINIMUM_WIDTH: f32 = 0.2;
+// Opponent who fired during these last frames is considered as threatening
+pub const TARGET_POLICY_RECENT_SHOOT_FRAMES: u64 = TARGET_FPS * 5;
+// Segments count used to draw fire sector arc
+pub const FIRE_SECTOR_ARC_SEGMENTS: usize = 12;
+// Frequency of ambushes trigger check
+pub const AMBUSH_UPDATE_FREQ: u64 = 10;
+// Ambush without kill zone is triggered by opponents under this distance
+pub const AMBUSH_TRIGGER_DISTANCE_METERS: i64 = 50;
+// Smaller drawn kill zones (width or height) are considered as accidental drags
+pub const AMBUSH_KILL_ZONE_MINIMUM_METERS: i64 = 10;
+// Frequency of bounding overwatches swap check
+pub const BOUNDING_OVERWATCH_UPDATE_FREQ: u64 = 60;
+// Length of one bounding overwatch bound
+pub const BOUNDING_OVERWATCH_BOUND_METERS: i64 = 40;
+// Bounding overwatch is finished when both squads are under this distance of destination
+pub const BOUNDING_OVERWATCH_ARRIVAL_METERS: i64 = 10;
 ///
 pub const VISIBILITY_IDLE_STANDUP_MODIFIER: f32 = 0.5;
 pub const VISIBILITY_IDLE_CROUCH_MODIFIER: f32 = 0.5;
@@ -55,6 +101,7 @@ pub const TILE_TYPE_OPACITY_LIGHT_UNDERBRUSH: f32 = 0.025;
 pub const TILE_TYPE_OPACITY_MIDDLE_WOOD_LOGS: f32 = 0.5;
 pub const TILE_TYPE_OPACITY_HEDGE: f32 = 0.5;
 pub const TILE_TYPE_OPACITY_MIDDLE_ROCK: f32 = 0.3;
+pub const TILE_TYPE_OPACITY_CRATER: f32 = 0.3;
 //
 pub const VISIBILITY_BY_LAST_FRAME_SHOOT: u64 = TARGET_FPS * 15;
 pub const VISIBILITY_BY_LAST_FRAME_SHOOT_DISTANCE: usize = 4;
@@ -83,6 +130,12 @@ pub const VEHICLE_DRIVE_ORIENTATION_ADVANCE_TOLERANCE_DIFF: f32 = 50.;
 
 // Grid distance to search cover point
 pub const COVER_DISTANCE: i32 = 6;
+// Grid distance to search cover point around a formation position
+pub const FORMATION_COVER_DISTANCE: i32 = 2;
+// Distance (in pixels) between squad members in formation
+pub const FORMATION_SPACING: f32 = 10.0;
+// Distance (in pixels) between squad members in dispersed formation
+pub const FORMATION_DISPERSED_SPACING: f32 = 25.0;
 
 // Visibility computing must consider firsts tiles differently
 pub const VISIBILITY_FIRSTS: usize = 4;
@@ -93,6 +146,98 @@ pub const VISIBILITY_PIXEL_STEPS: usize = 5;
 pub const COVERAGE_PIXEL_STEPS: usize = 5;
 // When compute coverage, configure here how many tile to consider starting from soldier
 pub const COVERAGE_TILE_STEPS: usize = 3;
+// Height (in meters) of eyes above ground when compute visibility on elevated terrain
+pub const ELEVATION_EYE_HEIGHT: f32 = 1.5;
+// When compute elevation masking, configure here each pixels step of line which me considered
+pub const ELEVATION_PIXEL_STEPS: usize = 5;
+// Opacity added by each tile of terrain higher than sight line
+pub const ELEVATION_MASK_OPACITY: f32 = 1.0;
+// Path cost added by each meter climbed
+pub const ELEVATION_CLIMB_COST: f32 = 10.0;
+// Height (in meters) above ground of explosion blast
+pub const ELEVATION_EXPLOSION_HEIGHT: f32 = 0.5;
+// Height (in meters) of each building floor above ground floor
+pub const INTERIOR_FLOOR_HEIGHT: f32 = 3.0;
+// Chance to be covered by walls when inside a building and shot from outside
+pub const INTERIOR_COVERAGE: f32 = 0.5;
+// Skill of soldiers when not specified by deployment (from 0.0 to 1.0)
+pub const SOLDIER_DEFAULT_SKILL: f32 = 0.5;
+// Dispersion cone is multiplied by this factor when shooter is lying
+pub const DISPERSION_FLAT_FACTOR: f32 = 0.7;
+// Dispersion cone is multiplied by this factor when shooter is moving
+pub const DISPERSION_MOVING_FACTOR: f32 = 2.5;
+// Missing bullet keeps going this distance (in meters) after its target
+pub const BULLET_MISS_EXTENSION_METERS: i64 = 150;
+// Soldier nearer than this distance (in millimeters) of a bullet path can be hit by it
+pub const BULLET_HIT_DISTANCE_MILLIMETERS: i64 = 300;
+// Average chance of a bullet passing nearer than BULLET_HIT_DISTANCE_MILLIMETERS of a standing
+// soldier to hit his body
+pub const BULLET_HIT_CHANCE: f32 = 0.2;
+// Hit chance is multiplied by this factor when soldier is lying
+pub const BULLET_HIT_FLAT_FACTOR: f32 = 0.4;
+// Chance of a hit to kill (otherwise it injures)
+pub const BULLET_HIT_KILL_CHANCE: f32 = 0.2;
+// Soldiers don't fire when a friend is nearer than this distance (in millimeters) of the line
+// of fire
+pub const FRIENDLY_LINE_OF_FIRE_MILLIMETERS: i64 = 1500;
+// Chance of a hit piercing vehicle armor to knock it out
+pub const VEHICLE_PENETRATION_KNOCK_OUT_CHANCE: f32 = 0.6;
+// Chance of each crew member to die when its vehicle is knocked out (others bail out)
+pub const VEHICLE_KNOCK_OUT_CREW_KILL_CHANCE: f32 = 0.3;
+// Crew bails out at this distance (in meters) beyond its knocked out vehicle ammunition blast
+pub const VEHICLE_BAILOUT_MARGIN_METERS: i64 = 2;
+// Dispersion cone of snipers is multiplied by this factor
+pub const SNIPER_DISPERSION_FACTOR: f32 = 0.4;
+// Sniper relocates after firing this count of shots from the same position (or once spotted)
+pub const SNIPER_RELOCATION_SHOTS: u32 = 3;
+// Effective range of snipers weapon is multiplied by this factor
+pub const SNIPER_EFFECTIVE_RANGE_FACTOR: f32 = 2.0;
+// Added to scouts behavior visibility modifier (lower is harder to see)
+pub const SCOUT_VISIBILITY_MODIFIER: f32 = -0.3;
+// Opacity scouts see through in addition of other soldiers
+pub const SCOUT_SPOTTING_BONUS: f32 = 0.2;
+// Opacity of window tile in interior walls (replace wall opacity)
+pub const OPENING_WINDOW_OPACITY: f32 = 0.1;
+// Opacity of door tile in interior walls (replace wall opacity)
+pub const OPENING_DOOR_OPACITY: f32 = 0.0;
+// Chance of a destructible tile to be degraded when in explosion direct death rayon
+pub const TERRAIN_DESTRUCTION_CHANCE: f32 = 0.5;
+// Duration (in seconds) of the smoke left by an explosion
+pub const EXPLOSION_SMOKE_DURATION: u64 = 20;
+// Rayon (in meters) of the smoke left by an explosion
+pub const EXPLOSION_SMOKE_RAYON: i64 = 3;
+// Duration (in seconds) of the fire of a vehicle hit by a shell
+pub const VEHICLE_FIRE_DURATION: u64 = 90;
+// Rayon (in meters) of the fire of a vehicle hit by a shell
+pub const VEHICLE_FIRE_RAYON: i64 = 4;
+// Opacity added by each smoke tile crossed by a sight line
+pub const CLOUD_SMOKE_OPACITY: f32 = 0.15;
+// Opacity added by each fire tile crossed by a sight line
+pub const CLOUD_FIRE_OPACITY: f32 = 0.1;
+// Clouds state (size, position, density) is updated by steps of this duration (in seconds)
+pub const CLOUD_STEP_DURATION: u64 = 2;
+// Duration (in seconds) for a cloud to reach its full rayon
+pub const CLOUD_EXPANSION_DURATION: u64 = 6;
+// Rayon of a cloud when it appears, relatively to its full rayon
+pub const CLOUD_START_RAYON_RATIO: f32 = 0.3;
+// Part of cloud life after which it starts to dissipate
+pub const CLOUD_DISSIPATION_START: f32 = 0.7;
+// Wind moving smoke clouds (in meters per second)
+pub const CLOUD_DRIFT: (f32, f32) = (0.15, 0.05);
+// Smoke grenades carried by each soldier
+pub const SOLDIER_SMOKE_GRENADES: u8 = 1;
+// Maximum distance (in meters) a smoke grenade can be thrown
+pub const SMOKE_GRENADE_MAXIMUM_THROW: i64 = 30;
+// Maximum distance (in meters) between aimed point and smoke grenade landing point
+pub const SMOKE_GRENADE_DISPERSION: f32 = 4.0;
+// Duration (in seconds) of a smoke grenade cloud
+pub const SMOKE_GRENADE_DURATION: u64 = 60;
+// Rayon (in meters) of a smoke grenade cloud
+pub const SMOKE_GRENADE_RAYON: i64 = 8;
+// Duration (in seconds) of a smoke shell cloud
+pub const SMOKE_SHELL_DURATION: u64 = 90;
+// Rayon (in meters) of a smoke shell cloud
+pub const SMOKE_SHELL_RAYON: i64 = 15;
 // How many meters maximum soldier hide before shoot
 pub const HIDE_MAXIMUM_RAYON: i64 = 50;
 // How many frames after last proximity shoot needed before soldier go from lying to crouch when idle
@@ -143,12 +288,20 @@ pub struct ServerConfig {
     pub tile_type_opacity_middle_wood_logs: f32,
     pub tile_type_opacity_hedge: f32,
     pub tile_type_opacity_middle_rock: f32,
+    pub tile_type_opacity_crater: f32,
     pub visibility_by_last_frame_shoot: u64,
     pub visibility_by_last_frame_shoot_distance: usize,
     pub explosive_direct_death_rayon: HashMap<ExplosiveType, Distance>,
     pub explosive_regressive_death_rayon: HashMap<ExplosiveType, Distance>,
     pub explosive_regressive_injured_rayon: HashMap<ExplosiveType, Distance>,
     pub hide_maximum_rayon: Distance,
+    pub friendly_fire: bool,
+    pub visibility_maximum_distance: Option<Distance>,
+    pub ai_update_freq: u64,
+    pub ambush_update_freq: u64,
+    pub bounding_overwatch_update_freq: u64,
+    pub lockstep_turn_frames: u64,
+    pub lockstep_checksum_freq: u64,
 }
 
 impl Default for ServerConfig {
@@ -226,12 +379,22 @@ impl Default for ServerConfig {
             tile_type_opacity_middle_wood_logs: TILE_TYPE_OPACITY_MIDDLE_WOOD_LOGS,
             tile_type_opacity_hedge: TILE_TYPE_OPACITY_HEDGE,
             tile_type_opacity_middle_rock: TILE_TYPE_OPACITY_MIDDLE_ROCK,
+            tile_type_opacity_crater: TILE_TYPE_OPACITY_CRATER,
 
             explosive_direct_death_rayon,
             explosive_regressive_death_rayon,
             explosive_regressive_injured_rayon,
 
             hide_maximum_rayon: Distance::from_meters(HIDE_MAXIMUM_RAYON),
+            friendly_fire: true,
+            // Soldiers can't see each other beyond this distance (if any)
+            visibility_maximum_distance: None,
+            ai_update_freq: AI_UPDATE_FREQ,
+            ambush_update_freq: AMBUSH_UPDATE_FREQ,
+            bounding_overwatch_update_freq: BOUNDING_OVERWATCH_UPDATE_FREQ,
+            // Lockstep ones can't be changed during battle (see `Runner::lockstep_input`)
+            lockstep_turn_frames: LOCKSTEP_TURN_FRAMES,
+            lockstep_checksum_freq: LOCKSTEP_CHECKSUM_FREQ,
         }
     }
 }
@@ -277,6 +440,26 @@ impl ServerConfig {
         self.feeling_decreasing_freq
     }
 
+    pub fn ai_update_freq(&self) -> u64 {
+        self.ai_update_freq
+    }
+
+    pub fn ambush_update_freq(&self) -> u64 {
+        self.ambush_update_freq
+    }
+
+    pub fn bounding_overwatch_update_freq(&self) -> u64 {
+        self.bounding_overwatch_update_freq
+    }
+
+    pub fn lockstep_turn_frames(&self) -> u64 {
+        self.lockstep_turn_frames
+    }
+
+    pub fn lockstep_checksum_freq(&self) -> u64 {
+        self.lockstep_checksum_freq
+    }
+
     pub fn visibility_behavior_modifier(&self, behavior: &Behavior) -> f32 {
         match behavior {
             Behavior::Idle(Body::StandUp) => self.visibility_idle_standup_modifier,
@@ -291,8 +474,10 @@ impl ServerConfig {
             Behavior::RotateTo(_) => self.visibility_in_vehicle_modifier,
             Behavior::SuppressFire(_) => self.visibility_suppress_fire_modifier,
             Behavior::EngageSoldier(_) => self.visibility_engage_modifier,
+            Behavior::EngageVehicle(_) => self.visibility_engage_modifier,
             Behavior::Dead => self.visibility_dead_modifier,
             Behavior::Unconscious => self.visibility_unconscious_modifier,
+            Behavior::ThrowSmoke(_) => self.visibility_idle_standup_modifier,
         }
     }
 
@@ -310,6 +495,8 @@ impl ServerConfig {
             Behavior::Unconscious => None,
             Behavior::SuppressFire(_) => None,
             Behavior::EngageSoldier(_) => None,
+            Behavior::EngageVehicle(_) => None,
+            Behavior::ThrowSmoke(_) => None,
         }
     }
 
@@ -330,6 +517,7 @@ impl ServerConfig {
             TileType::MiddleWoodLogs => self.tile_type_opacity_middle_wood_logs,
             TileType::Hedge => self.tile_type_opacity_hedge,
             TileType::MiddleRock => self.tile_type_opacity_middle_rock,
+            TileType::Crater => self.tile_type_opacity_crater,
         }
     }
 
@@ -372,8 +560,11 @@ impl ServerConfig {
             ChangeConfigMessage::TileTypeOpacityMiddleWoodLogs(v) => self.tile_type_opacity_middle_wood_logs = *v,
             ChangeConfigMessage::TileTypeOpacityHedge(v) => self.tile_type_opacity_hedge = *v,
             ChangeConfigMessage::TileTypeOpacityMiddleRock(v) => self.tile_type_opacity_middle_rock = *v,
+            ChangeConfigMessage::TileTypeOpacityCrater(v) => self.tile_type_opacity_crater = *v,
             ChangeConfigMessage::VisibilityByLastFrameShot(v) => self.visibility_by_last_frame_shoot = *v,
             ChangeConfigMessage::VisibilityByLastFrameShotDistance(v) => self.visibility_by_last_frame_shoot_distance = *v,
+            ChangeConfigMessage::FriendlyFire(v) => self.friendly_fire = *v,
+            ChangeConfigMessage::VisibilityMaximumDistance(v) => self.visibility_maximum_distance = *v,
             ChangeConfigMessage::ExplosiveDirectDeathRayon(explosive, new_distance) => {
                 if let Some(distance) = self.explosive_direct_death_rayon.get_mut(explosive) {
                     distance.millimeters = new_distance.millimeters()
@@ -445,9 +636,12 @@ pub enum ChangeConfigMessage {
     TileTypeOpacityMiddleWoodLogs(f32),
     TileTypeOpacityHedge(f32),
     TileTypeOpacityMiddleRock(f32),
+    TileTypeOpacityCrater(f32),
     VisibilityByLastFrameShot(u64),
     VisibilityByLastFrameShotDistance(usize),
     ExplosiveDirectDeathRayon(ExplosiveType, Distance),
     ExplosiveRegressiveDeathRayon(ExplosiveType, Distance),
     ExplosiveRegressiveInjuredRayon(ExplosiveType, Distance),
+    FriendlyFire(bool),
+    VisibilityMaximumDistance(Option<Distance>),
 }
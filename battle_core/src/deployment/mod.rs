@@ -9,11 +9,12 @@ use crate::{
         vehicle::{Vehicle, VehicleType},
     },
     game::{
+        role::SoldierRole,
         weapon::{Magazine, Weapon},
         Side,
     },
     state::battle::BattleState,
-    types::{SoldierIndex, SoldiersOnBoard, SquadUuid, VehicleIndex, WorldPoint},
+    types::{Skill, SoldierIndex, SoldiersOnBoard, SquadUuid, VehicleIndex, WorldPoint},
 };
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -103,6 +104,10 @@ pub struct SoldierDeployment {
     squad_uuid: SquadUuid,
     main_weapon: Option<Weapon>,
     magazines: Vec<Magazine>,
+    #[serde(default)]
+    skill: Skill,
+    #[serde(default)]
+    role: SoldierRole,
 }
 
 impl SoldierDeployment {
@@ -121,9 +126,21 @@ impl SoldierDeployment {
             squad_uuid,
             main_weapon,
             magazines,
+            skill: Skill::default(),
+            role: SoldierRole::default(),
         }
     }
 
+    pub fn with_skill(mut self, skill: Skill) -> Self {
+        self.skill = skill;
+        self
+    }
+
+    pub fn with_role(mut self, role: SoldierRole) -> Self {
+        self.role = role;
+        self
+    }
+
     pub fn uuid(&self) -> SoldierIndex {
         self.uuid
     }
@@ -147,6 +164,14 @@ impl SoldierDeployment {
     pub fn magazines(&self) -> &[Magazine] {
         self.magazines.as_ref()
     }
+
+    pub fn skill(&self) -> &Skill {
+        &self.skill
+    }
+
+    pub fn role(&self) -> &SoldierRole {
+        &self.role
+    }
 }
 
 impl From<&Soldier> for SoldierDeployment {
@@ -158,6 +183,8 @@ impl From<&Soldier> for SoldierDeployment {
             squad_uuid: soldier.squad_uuid(),
             main_weapon: soldier.main_weapon().clone(),
             magazines: soldier.magazines().clone(),
+            skill: *soldier.skill(),
+            role: *soldier.role(),
         }
     }
 }
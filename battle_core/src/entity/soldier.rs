@@ -1,7 +1,9 @@
 use crate::{
     behavior::{feeling::Feeling, gesture::Gesture, Behavior, Body},
+    config::SOLDIER_SMOKE_GRENADES,
     deployment::SoldierDeployment,
     game::{
+        role::SoldierRole,
         weapon::{Magazine, Weapon},
         Side,
     },
@@ -27,6 +29,10 @@ pub struct Soldier {
     magazines: Vec<Magazine>,
     last_shoot_frame_i: u64,
     last_shot_frame_i: u64,
+    position_shots: u32,
+    smoke_grenades: u8,
+    skill: Skill,
+    role: SoldierRole,
 }
 
 impl Soldier {
@@ -54,9 +60,23 @@ impl Soldier {
             magazines,
             last_shot_frame_i: 0,
             last_shoot_frame_i: 0,
+            position_shots: 0,
+            smoke_grenades: SOLDIER_SMOKE_GRENADES,
+            skill: Skill::default(),
+            role: SoldierRole::default(),
         }
     }
 
+    pub fn with_skill(mut self, skill: Skill) -> Self {
+        self.skill = skill;
+        self
+    }
+
+    pub fn with_role(mut self, role: SoldierRole) -> Self {
+        self.role = role;
+        self
+    }
+
     pub fn from_soldier(soldier: &Soldier) -> Self {
         Self::new(
             soldier.uuid(),
@@ -66,6 +86,8 @@ impl Soldier {
             soldier.main_weapon().clone(),
             soldier.magazines().clone(),
         )
+        .with_skill(*soldier.skill())
+        .with_role(*soldier.role())
     }
 
     pub fn uuid(&self) -> SoldierIndex {
@@ -81,6 +103,9 @@ impl Soldier {
     }
 
     pub fn set_world_point(&mut self, point: WorldPoint) {
+        if point != self.world_point {
+            self.position_shots = 0;
+        }
         self.world_point = point
     }
 
@@ -136,6 +161,22 @@ impl Soldier {
         &self.magazines
     }
 
+    pub fn skill(&self) -> &Skill {
+        &self.skill
+    }
+
+    pub fn role(&self) -> &SoldierRole {
+        &self.role
+    }
+
+    pub fn smoke_grenades(&self) -> u8 {
+        self.smoke_grenades
+    }
+
+    pub fn throw_smoke_grenade(&mut self) {
+        self.smoke_grenades = self.smoke_grenades.saturating_sub(1)
+    }
+
     pub fn alive_mut(&mut self) -> &mut bool {
         &mut self.alive
     }
@@ -256,6 +297,12 @@ impl Soldier {
         if let Some(weapon) = self.weapon_mut(class) {
             weapon.shot();
         }
+        self.position_shots += 1;
+    }
+
+    /// Shots fired since soldier last moved
+    pub fn position_shots(&self) -> u32 {
+        self.position_shots
     }
 
     pub fn alive(&self) -> bool {
@@ -282,8 +329,10 @@ impl From<&SoldierDeployment> for Soldier {
             soldier.squad_uuid(),
             soldier.side(),
             soldier.main_weapon().cloned(),
-            soldier.magazines().clone().to_vec(),
+            soldier.magazines().to_vec(),
         )
+        .with_skill(*soldier.skill())
+        .with_role(*soldier.role())
     }
 }
 
@@ -3,8 +3,9 @@ use crate::{
     deployment::VehicleDeployment,
     graphics::vehicle::VehicleGraphicInfos,
     types::*,
-    utils::WorldShape,
+    utils::{angle, WorldShape},
 };
+use glam::Vec2;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -36,6 +37,22 @@ impl VehicleType {
             VehicleType::T26 => 5.0 / TARGET_FPS as f32,
         }
     }
+
+    /// Armor thickness (millimeters) of the facing
+    pub fn armor(&self, facing: &VehicleFacing) -> i64 {
+        match (self, facing) {
+            (VehicleType::T26, VehicleFacing::Front) => 15,
+            (VehicleType::T26, VehicleFacing::Side) => 15,
+            (VehicleType::T26, VehicleFacing::Rear) => 10,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub enum VehicleFacing {
+    Front,
+    Side,
+    Rear,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq, Hash)]
@@ -53,6 +70,7 @@ pub struct Vehicle {
     world_point: WorldPoint,
     chassis_orientation: Angle,
     main_turret_relative_orientation: Angle,
+    knocked_out: bool,
 }
 
 impl Vehicle {
@@ -63,6 +81,7 @@ impl Vehicle {
             world_point,
             chassis_orientation: Angle(0.),
             main_turret_relative_orientation: Angle(0.),
+            knocked_out: false,
         }
     }
 
@@ -73,6 +92,7 @@ impl Vehicle {
             world_point: vehicle.world_point(),
             chassis_orientation: *vehicle.chassis_orientation(),
             main_turret_relative_orientation: *vehicle.main_turret_relative_orientation(),
+            knocked_out: vehicle.knocked_out(),
         }
     }
 
@@ -121,6 +141,30 @@ impl Vehicle {
         self.main_turret_relative_orientation = orientation
     }
 
+    pub fn knocked_out(&self) -> bool {
+        self.knocked_out
+    }
+
+    pub fn set_knocked_out(&mut self, knocked_out: bool) {
+        self.knocked_out = knocked_out
+    }
+
+    /// Facing exposed to a shot coming from point
+    pub fn facing_from(&self, point: &WorldPoint) -> VehicleFacing {
+        let from = angle(point, &self.world_point);
+        let deviation = Vec2::from_angle(self.chassis_orientation.0)
+            .angle_between(Vec2::from_angle(from.0))
+            .abs();
+
+        if deviation <= std::f32::consts::FRAC_PI_4 {
+            VehicleFacing::Front
+        } else if deviation >= 3. * std::f32::consts::FRAC_PI_4 {
+            VehicleFacing::Rear
+        } else {
+            VehicleFacing::Side
+        }
+    }
+
     pub fn chassis_shape(&self) -> WorldShape {
         VehicleGraphicInfos::from_type(&self.type_)
             .chassis_physics()
@@ -139,3 +183,49 @@ impl From<&VehicleDeployment> for Vehicle {
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use super::*;
+    use crate::game::weapon::Ammunition;
+
+    #[rstest]
+    #[case(WorldPoint::new(0., -50.), VehicleFacing::Front)]
+    #[case(WorldPoint::new(10., -50.), VehicleFacing::Front)]
+    #[case(WorldPoint::new(50., 0.), VehicleFacing::Side)]
+    #[case(WorldPoint::new(-50., 10.), VehicleFacing::Side)]
+    #[case(WorldPoint::new(0., 50.), VehicleFacing::Rear)]
+    fn facing_from_shot_origin(#[case] from: WorldPoint, #[case] expected: VehicleFacing) {
+        // Given
+        let mut vehicle = Vehicle::new(VehicleIndex(0), VehicleType::T26, WorldPoint::new(0., 0.));
+        vehicle.set_chassis_orientation(angle(&WorldPoint::new(0., -10.), &vehicle.world_point()));
+
+        // When
+        let facing = vehicle.facing_from(&from);
+
+        // Then
+        assert_eq!(facing, expected);
+    }
+
+    #[rstest]
+    #[case(Ammunition::x762x54R, 50, VehicleFacing::Rear, false)]
+    #[case(Ammunition::x145x114, 100, VehicleFacing::Front, true)]
+    #[case(Ammunition::x145x114, 1500, VehicleFacing::Front, false)]
+    #[case(Ammunition::x145x114, 1200, VehicleFacing::Rear, true)]
+    #[case(Ammunition::GrenadeBundle, 10, VehicleFacing::Side, true)]
+    #[case(Ammunition::Vpgs41, 50, VehicleFacing::Front, true)]
+    fn ammunition_pierces_armor(
+        #[case] ammunition: Ammunition,
+        #[case] meters: i64,
+        #[case] facing: VehicleFacing,
+        #[case] expected: bool,
+    ) {
+        // When
+        let penetration = ammunition.armor_penetration(&Distance::from_meters(meters));
+
+        // Then
+        assert_eq!(penetration >= VehicleType::T26.armor(&facing), expected);
+    }
+}
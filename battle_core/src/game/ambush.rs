@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::AMBUSH_KILL_ZONE_MINIMUM_METERS,
+    physics::utils::distance_between_points,
+    types::{Distance, WorldPoint},
+};
+
+/// Ambushing squad holds its fire (and stays hidden) until it is triggered
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum AmbushTrigger {
+    /// Opponent enters the rectangle between these two corners
+    KillZone(WorldPoint, WorldPoint),
+    /// Opponent comes under this distance of one of squad members
+    Distance(Distance),
+}
+
+impl AmbushTrigger {
+    pub fn kill_zone(from: WorldPoint, to: WorldPoint) -> Self {
+        Self::KillZone(
+            WorldPoint::new(from.x.min(to.x), from.y.min(to.y)),
+            WorldPoint::new(from.x.max(to.x), from.y.max(to.y)),
+        )
+    }
+
+    /// Kill zone drawn between `from` and `to`, if large enough
+    pub fn drawn_kill_zone(from: WorldPoint, to: WorldPoint) -> Option<Self> {
+        let minimum = Distance::from_meters(AMBUSH_KILL_ZONE_MINIMUM_METERS);
+        let width = distance_between_points(&from, &WorldPoint::new(to.x, from.y));
+        let height = distance_between_points(&from, &WorldPoint::new(from.x, to.y));
+        if width >= minimum && height >= minimum {
+            Some(Self::kill_zone(from, to))
+        } else {
+            None
+        }
+    }
+
+    /// Opponent at `opponent` point triggers ambush of squad members at `members` points
+    pub fn triggered_by(&self, opponent: &WorldPoint, members: &[WorldPoint]) -> bool {
+        match self {
+            AmbushTrigger::KillZone(top_left, bottom_right) => {
+                opponent.x >= top_left.x
+                    && opponent.x <= bottom_right.x
+                    && opponent.y >= top_left.y
+                    && opponent.y <= bottom_right.y
+            }
+            AmbushTrigger::Distance(distance) => members
+                .iter()
+                .any(|member| &distance_between_points(member, opponent) <= distance),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(
+        AmbushTrigger::kill_zone(WorldPoint::new(100., 100.), WorldPoint::new(0., 0.)),
+        WorldPoint::new(50., 50.),
+        true
+    )]
+    #[case(
+        AmbushTrigger::kill_zone(WorldPoint::new(0., 0.), WorldPoint::new(100., 100.)),
+        WorldPoint::new(150., 50.),
+        false
+    )]
+    #[case(
+        AmbushTrigger::Distance(Distance::from_meters(30)),
+        WorldPoint::new(0., 290.),
+        true
+    )]
+    #[case(
+        AmbushTrigger::Distance(Distance::from_meters(30)),
+        WorldPoint::new(0., 310.),
+        false
+    )]
+    fn ambush_triggered_by(
+        #[case] trigger: AmbushTrigger,
+        #[case] opponent: WorldPoint,
+        #[case] expected: bool,
+    ) {
+        // Given
+        let members = vec![WorldPoint::new(0., 500.), WorldPoint::new(0., 200.)];
+
+        // When
+        let triggered = trigger.triggered_by(&opponent, &members);
+
+        // Then
+        assert_eq!(triggered, expected)
+    }
+
+    #[rstest]
+    #[case(WorldPoint::new(0., 0.), WorldPoint::new(200., 150.), true)]
+    #[case(WorldPoint::new(200., 150.), WorldPoint::new(0., 0.), true)]
+    #[case(WorldPoint::new(0., 0.), WorldPoint::new(200., 5.), false)]
+    #[case(WorldPoint::new(0., 0.), WorldPoint::new(3., 3.), false)]
+    fn drawn_kill_zone(#[case] from: WorldPoint, #[case] to: WorldPoint, #[case] expected: bool) {
+        // Given
+        // When
+        let kill_zone = AmbushTrigger::drawn_kill_zone(from, to);
+
+        // Then
+        assert_eq!(kill_zone.is_some(), expected)
+    }
+}
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    physics::utils::DISTANCE_TO_METERS_COEFFICIENT,
+    types::{Distance, SquadUuid, WorldPoint},
+};
+
+/// Fire and movement of two squads : the moving squad bounds to next cover while the covering
+/// one engages, then they swap, until destination is reached
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BoundingOverwatch {
+    moving: SquadUuid,
+    covering: SquadUuid,
+    destination: WorldPoint,
+}
+
+impl BoundingOverwatch {
+    /// Squads swap when moving one is not moving : `covering` squad makes the first bound
+    pub fn new(moving: SquadUuid, covering: SquadUuid, destination: WorldPoint) -> Self {
+        Self {
+            moving,
+            covering,
+            destination,
+        }
+    }
+
+    pub fn moving(&self) -> &SquadUuid {
+        &self.moving
+    }
+
+    pub fn covering(&self) -> &SquadUuid {
+        &self.covering
+    }
+
+    pub fn destination(&self) -> &WorldPoint {
+        &self.destination
+    }
+
+    pub fn contains(&self, squad_uuid: &SquadUuid) -> bool {
+        &self.moving == squad_uuid || &self.covering == squad_uuid
+    }
+
+    pub fn swap(&mut self) {
+        std::mem::swap(&mut self.moving, &mut self.covering);
+    }
+
+    /// Point at `bound` distance toward destination (or destination if it is closer)
+    pub fn next_bound_point(&self, from: &WorldPoint, bound: &Distance) -> WorldPoint {
+        let vector = self.destination.to_vec2() - from.to_vec2();
+        let length = (bound.millimeters() as f32 / 1000.) / DISTANCE_TO_METERS_COEFFICIENT;
+        if vector.length() <= length {
+            return self.destination;
+        }
+
+        from.apply(vector.normalize() * length)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(WorldPoint::new(0., 0.), WorldPoint::new(0., 100.))]
+    #[case(WorldPoint::new(0., 250.), WorldPoint::new(0., 300.))]
+    #[case(WorldPoint::new(0., 300.), WorldPoint::new(0., 300.))]
+    fn bounding_overwatch_next_bound_point(#[case] from: WorldPoint, #[case] expected: WorldPoint) {
+        // Given
+        let bounding_overwatch =
+            BoundingOverwatch::new(SquadUuid(0), SquadUuid(1), WorldPoint::new(0., 300.));
+
+        // When
+        let point = bounding_overwatch.next_bound_point(&from, &Distance::from_meters(30));
+
+        // Then
+        assert!(point.to_vec2().distance(expected.to_vec2()) < 0.01)
+    }
+}
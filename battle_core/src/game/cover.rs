@@ -1,20 +1,24 @@
 use crate::{
-    config::{ServerConfig, COVER_DISTANCE},
+    config::{ServerConfig, COVER_DISTANCE, FORMATION_COVER_DISTANCE},
     entity::soldier::Soldier,
-    map::{find_arbitrary_cover_grid_point, find_arbitrary_cover_grid_points},
+    map::{
+        find_arbitrary_cover_grid_point, find_arbitrary_cover_grid_points,
+        terrain::{TerrainTile, TileType},
+    },
     physics::visibility::Visibility,
     state::battle::BattleState,
     types::{GridPoint, SoldierIndex, SquadComposition, WorldPoint},
     utils::NewDebugPoint,
 };
 
-use super::squad::{squad_positions, Formation};
+use super::squad::squad_positions;
 
 pub struct CoverFinder<'a> {
     battle_state: &'a BattleState,
     config: &'a ServerConfig,
     point: Option<WorldPoint>,
     exclude_grid_points: Vec<GridPoint>,
+    keep_points: Vec<(SoldierIndex, WorldPoint)>,
 }
 
 impl<'a> CoverFinder<'a> {
@@ -24,6 +28,7 @@ impl<'a> CoverFinder<'a> {
             config,
             point: None,
             exclude_grid_points: vec![],
+            keep_points: vec![],
         }
     }
 
@@ -37,6 +42,12 @@ impl<'a> CoverFinder<'a> {
         self
     }
 
+    /// Previous formation points, kept while they still cover their formation position
+    pub fn keep_points(mut self, points: Vec<(SoldierIndex, WorldPoint)>) -> Self {
+        self.keep_points = points;
+        self
+    }
+
     pub fn find_arbitrary_cover_points(
         &self,
         squad: &SquadComposition,
@@ -49,8 +60,8 @@ impl<'a> CoverFinder<'a> {
         let mut already_used_cover_grid_points: Vec<GridPoint> = self.exclude_grid_points.clone();
         let mut debug_points = vec![];
 
-        for (member_id, formation_position) in
-            squad_positions(squad, Formation::Line, leader, self.point)
+        let formation = self.battle_state.squad_formation(&leader.squad_uuid());
+        for (member_id, formation_position) in squad_positions(squad, formation, leader, self.point)
         {
             let soldier = self.battle_state.soldier(member_id);
             let grid_point = self
@@ -89,6 +100,76 @@ impl<'a> CoverFinder<'a> {
         (moves, debug_points)
     }
 
+    /// Squad formation positions, each one moved to the most covered point close to it (if
+    /// any). Members keep their formation position in open ground. Kept points (see
+    /// `keep_points`) are preferred while close and covered enough, to not jump between
+    /// covers while leader moves.
+    pub fn find_formation_points(
+        &self,
+        squad: &SquadComposition,
+        leader: &Soldier,
+    ) -> Vec<(SoldierIndex, WorldPoint)> {
+        let map = self.battle_state.map();
+        let formation = self.battle_state.squad_formation(&leader.squad_uuid());
+        let mut used_grid_points: Vec<GridPoint> = self.exclude_grid_points.clone();
+        let mut positions: Vec<(SoldierIndex, WorldPoint)> =
+            squad_positions(squad, formation, leader, self.point)
+                .into_iter()
+                .collect();
+        // Hash map order is random, keep same result for same squad
+        positions.sort_by_key(|(soldier_index, _)| soldier_index.0);
+
+        let mut points = vec![];
+        for (member_id, formation_position) in positions {
+            let grid_point = map.grid_point_from_world_point(&formation_position);
+            let opacity = map
+                .terrain_tile(&grid_point)
+                .map(|tile| self.config.terrain_tile_opacity(&tile.type_))
+                .unwrap_or(0.);
+            let covering = |cover_grid_point: &GridPoint, tile: &TerrainTile| {
+                // Soldiers can't stand into walls or deep water
+                !matches!(tile.type_, TileType::BrickWall | TileType::DeepWater)
+                    && !map.closed_wall(cover_grid_point)
+                    && !used_grid_points.contains(cover_grid_point)
+                    && self.config.terrain_tile_opacity(&tile.type_) > opacity
+                    && (cover_grid_point.x - grid_point.x).abs() <= FORMATION_COVER_DISTANCE
+                    && (cover_grid_point.y - grid_point.y).abs() <= FORMATION_COVER_DISTANCE
+            };
+            let kept = self
+                .keep_points
+                .iter()
+                .find(|(soldier_index, _)| soldier_index == &member_id)
+                .map(|(_, point)| map.grid_point_from_world_point(point))
+                .filter(|kept_grid_point| {
+                    map.terrain_tile(kept_grid_point)
+                        .map(|tile| covering(kept_grid_point, tile))
+                        .unwrap_or(false)
+                });
+            let cover = kept.or_else(|| {
+                find_arbitrary_cover_grid_points(
+                    self.config,
+                    &grid_point,
+                    map,
+                    FORMATION_COVER_DISTANCE,
+                )
+                .into_iter()
+                .rev()
+                .find(|(cover_grid_point, tile)| covering(cover_grid_point, tile))
+                .map(|(cover_grid_point, _)| cover_grid_point)
+            });
+
+            match cover {
+                Some(cover_grid_point) => {
+                    used_grid_points.push(cover_grid_point);
+                    points.push((member_id, map.world_point_from_grid_point(cover_grid_point)));
+                }
+                None => points.push((member_id, formation_position)),
+            }
+        }
+
+        points
+    }
+
     /// Search better covered position than current soldier point according to given point.
     /// Used to search a better place to hide from some shooters or find
     /// # Arguments
@@ -125,6 +206,7 @@ impl<'a> CoverFinder<'a> {
                     &possible_cover_point,
                     from_point,
                     self.battle_state.map(),
+                    self.battle_state.clouds(),
                 )
                 // FIXME BS NOW : if keep_visible is false and not hided point found, take most opaque
                 .visible
@@ -138,3 +220,122 @@ impl<'a> CoverFinder<'a> {
         None
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, path::PathBuf};
+
+    use rstest::*;
+
+    use crate::{
+        game::{flag::FlagsOwnership, Side},
+        map::{decor::Decor, Map},
+        state::battle::phase::Phase,
+        types::{SquadComposition, SquadUuid},
+    };
+
+    use super::*;
+
+    /// 20x20 tiles (10 pixels each) map with underbrush on even columns
+    fn battle_state(leader_point: WorldPoint) -> BattleState {
+        let terrain_tiles = (0..20 * 20)
+            .map(|i| {
+                let (x, y) = (i % 20, i / 20);
+                let id = if x % 2 == 0 {
+                    "Underbrush"
+                } else {
+                    "ShortGrass"
+                };
+                TerrainTile::from_str_id(id, 10, 10, 0.1, 0.1, x, y, 0, 0).unwrap()
+            })
+            .collect();
+        let map = Map::new(
+            "TestMap".to_string(),
+            PathBuf::from("."),
+            PathBuf::from("."),
+            PathBuf::from("."),
+            vec![],
+            vec![],
+            20,
+            20,
+            terrain_tiles,
+            10,
+            10,
+            Decor::new(vec![], vec![]),
+            vec![],
+        );
+        let soldiers = vec![
+            Soldier::new(
+                SoldierIndex(0),
+                leader_point,
+                SquadUuid(0),
+                Side::A,
+                None,
+                vec![],
+            ),
+            Soldier::new(
+                SoldierIndex(1),
+                leader_point,
+                SquadUuid(0),
+                Side::A,
+                None,
+                vec![],
+            ),
+        ];
+        let mut battle_state = BattleState::new(
+            0,
+            map,
+            soldiers,
+            vec![],
+            HashMap::new(),
+            Phase::Battle,
+            FlagsOwnership::empty(),
+        );
+        battle_state.set_squads(HashMap::from([(
+            SquadUuid(0),
+            SquadComposition::new(SoldierIndex(0), vec![SoldierIndex(0), SoldierIndex(1)]),
+        )]));
+        battle_state
+    }
+
+    fn formation_point(
+        battle_state: &BattleState,
+        keep_points: Vec<(SoldierIndex, WorldPoint)>,
+    ) -> WorldPoint {
+        let config = ServerConfig::default();
+        let squad = &battle_state.squads()[&SquadUuid(0)];
+        let leader = battle_state.soldier(SoldierIndex(0));
+        CoverFinder::new(battle_state, &config)
+            .keep_points(keep_points)
+            .find_formation_points(squad, leader)[0]
+            .1
+    }
+
+    #[rstest]
+    fn formation_cover_is_kept_while_leader_moves() {
+        // Given a leader moving by one tile
+        let before = formation_point(&battle_state(WorldPoint::new(105., 55.)), vec![]);
+        let moved = battle_state(WorldPoint::new(105., 65.));
+
+        // When
+        let fresh = formation_point(&moved, vec![]);
+        let kept = formation_point(&moved, vec![(SoldierIndex(1), before)]);
+
+        // Then
+        assert_ne!(fresh, before);
+        assert_eq!(kept, before);
+    }
+
+    #[rstest]
+    fn formation_cover_is_left_when_too_far() {
+        // Given a leader moving by five tiles
+        let before = formation_point(&battle_state(WorldPoint::new(105., 55.)), vec![]);
+        let moved = battle_state(WorldPoint::new(155., 55.));
+
+        // When
+        let kept = formation_point(&moved, vec![(SoldierIndex(1), before)]);
+
+        // Then
+        assert_ne!(kept, before);
+    }
+}
@@ -0,0 +1,222 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::map::terrain::TileType;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum Weather {
+    #[default]
+    Clear,
+    Fog,
+    Rain,
+    Snow,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum Daylight {
+    #[default]
+    Day,
+    Dusk,
+    Night,
+}
+
+/// Battle environmental conditions : they reduce sight, slow soldiers and cover sounds
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct Environment {
+    weather: Weather,
+    daylight: Daylight,
+}
+
+impl Environment {
+    pub fn new(weather: Weather, daylight: Daylight) -> Self {
+        Self { weather, daylight }
+    }
+
+    pub fn weather(&self) -> &Weather {
+        &self.weather
+    }
+
+    pub fn daylight(&self) -> &Daylight {
+        &self.daylight
+    }
+
+    /// Factor to apply to `visible_starts_at` (lower is less visible)
+    pub fn visible_starts_at_factor(&self) -> f32 {
+        let weather = match self.weather {
+            Weather::Clear => 1.0,
+            Weather::Fog => 0.6,
+            Weather::Rain => 0.9,
+            Weather::Snow => 0.8,
+        };
+        let daylight = match self.daylight {
+            Daylight::Day => 1.0,
+            Daylight::Dusk => 0.8,
+            Daylight::Night => 0.5,
+        };
+        weather * daylight
+    }
+
+    /// Opacity added by each tile crossed by sight
+    pub fn opacity(&self) -> f32 {
+        let weather = match self.weather {
+            Weather::Clear => 0.0,
+            Weather::Fog => 0.005,
+            Weather::Rain => 0.001,
+            Weather::Snow => 0.002,
+        };
+        let daylight = match self.daylight {
+            Daylight::Day => 0.0,
+            Daylight::Dusk => 0.001,
+            Daylight::Night => 0.003,
+        };
+        weather + daylight
+    }
+
+    /// Factor to apply to pedestrian cost of tile (mud and snow slow movement)
+    pub fn ground_factor(&self, tile_type: &TileType) -> f32 {
+        match (self.weather, tile_type) {
+            (Weather::Rain, TileType::Mud) => 1.5,
+            (Weather::Rain, TileType::Dirt) => 1.2,
+            (Weather::Snow, TileType::Mud) => 1.6,
+            (Weather::Snow, TileType::Water | TileType::DeepWater) => 1.0,
+            (Weather::Snow, _) => 1.3,
+            _ => 1.0,
+        }
+    }
+
+    /// Factor to apply to battle sounds volume
+    pub fn audibility(&self) -> f32 {
+        match self.weather {
+            Weather::Clear => 1.0,
+            Weather::Fog => 0.9,
+            Weather::Rain => 0.5,
+            Weather::Snow => 0.7,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum EnvironmentError {
+    UnknownCondition(String),
+    InvalidStart(String),
+}
+
+impl Display for EnvironmentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnvironmentError::UnknownCondition(condition) => f.write_str(&format!(
+                "Unknown condition '{}' (expected clear, fog, rain, snow, day, dusk or night)",
+                condition
+            )),
+            EnvironmentError::InvalidStart(start) => {
+                f.write_str(&format!("Invalid start seconds '{}'", start))
+            }
+        }
+    }
+}
+
+/// Comma separated conditions, like `fog,night`. Missing ones are clear and day.
+impl FromStr for Environment {
+    type Err = EnvironmentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut environment = Environment::default();
+        for condition in s.split(',').map(|c| c.trim()).filter(|c| !c.is_empty()) {
+            match condition.to_lowercase().as_str() {
+                "clear" => environment.weather = Weather::Clear,
+                "fog" => environment.weather = Weather::Fog,
+                "rain" => environment.weather = Weather::Rain,
+                "snow" => environment.weather = Weather::Snow,
+                "day" => environment.daylight = Daylight::Day,
+                "dusk" => environment.daylight = Daylight::Dusk,
+                "night" => environment.daylight = Daylight::Night,
+                _ => return Err(EnvironmentError::UnknownCondition(condition.to_string())),
+            }
+        }
+        Ok(environment)
+    }
+}
+
+/// Environment which applies from `start` seconds after battle state creation (placement
+/// phase included)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EnvironmentChange {
+    start: u64,
+    environment: Environment,
+}
+
+impl EnvironmentChange {
+    pub fn new(start: u64, environment: Environment) -> Self {
+        Self { start, environment }
+    }
+
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+}
+
+/// Conditions with optional start seconds, like `fog,dusk` or `300:rain,night`
+impl FromStr for EnvironmentChange {
+    type Err = EnvironmentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, environment) = match s.split_once(':') {
+            Some((start, environment)) => (
+                start
+                    .trim()
+                    .parse()
+                    .map_err(|_| EnvironmentError::InvalidStart(start.to_string()))?,
+                environment,
+            ),
+            None => (0, s),
+        };
+        Ok(Self::new(start, environment.parse()?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case("fog", 0, Weather::Fog, Daylight::Day)]
+    #[case("rain,night", 0, Weather::Rain, Daylight::Night)]
+    #[case("300:Snow, dusk", 300, Weather::Snow, Daylight::Dusk)]
+    #[case("60:", 60, Weather::Clear, Daylight::Day)]
+    fn environment_change_from_str(
+        #[case] value: &str,
+        #[case] expected_start: u64,
+        #[case] expected_weather: Weather,
+        #[case] expected_daylight: Daylight,
+    ) {
+        // Given
+        let expected = EnvironmentChange::new(
+            expected_start,
+            Environment::new(expected_weather, expected_daylight),
+        );
+
+        // When
+        let change: EnvironmentChange = value.parse().unwrap();
+
+        // Then
+        assert_eq!(change, expected)
+    }
+
+    #[rstest]
+    #[case("hail")]
+    #[case("soon:fog")]
+    fn environment_change_from_invalid_str(#[case] value: &str) {
+        // When
+        let change = value.parse::<EnvironmentChange>();
+
+        // Then
+        assert!(change.is_err())
+    }
+}
@@ -12,12 +12,19 @@ use crate::{
 #[derive(Debug, Clone, Serialize, Deserialize, EnumIter, PartialEq, Eq, Hash, Display)]
 pub enum ExplosiveType {
     FA19241927,
+    Vpgs41,
+    GrenadeBundle,
+    /// Knocked out vehicle ammunition cooking off
+    AmmunitionRack,
 }
 
 impl ExplosiveType {
     pub fn sounds(&self) -> Vec<Sound> {
         let pick_from = match self {
-            ExplosiveType::FA19241927 => vec![Sound::CannonFire1],
+            ExplosiveType::FA19241927
+            | ExplosiveType::Vpgs41
+            | ExplosiveType::GrenadeBundle
+            | ExplosiveType::AmmunitionRack => vec![Sound::CannonFire1],
         };
         let sound = *pick_from
             .choose(&mut rand::thread_rng())
@@ -28,26 +35,46 @@ impl ExplosiveType {
 
     pub fn sprite(&self) -> Box<dyn Sprite> {
         let animation_type = match self {
-            ExplosiveType::FA19241927 => ExplosionAnimationType::Explosion1,
+            ExplosiveType::FA19241927
+            | ExplosiveType::Vpgs41
+            | ExplosiveType::GrenadeBundle
+            | ExplosiveType::AmmunitionRack => ExplosionAnimationType::Explosion1,
         };
         Box::new(animation_type)
     }
 
+    /// Charge thrown or launched by infantry against vehicles
+    pub fn anti_tank_charge(&self) -> bool {
+        match self {
+            ExplosiveType::FA19241927 | ExplosiveType::AmmunitionRack => false,
+            ExplosiveType::Vpgs41 | ExplosiveType::GrenadeBundle => true,
+        }
+    }
+
     pub fn direct_death_rayon(&self) -> Distance {
         match self {
             ExplosiveType::FA19241927 => Distance::from_meters(1),
+            ExplosiveType::Vpgs41 => Distance::from_meters(1),
+            ExplosiveType::GrenadeBundle => Distance::from_meters(1),
+            ExplosiveType::AmmunitionRack => Distance::from_meters(2),
         }
     }
 
     pub fn regressive_death_rayon(&self) -> Distance {
         match self {
             ExplosiveType::FA19241927 => Distance::from_meters(3),
+            ExplosiveType::Vpgs41 => Distance::from_meters(2),
+            ExplosiveType::GrenadeBundle => Distance::from_meters(3),
+            ExplosiveType::AmmunitionRack => Distance::from_meters(5),
         }
     }
 
     pub fn regressive_injured_rayon(&self) -> Distance {
         match self {
             ExplosiveType::FA19241927 => Distance::from_meters(6),
+            ExplosiveType::Vpgs41 => Distance::from_meters(4),
+            ExplosiveType::GrenadeBundle => Distance::from_meters(8),
+            ExplosiveType::AmmunitionRack => Distance::from_meters(10),
         }
     }
 }
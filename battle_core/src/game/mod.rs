@@ -1,12 +1,19 @@
 use serde::{Deserialize, Serialize};
 
+pub mod ambush;
+pub mod bounding;
 pub mod control;
 pub mod cover;
+pub mod environment;
 pub mod explosive;
 pub mod flag;
 pub mod health;
 pub mod posture;
+pub mod role;
+pub mod sector;
 pub mod squad;
+pub mod stats;
+pub mod target;
 pub mod weapon;
 
 #[derive(Debug, Copy, Serialize, Deserialize, Clone, PartialEq)]
@@ -1,4 +1,15 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Posture {
     StandUp,
     Flat,
 }
+
+impl Posture {
+    /// Height (in meters) of soldier body above ground
+    pub fn height(&self) -> f32 {
+        match self {
+            Posture::StandUp => 1.5,
+            Posture::Flat => 0.3,
+        }
+    }
+}
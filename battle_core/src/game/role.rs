@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::{
+    SCOUT_SPOTTING_BONUS, SCOUT_VISIBILITY_MODIFIER, SNIPER_DISPERSION_FACTOR,
+    SNIPER_EFFECTIVE_RANGE_FACTOR,
+};
+
+/// Speciality of a soldier inside its squad
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum SoldierRole {
+    #[default]
+    Rifleman,
+    /// Equipped to fight vehicles
+    AntiTank,
+    /// Long range accurate fire, relocates after firing
+    Sniper,
+    /// Better spotting and harder to see
+    Scout,
+}
+
+impl SoldierRole {
+    pub fn display(&self) -> &str {
+        match self {
+            SoldierRole::Rifleman => "rifleman",
+            SoldierRole::AntiTank => "anti-tank",
+            SoldierRole::Sniper => "sniper",
+            SoldierRole::Scout => "scout",
+        }
+    }
+
+    /// Added to the behavior visibility modifier of the soldier (lower is harder to see)
+    pub fn visibility_modifier(&self) -> f32 {
+        match self {
+            SoldierRole::Scout => SCOUT_VISIBILITY_MODIFIER,
+            SoldierRole::Rifleman | SoldierRole::AntiTank | SoldierRole::Sniper => 0.,
+        }
+    }
+
+    /// Opacity the soldier sees through in addition of others
+    pub fn spotting_bonus(&self) -> f32 {
+        match self {
+            SoldierRole::Scout => SCOUT_SPOTTING_BONUS,
+            SoldierRole::Rifleman | SoldierRole::AntiTank | SoldierRole::Sniper => 0.,
+        }
+    }
+
+    /// Factor applied on shot dispersion cone
+    pub fn dispersion_factor(&self) -> f32 {
+        match self {
+            SoldierRole::Sniper => SNIPER_DISPERSION_FACTOR,
+            SoldierRole::Rifleman | SoldierRole::AntiTank | SoldierRole::Scout => 1.,
+        }
+    }
+
+    /// Factor applied on weapon effective range
+    pub fn effective_range_factor(&self) -> f32 {
+        match self {
+            SoldierRole::Sniper => SNIPER_EFFECTIVE_RANGE_FACTOR,
+            SoldierRole::Rifleman | SoldierRole::AntiTank | SoldierRole::Scout => 1.,
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::f32::consts::{PI, TAU};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::FIRE_SECTOR_MINIMUM_WIDTH,
+    physics::utils::distance_between_points,
+    types::{Angle, Distance, WorldPoint},
+};
+
+use super::Side;
+
+/// Angle difference in `-PI..PI`
+fn angle_difference(a: &Angle, b: &Angle) -> f32 {
+    (a.0 - b.0 + PI).rem_euclid(TAU) - PI
+}
+
+/// Squad engages only targets in this arc and under this range
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct FireSector {
+    angle: Angle,
+    width: f32,
+    range: Distance,
+}
+
+impl FireSector {
+    pub fn new(angle: Angle, width: f32, range: Distance) -> Self {
+        Self {
+            angle,
+            width: width.max(FIRE_SECTOR_MINIMUM_WIDTH),
+            range,
+        }
+    }
+
+    /// Sector from `from` point, between `start` and `end` directions and up to the farthest
+    pub fn from_points(from: &WorldPoint, start: &WorldPoint, end: &WorldPoint) -> Self {
+        let start_angle = Angle::from_points(&start.to_vec2(), &from.to_vec2());
+        let end_angle = Angle::from_points(&end.to_vec2(), &from.to_vec2());
+        let difference = angle_difference(&end_angle, &start_angle);
+        let range = Distance::from_millimeters(
+            distance_between_points(from, start)
+                .millimeters()
+                .max(distance_between_points(from, end).millimeters()),
+        );
+
+        Self::new(
+            Angle(start_angle.0 + difference / 2.),
+            difference.abs(),
+            range,
+        )
+    }
+
+    pub fn angle(&self) -> &Angle {
+        &self.angle
+    }
+
+    /// Arc width, in radians
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn range(&self) -> &Distance {
+        &self.range
+    }
+
+    pub fn contains(&self, from: &WorldPoint, point: &WorldPoint) -> bool {
+        let angle = Angle::from_points(&point.to_vec2(), &from.to_vec2());
+        distance_between_points(from, point) <= self.range
+            && angle_difference(&angle, &self.angle).abs() <= self.width / 2.
+    }
+}
+
+/// Side soldiers never target opponents in this area
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NoFireArea {
+    side: Side,
+    top_left: WorldPoint,
+    bottom_right: WorldPoint,
+}
+
+impl NoFireArea {
+    pub fn new(side: Side, from: WorldPoint, to: WorldPoint) -> Self {
+        Self {
+            side,
+            top_left: WorldPoint::new(from.x.min(to.x), from.y.min(to.y)),
+            bottom_right: WorldPoint::new(from.x.max(to.x), from.y.max(to.y)),
+        }
+    }
+
+    pub fn side(&self) -> &Side {
+        &self.side
+    }
+
+    pub fn top_left(&self) -> &WorldPoint {
+        &self.top_left
+    }
+
+    pub fn bottom_right(&self) -> &WorldPoint {
+        &self.bottom_right
+    }
+
+    pub fn contains(&self, point: &WorldPoint) -> bool {
+        point.x >= self.top_left.x
+            && point.x <= self.bottom_right.x
+            && point.y >= self.top_left.y
+            && point.y <= self.bottom_right.y
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    // North
+    #[case(WorldPoint::new(0., -100.), true)]
+    // North, too far
+    #[case(WorldPoint::new(0., -400.), false)]
+    // North-est, in arc
+    #[case(WorldPoint::new(50., -100.), true)]
+    // Est
+    #[case(WorldPoint::new(100., 0.), false)]
+    // South
+    #[case(WorldPoint::new(0., 100.), false)]
+    fn fire_sector_contains(#[case] point: WorldPoint, #[case] expected: bool) {
+        // Given : north sector from north-west to north-est, up to 141 pixels
+        let from = WorldPoint::new(0., 0.);
+        let sector = FireSector::from_points(
+            &from,
+            &WorldPoint::new(-100., -100.),
+            &WorldPoint::new(100., -100.),
+        );
+
+        // When
+        let contains = sector.contains(&from, &point);
+
+        // Then
+        assert_eq!(contains, expected)
+    }
+}
@@ -1,9 +1,12 @@
 use std::collections::HashMap;
 
+use glam::Vec2;
 use oc_core::{graphics::ammunition::AmmunitionReserveStatus, health::Health, morale::Morale};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     behavior::{feeling::UNDER_FIRE_MAX, gesture::Gesture, Behavior},
+    config::{FORMATION_DISPERSED_SPACING, FORMATION_SPACING},
     entity::soldier::Soldier,
     state::battle::BattleState,
     types::{SoldierIndex, SquadComposition, SquadUuid, WorldPoint},
@@ -12,12 +15,72 @@ use crate::{
 
 use super::{
     health::SoldierHealthBuilder,
+    role::SoldierRole,
+    target::TargetPolicy,
     weapon::{Magazine, Weapon},
     Side,
 };
 
+/// Members positions around squad leader, relative to the leader looking direction
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub enum Formation {
+    #[default]
     Line,
+    Column,
+    Wedge,
+    EchelonLeft,
+    EchelonRight,
+    File,
+    Dispersed,
+}
+
+impl Formation {
+    /// Formation following this one (used to toggle formations)
+    pub fn next(&self) -> Self {
+        match self {
+            Formation::Line => Formation::Column,
+            Formation::Column => Formation::Wedge,
+            Formation::Wedge => Formation::EchelonLeft,
+            Formation::EchelonLeft => Formation::EchelonRight,
+            Formation::EchelonRight => Formation::File,
+            Formation::File => Formation::Dispersed,
+            Formation::Dispersed => Formation::Line,
+        }
+    }
+
+    pub fn display(&self) -> &str {
+        match self {
+            Formation::Line => "line",
+            Formation::Column => "column",
+            Formation::Wedge => "wedge",
+            Formation::EchelonLeft => "echelon left",
+            Formation::EchelonRight => "echelon right",
+            Formation::File => "file",
+            Formation::Dispersed => "dispersed",
+        }
+    }
+
+    /// Offset of member (by rank, leader excluded) when leader looks to north : negative x
+    /// is on leader left, positive y is behind leader
+    pub fn offset(&self, rank: usize) -> (f32, f32) {
+        // Members alternate on each side of the leader
+        let side = if rank % 2 == 1 { 1.0 } else { -1.0 };
+        let row = (rank / 2 + 1) as f32;
+        let file = (rank + 1) as f32;
+
+        match self {
+            Formation::Line => (side * row * FORMATION_SPACING, 0.),
+            Formation::Column => (side * FORMATION_SPACING / 2., row * FORMATION_SPACING),
+            Formation::Wedge => (side * row * FORMATION_SPACING, row * FORMATION_SPACING),
+            Formation::EchelonLeft => (-file * FORMATION_SPACING, file * FORMATION_SPACING),
+            Formation::EchelonRight => (file * FORMATION_SPACING, file * FORMATION_SPACING),
+            Formation::File => (0., file * FORMATION_SPACING),
+            Formation::Dispersed => (
+                side * row * FORMATION_DISPERSED_SPACING,
+                (rank % 3) as f32 * FORMATION_DISPERSED_SPACING / 2.,
+            ),
+        }
+    }
 }
 
 pub fn squad_positions(
@@ -30,37 +93,12 @@ pub fn squad_positions(
     let ref_point = point.unwrap_or(leader.world_point());
     let ref_angle = leader.get_looking_direction();
 
-    match formation {
-        Formation::Line => {
-            let mut x_offset: f32 = 0.0;
-            let mut y_offset: f32 = 0.0;
-            let mut counter: u8 = 0;
-
-            for (i, soldier_index) in squad.members().iter().enumerate() {
-                // Don't return position for leader
-                if *soldier_index == squad.leader() {
-                    continue;
-                }
-
-                if counter % 2 == 0 {
-                    x_offset += 10.0;
-                    y_offset += 0.0;
-                }
-                counter += 1;
-
-                let (x_offset_, y_offset_) = if i % 2 == 0 {
-                    (x_offset, y_offset)
-                } else {
-                    (-x_offset, -y_offset)
-                };
-
-                let member_scene_point =
-                    WorldPoint::new(ref_point.x + x_offset_, ref_point.y + y_offset_);
-                let member_scene_point =
-                    apply_angle_on_point(&member_scene_point, &ref_point, &ref_angle);
-                positions.insert(*soldier_index, member_scene_point);
-            }
-        }
+    // Don't return position for leader
+    for (rank, soldier_index) in squad.subordinates().into_iter().enumerate() {
+        let (x_offset, y_offset) = formation.offset(rank);
+        let member_scene_point = WorldPoint::new(ref_point.x + x_offset, ref_point.y + y_offset);
+        let member_scene_point = apply_angle_on_point(&member_scene_point, &ref_point, &ref_angle);
+        positions.insert(*soldier_index, member_scene_point);
     }
 
     positions
@@ -91,6 +129,8 @@ impl SquadStatusesResume {
 pub struct SquadStatusResume {
     squad_id: SquadUuid,
     health: SquadHealth,
+    formation: Formation,
+    target_policy: Option<TargetPolicy>,
     members: Vec<SquadMemberStatus>,
 }
 
@@ -100,6 +140,8 @@ impl SquadStatusResume {
         Self {
             squad_id: *squad_id,
             health: SquadHealth::from_squad(battle_state, squad),
+            formation: battle_state.squad_formation(squad_id),
+            target_policy: battle_state.squad_target_policy(squad_id).copied(),
             members: squad
                 .members()
                 .iter()
@@ -118,6 +160,15 @@ impl SquadStatusResume {
         &self.health
     }
 
+    pub fn formation(&self) -> &Formation {
+        &self.formation
+    }
+
+    /// Policy given to the squad (members use their role default if none)
+    pub fn target_policy(&self) -> Option<&TargetPolicy> {
+        self.target_policy.as_ref()
+    }
+
     pub fn members(&self) -> &[SquadMemberStatus] {
         self.members.as_ref()
     }
@@ -161,6 +212,7 @@ pub struct SquadMemberStatus {
     under_fire_coefficient: f32,
     current: CurrentAction,
     leader: bool,
+    role: SoldierRole,
 }
 
 // FIXME : this func is here because AmmunitionReserveStatus, Soldier, etc will have to move
@@ -200,6 +252,7 @@ impl SquadMemberStatus {
             under_fire_coefficient: (*soldier.under_fire().value() as f32 / UNDER_FIRE_MAX as f32),
             current: CurrentAction::from_soldier(battle_state, squad, soldier),
             leader: battle_state.squad(soldier.squad_uuid()).leader() == soldier.uuid(),
+            role: *soldier.role(),
         }
     }
 
@@ -231,6 +284,10 @@ impl SquadMemberStatus {
         self.leader
     }
 
+    pub fn role(&self) -> &SoldierRole {
+        &self.role
+    }
+
     pub fn soldier_index(&self) -> SoldierIndex {
         self.soldier_index
     }
@@ -251,6 +308,7 @@ pub enum CurrentAction {
     Hiding,
     Driving,
     Rotating,
+    ThrowingSmoke,
     // ...
 }
 
@@ -274,12 +332,13 @@ impl CurrentAction {
                 Gesture::Aiming(_, _) => Self::Aiming,
                 Gesture::Firing(_, _) => Self::SuppressFiring,
             },
-            Behavior::EngageSoldier(_) => match soldier.gesture() {
+            Behavior::EngageSoldier(_) | Behavior::EngageVehicle(_) => match soldier.gesture() {
                 Gesture::Idle => Self::Idle,
                 Gesture::Reloading(_, _) => Self::Reloading,
                 Gesture::Aiming(_, _) => Self::Aiming,
                 Gesture::Firing(_, _) => Self::TargetFiring,
             },
+            Behavior::ThrowSmoke(_) => Self::ThrowingSmoke,
             Behavior::Idle(_) | Behavior::Dead | Behavior::Unconscious => Self::Idle,
         }
     }
@@ -298,6 +357,114 @@ impl CurrentAction {
             CurrentAction::Hiding => "hiding",
             CurrentAction::Driving => "driving",
             CurrentAction::Rotating => "rotating",
+            CurrentAction::ThrowingSmoke => "throwing smoke",
+        }
+    }
+}
+
+/// Spread squads along the line from `start` to `end`. Squads are sorted by their leader
+/// position along the line to prevent their paths crossing.
+pub fn frontage_points(
+    leaders: &[(SquadUuid, WorldPoint)],
+    start: WorldPoint,
+    end: WorldPoint,
+) -> Vec<(SquadUuid, WorldPoint)> {
+    let line = end.to_vec2() - start.to_vec2();
+    let projection = |point: &WorldPoint| (point.to_vec2() - start.to_vec2()).dot(line);
+    let mut leaders = leaders.to_vec();
+    leaders.sort_by(|(_, a), (_, b)| projection(a).total_cmp(&projection(b)));
+
+    let count = leaders.len() as f32;
+    leaders
+        .into_iter()
+        .enumerate()
+        .map(|(i, (squad_id, _))| (squad_id, start.apply(line * (i as f32 + 0.5) / count)))
+        .collect()
+}
+
+/// Offset of `leader` from the center of `leaders` (squads ordered together)
+pub fn group_offset(leader: &WorldPoint, leaders: &[WorldPoint]) -> Vec2 {
+    if leaders.len() < 2 {
+        return Vec2::ZERO;
+    }
+
+    let center = leaders.iter().map(|point| point.to_vec2()).sum::<Vec2>() / leaders.len() as f32;
+    leader.to_vec2() - center
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(Formation::Line)]
+    #[case(Formation::Column)]
+    #[case(Formation::Wedge)]
+    #[case(Formation::EchelonLeft)]
+    #[case(Formation::EchelonRight)]
+    #[case(Formation::File)]
+    #[case(Formation::Dispersed)]
+    fn formation_members_follow_leader(#[case] formation: Formation) {
+        // Given
+        let ranks = 0..9;
+
+        // When
+        let offsets: Vec<(f32, f32)> = ranks.map(|rank| formation.offset(rank)).collect();
+
+        // Then
+        for (i, offset) in offsets.iter().enumerate() {
+            assert!(offset.1 >= 0., "member {} is ahead of leader", i);
+            assert_ne!(offset, &(0., 0.), "member {} is on leader", i);
+            assert!(!offsets[..i].contains(offset), "member {} is on other", i);
         }
     }
+
+    #[rstest]
+    fn frontage_points_are_evenly_spaced_in_leaders_order() {
+        // Given leaders of squads 0 and 2 swapped along the line
+        let leaders = vec![
+            (SquadUuid(0), WorldPoint::new(90., 50.)),
+            (SquadUuid(1), WorldPoint::new(50., 40.)),
+            (SquadUuid(2), WorldPoint::new(10., 60.)),
+        ];
+
+        // When
+        let points = frontage_points(&leaders, WorldPoint::new(0., 0.), WorldPoint::new(60., 0.));
+
+        // Then
+        assert_eq!(
+            points,
+            vec![
+                (SquadUuid(2), WorldPoint::new(10., 0.)),
+                (SquadUuid(1), WorldPoint::new(30., 0.)),
+                (SquadUuid(0), WorldPoint::new(50., 0.)),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(WorldPoint::new(0., 0.), vec![WorldPoint::new(0., 0.)], Vec2::ZERO)]
+    #[case(
+        WorldPoint::new(0., 0.),
+        vec![WorldPoint::new(0., 0.), WorldPoint::new(20., 10.)],
+        Vec2::new(-10., -5.)
+    )]
+    #[case(
+        WorldPoint::new(20., 10.),
+        vec![WorldPoint::new(0., 0.), WorldPoint::new(20., 10.)],
+        Vec2::new(10., 5.)
+    )]
+    fn group_offset_from_leaders_center(
+        #[case] leader: WorldPoint,
+        #[case] leaders: Vec<WorldPoint>,
+        #[case] expected: Vec2,
+    ) {
+        // When
+        let offset = group_offset(&leader, &leaders);
+
+        // Then
+        assert_eq!(offset, expected);
+    }
 }
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+use super::Side;
+
+/// After action statistics of the battle
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BattleStats {
+    a_fratricides: u32,
+    b_fratricides: u32,
+}
+
+impl BattleStats {
+    /// Soldiers of side killed or injured by their own side
+    pub fn fratricides(&self, side: &Side) -> u32 {
+        match side {
+            Side::A => self.a_fratricides,
+            Side::B => self.b_fratricides,
+            Side::All => self.a_fratricides + self.b_fratricides,
+        }
+    }
+
+    pub fn add_fratricide(&mut self, side: &Side) {
+        match side {
+            Side::A => self.a_fratricides += 1,
+            Side::B => self.b_fratricides += 1,
+            Side::All => {}
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entity::soldier::Soldier,
+    game::{role::SoldierRole, weapon::Weapon},
+};
+
+/// How a soldier chooses its target among visible opponents
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum TargetPolicy {
+    #[default]
+    Nearest,
+    /// Machine gunners and opponents firing at us first
+    MostThreatening,
+    WeakestCover,
+    VehiclesFirst,
+    LeadersFirst,
+    /// Squad leaders, then vehicle crews
+    OfficersAndCrews,
+}
+
+impl TargetPolicy {
+    /// Policy of soldier role (or deduced from its main weapon)
+    pub fn role_default(soldier: &Soldier) -> Self {
+        match soldier.role() {
+            SoldierRole::AntiTank => return Self::VehiclesFirst,
+            SoldierRole::Sniper => return Self::OfficersAndCrews,
+            // Scouts only reveal themselves to answer those firing at them
+            SoldierRole::Scout => return Self::MostThreatening,
+            SoldierRole::Rifleman => {}
+        }
+
+        match soldier.main_weapon() {
+            Some(weapon) if weapon.is_machine_gun() => Self::MostThreatening,
+            Some(Weapon::MosinNagantM1924(_, _)) | None => Self::Nearest,
+            Some(Weapon::Ptrd41(_, _))
+            | Some(Weapon::Dyakonov(_, _))
+            | Some(Weapon::GrenadeBundle(_, _)) => Self::VehiclesFirst,
+        }
+    }
+
+    /// Policy following this one (used to toggle policies)
+    pub fn next(&self) -> Self {
+        match self {
+            TargetPolicy::Nearest => TargetPolicy::MostThreatening,
+            TargetPolicy::MostThreatening => TargetPolicy::WeakestCover,
+            TargetPolicy::WeakestCover => TargetPolicy::VehiclesFirst,
+            TargetPolicy::VehiclesFirst => TargetPolicy::LeadersFirst,
+            TargetPolicy::LeadersFirst => TargetPolicy::OfficersAndCrews,
+            TargetPolicy::OfficersAndCrews => TargetPolicy::Nearest,
+        }
+    }
+
+    pub fn display(&self) -> &str {
+        match self {
+            TargetPolicy::Nearest => "nearest",
+            TargetPolicy::MostThreatening => "most threatening",
+            TargetPolicy::WeakestCover => "weakest cover",
+            TargetPolicy::VehiclesFirst => "vehicles first",
+            TargetPolicy::LeadersFirst => "leaders first",
+            TargetPolicy::OfficersAndCrews => "officers and crews",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use crate::{
+        game::Side,
+        types::{SoldierIndex, SquadUuid, WorldPoint},
+    };
+
+    use super::*;
+
+    #[rstest]
+    #[case(
+        SoldierRole::Rifleman,
+        Some(Weapon::MosinNagantM1924(true, None)),
+        TargetPolicy::Nearest
+    )]
+    #[case(SoldierRole::Rifleman, None, TargetPolicy::Nearest)]
+    #[case(
+        SoldierRole::Rifleman,
+        Some(Weapon::Ptrd41(true, None)),
+        TargetPolicy::VehiclesFirst
+    )]
+    #[case(
+        SoldierRole::Rifleman,
+        Some(Weapon::GrenadeBundle(true, None)),
+        TargetPolicy::VehiclesFirst
+    )]
+    #[case(
+        SoldierRole::AntiTank,
+        Some(Weapon::MosinNagantM1924(true, None)),
+        TargetPolicy::VehiclesFirst
+    )]
+    #[case(
+        SoldierRole::Sniper,
+        Some(Weapon::MosinNagantM1924(true, None)),
+        TargetPolicy::OfficersAndCrews
+    )]
+    #[case(
+        SoldierRole::Scout,
+        Some(Weapon::MosinNagantM1924(true, None)),
+        TargetPolicy::MostThreatening
+    )]
+    fn role_default_policy(
+        #[case] role: SoldierRole,
+        #[case] weapon: Option<Weapon>,
+        #[case] expected: TargetPolicy,
+    ) {
+        // Given
+        let soldier = Soldier::new(
+            SoldierIndex(0),
+            WorldPoint::new(0., 0.),
+            SquadUuid(0),
+            Side::A,
+            weapon,
+            vec![],
+        )
+        .with_role(role);
+
+        // When
+        let policy = TargetPolicy::role_default(&soldier);
+
+        // Then
+        assert_eq!(policy, expected)
+    }
+}
@@ -1,47 +1,101 @@
 use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
 
-use crate::audio::Sound;
+use crate::{audio::Sound, types::Distance};
+
+use super::explosive::ExplosiveType;
 
 #[allow(non_camel_case_types)]
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
 pub enum Ammunition {
     x762x54R,
+    x145x114,
+    Vpgs41,
+    GrenadeBundle,
+}
+
+impl Ammunition {
+    /// Armor thickness (millimeters) pierced at this distance
+    pub fn armor_penetration(&self, distance: &Distance) -> i64 {
+        match self {
+            Ammunition::x762x54R => 0,
+            // Kinetic penetrator loses power with distance
+            Ammunition::x145x114 => (35 - distance.meters() / 50).max(0),
+            // Shaped charge penetration doesn't depend on distance
+            Ammunition::Vpgs41 => 50,
+            Ammunition::GrenadeBundle => 20,
+        }
+    }
+
+    pub fn is_anti_tank(&self) -> bool {
+        match self {
+            Ammunition::x762x54R => false,
+            Ammunition::x145x114 | Ammunition::Vpgs41 | Ammunition::GrenadeBundle => true,
+        }
+    }
+
+    /// Explosive detonating at impact point (if any)
+    pub fn explosive(&self) -> Option<ExplosiveType> {
+        match self {
+            Ammunition::x762x54R | Ammunition::x145x114 => None,
+            Ammunition::Vpgs41 => Some(ExplosiveType::Vpgs41),
+            Ammunition::GrenadeBundle => Some(ExplosiveType::GrenadeBundle),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub enum Magazine {
     MosinNagant(usize),
+    // Single shot weapons : one cartridge, grenade or bundle per "magazine"
+    Ptrd(usize),
+    Vpgs41(usize),
+    GrenadeBundle(usize),
 }
 
 impl Magazine {
     pub fn name(&self) -> &str {
         match self {
             Magazine::MosinNagant(_) => "Mosin Nagant",
+            Magazine::Ptrd(_) => "PTRD",
+            Magazine::Vpgs41(_) => "VPGS-41",
+            Magazine::GrenadeBundle(_) => "Grenade bundle",
         }
     }
 
     pub fn full(magazine: Self) -> Self {
         match magazine {
             Magazine::MosinNagant(_) => Magazine::MosinNagant(5),
+            Magazine::Ptrd(_) => Magazine::Ptrd(1),
+            Magazine::Vpgs41(_) => Magazine::Vpgs41(1),
+            Magazine::GrenadeBundle(_) => Magazine::GrenadeBundle(1),
         }
     }
 
     pub fn ammunition(&self) -> Ammunition {
         match self {
             Magazine::MosinNagant(_) => Ammunition::x762x54R,
+            Magazine::Ptrd(_) => Ammunition::x145x114,
+            Magazine::Vpgs41(_) => Ammunition::Vpgs41,
+            Magazine::GrenadeBundle(_) => Ammunition::GrenadeBundle,
         }
     }
 
     pub fn filled(&self) -> bool {
         match self {
-            Magazine::MosinNagant(fill) => *fill > 0,
+            Magazine::MosinNagant(fill)
+            | Magazine::Ptrd(fill)
+            | Magazine::Vpgs41(fill)
+            | Magazine::GrenadeBundle(fill) => *fill > 0,
         }
     }
 
     fn remove_one(&mut self) {
         match self {
-            Magazine::MosinNagant(fill) => {
+            Magazine::MosinNagant(fill)
+            | Magazine::Ptrd(fill)
+            | Magazine::Vpgs41(fill)
+            | Magazine::GrenadeBundle(fill) => {
                 if *fill > 0 {
                     *fill -= 1;
                 }
@@ -53,6 +107,7 @@ impl Magazine {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum GunFireSoundType {
     MosinNagant,
+    Thrown,
 }
 
 impl GunFireSoundType {
@@ -65,6 +120,7 @@ impl GunFireSoundType {
                 Sound::MosinNagantFire4,
                 Sound::MosinNagantFire5,
             ],
+            GunFireSoundType::Thrown => return vec![],
         };
         let sound = *pick_from
             .choose(&mut rand::thread_rng())
@@ -78,29 +134,41 @@ impl GunFireSoundType {
 pub enum Weapon {
     // ready bullet, filled magazine
     MosinNagantM1924(bool, Option<Magazine>),
+    Ptrd41(bool, Option<Magazine>),
+    /// Rifle grenade launcher
+    Dyakonov(bool, Option<Magazine>),
+    GrenadeBundle(bool, Option<Magazine>),
 }
 
 impl Weapon {
     pub fn name(&self) -> &str {
         match self {
             Weapon::MosinNagantM1924(_, _) => "Mosin Nagant M1924",
+            Weapon::Ptrd41(_, _) => "PTRD-41",
+            Weapon::Dyakonov(_, _) => "Dyakonov",
+            Weapon::GrenadeBundle(_, _) => "Grenade bundle",
         }
     }
 
     pub fn gun_fire_sound_type(&self) -> GunFireSoundType {
         match self {
-            Weapon::MosinNagantM1924(_, _) => GunFireSoundType::MosinNagant,
+            // No dedicated samples yet (rifle grenade is launched by a rifle blank cartridge)
+            Weapon::MosinNagantM1924(_, _) | Weapon::Ptrd41(_, _) | Weapon::Dyakonov(_, _) => {
+                GunFireSoundType::MosinNagant
+            }
+            Weapon::GrenadeBundle(_, _) => GunFireSoundType::Thrown,
         }
     }
 
     pub fn reload_sounds(&self) -> Vec<Sound> {
         let pick_from = match self {
-            Weapon::MosinNagantM1924(_, _) => vec![
+            Weapon::MosinNagantM1924(_, _) | Weapon::Ptrd41(_, _) | Weapon::Dyakonov(_, _) => vec![
                 Sound::MosinNagantReload1,
                 Sound::MosinNagantReload2,
                 Sound::MosinNagantReload3,
                 Sound::MosinNagantReload4,
             ],
+            Weapon::GrenadeBundle(_, _) => return vec![],
         };
         let sound = *pick_from
             .choose(&mut rand::thread_rng())
@@ -111,14 +179,21 @@ impl Weapon {
 
     pub fn magazine(&self) -> &Option<Magazine> {
         match self {
-            Weapon::MosinNagantM1924(_, magazine) => magazine,
+            Weapon::MosinNagantM1924(_, magazine)
+            | Weapon::Ptrd41(_, magazine)
+            | Weapon::Dyakonov(_, magazine)
+            | Weapon::GrenadeBundle(_, magazine) => magazine,
         }
     }
 
     pub fn accepted_magazine(&self, magazine: &Magazine) -> bool {
-        match magazine {
-            Magazine::MosinNagant(_) => true,
-        }
+        matches!(
+            (self, magazine),
+            (Weapon::MosinNagantM1924(_, _), Magazine::MosinNagant(_))
+                | (Weapon::Ptrd41(_, _), Magazine::Ptrd(_))
+                | (Weapon::Dyakonov(_, _), Magazine::Vpgs41(_))
+                | (Weapon::GrenadeBundle(_, _), Magazine::GrenadeBundle(_))
+        )
     }
 
     pub fn ammunition(&self) -> Ammunition {
@@ -129,18 +204,27 @@ impl Weapon {
         // Default value
         match self {
             Weapon::MosinNagantM1924(_, _) => Ammunition::x762x54R,
+            Weapon::Ptrd41(_, _) => Ammunition::x145x114,
+            Weapon::Dyakonov(_, _) => Ammunition::Vpgs41,
+            Weapon::GrenadeBundle(_, _) => Ammunition::GrenadeBundle,
         }
     }
 
     pub fn can_fire(&self) -> bool {
         match self {
-            Weapon::MosinNagantM1924(ammunition, _) => *ammunition,
+            Weapon::MosinNagantM1924(ammunition, _)
+            | Weapon::Ptrd41(ammunition, _)
+            | Weapon::Dyakonov(ammunition, _)
+            | Weapon::GrenadeBundle(ammunition, _) => *ammunition,
         }
     }
 
     pub fn can_reload(&self) -> bool {
         match self {
-            Weapon::MosinNagantM1924(_, magazine) => {
+            Weapon::MosinNagantM1924(_, magazine)
+            | Weapon::Ptrd41(_, magazine)
+            | Weapon::Dyakonov(_, magazine)
+            | Weapon::GrenadeBundle(_, magazine) => {
                 if let Some(magazine) = magazine {
                     return magazine.filled();
                 }
@@ -152,7 +236,10 @@ impl Weapon {
 
     pub fn reload(&mut self) {
         match self {
-            Weapon::MosinNagantM1924(ready_bullet, magazine) => {
+            Weapon::MosinNagantM1924(ready_bullet, magazine)
+            | Weapon::Ptrd41(ready_bullet, magazine)
+            | Weapon::Dyakonov(ready_bullet, magazine)
+            | Weapon::GrenadeBundle(ready_bullet, magazine) => {
                 if !*ready_bullet {
                     if let Some(magazine_) = magazine {
                         if magazine_.filled() {
@@ -171,19 +258,70 @@ impl Weapon {
 
     pub fn shot(&mut self) {
         match self {
-            Weapon::MosinNagantM1924(ready_bullet, _) => *ready_bullet = false,
+            Weapon::MosinNagantM1924(ready_bullet, _)
+            | Weapon::Ptrd41(ready_bullet, _)
+            | Weapon::Dyakonov(ready_bullet, _)
+            | Weapon::GrenadeBundle(ready_bullet, _) => *ready_bullet = false,
         }
     }
 
     pub fn set_magazine(&mut self, new_magazine: Magazine) {
         match self {
-            Weapon::MosinNagantM1924(_, magazine) => *magazine = Some(new_magazine),
+            Weapon::MosinNagantM1924(_, magazine)
+            | Weapon::Ptrd41(_, magazine)
+            | Weapon::Dyakonov(_, magazine)
+            | Weapon::GrenadeBundle(_, magazine) => *magazine = Some(new_magazine),
+        }
+    }
+
+    /// Half angle (radians) of the weapon own dispersion cone
+    pub fn dispersion(&self) -> f32 {
+        match self {
+            Weapon::MosinNagantM1924(_, _) => 0.002,
+            Weapon::Ptrd41(_, _) => 0.003,
+            Weapon::Dyakonov(_, _) => 0.03,
+            Weapon::GrenadeBundle(_, _) => 0.08,
+        }
+    }
+
+    /// Distance beyond which dispersion grows
+    pub fn effective_range(&self) -> Distance {
+        match self {
+            Weapon::MosinNagantM1924(_, _) => Distance::from_meters(500),
+            Weapon::Ptrd41(_, _) => Distance::from_meters(300),
+            Weapon::Dyakonov(_, _) => Distance::from_meters(60),
+            Weapon::GrenadeBundle(_, _) => Distance::from_meters(10),
+        }
+    }
+
+    /// Distance beyond which weapon can't be used (if any)
+    pub fn maximum_range(&self) -> Option<Distance> {
+        match self {
+            Weapon::MosinNagantM1924(_, _) | Weapon::Ptrd41(_, _) => None,
+            Weapon::Dyakonov(_, _) => Some(Distance::from_meters(150)),
+            Weapon::GrenadeBundle(_, _) => Some(Distance::from_meters(20)),
+        }
+    }
+
+    pub fn is_anti_tank(&self) -> bool {
+        self.ammunition().is_anti_tank()
+    }
+
+    pub fn is_machine_gun(&self) -> bool {
+        match self {
+            Weapon::MosinNagantM1924(_, _)
+            | Weapon::Ptrd41(_, _)
+            | Weapon::Dyakonov(_, _)
+            | Weapon::GrenadeBundle(_, _) => false,
         }
     }
 
     pub fn ok_count_magazines(&self) -> usize {
         match self {
             Weapon::MosinNagantM1924(_, _) => 4,
+            Weapon::Ptrd41(_, _) => 10,
+            Weapon::Dyakonov(_, _) => 3,
+            Weapon::GrenadeBundle(_, _) => 1,
         }
     }
 }
@@ -1,3 +1,145 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    behavior::Behavior,
+    config::{OPENING_DOOR_OPACITY, OPENING_WINDOW_OPACITY},
+    types::{GridPoint, WorldPoint},
+};
+
+/// Opening in interior walls : doors are entry points, windows are firing ports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpeningType {
+    Door,
+    Window,
+}
+
+impl FromStr for OpeningType {
+    type Err = InteriorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Door" => Ok(Self::Door),
+            "Window" => Ok(Self::Window),
+            _ => Err(InteriorError::UnknownOpening(s.to_string())),
+        }
+    }
+}
+
+impl OpeningType {
+    pub fn walkable(&self) -> bool {
+        match self {
+            OpeningType::Door => true,
+            OpeningType::Window => false,
+        }
+    }
+
+    pub fn opacity(&self) -> f32 {
+        match self {
+            OpeningType::Door => OPENING_DOOR_OPACITY,
+            OpeningType::Window => OPENING_WINDOW_OPACITY,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum InteriorError {
+    UnknownOpening(String),
+}
+
+impl Display for InteriorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InteriorError::UnknownOpening(name) => {
+                f.write_str(&format!("Unknown opening : {}", name))
+            }
+        }
+    }
+}
+
+/// Part of a building separated from others by inner walls
+#[derive(Clone)]
+pub struct Room {
+    x: f32,
+    relative_x: f32,
+    y: f32,
+    relative_y: f32,
+    width: f32,
+    relative_width: f32,
+    height: f32,
+    relative_height: f32,
+}
+
+impl Room {
+    pub fn new(
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        image_width: f32,
+        image_height: f32,
+    ) -> Self {
+        Self {
+            x,
+            relative_x: x / image_width,
+            y,
+            relative_y: y / image_height,
+            width,
+            relative_width: width / image_width,
+            height,
+            relative_height: height / image_height,
+        }
+    }
+
+    pub fn x(&self) -> f32 {
+        self.x
+    }
+
+    pub fn y(&self) -> f32 {
+        self.y
+    }
+
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    pub fn relative_x(&self) -> f32 {
+        self.relative_x
+    }
+
+    pub fn relative_y(&self) -> f32 {
+        self.relative_y
+    }
+
+    pub fn relative_width(&self) -> f32 {
+        self.relative_width
+    }
+
+    pub fn relative_height(&self) -> f32 {
+        self.relative_height
+    }
+
+    pub fn contains(&self, point: &WorldPoint) -> bool {
+        point.x >= self.x
+            && point.x <= self.x + self.width
+            && point.y >= self.y
+            && point.y <= self.y + self.height
+    }
+
+    /// True if rooms share some surface (touching borders don't overlap)
+    pub fn overlaps(&self, other: &Room) -> bool {
+        self.x < other.x + other.width
+            && other.x < self.x + self.width
+            && self.y < other.y + other.height
+            && other.y < self.y + self.height
+    }
+}
+
+/// Building. Without openings, its walls are simple terrain tiles. With openings, walls can
+/// only be crossed by doors and seen through by windows. Rooms (if any) split its inside.
 #[derive(Clone)]
 pub struct Interior {
     x: f32,
@@ -8,6 +150,9 @@ pub struct Interior {
     relative_width: f32,
     height: f32,
     relative_height: f32,
+    floors: u8,
+    openings: Vec<(GridPoint, OpeningType)>,
+    rooms: Vec<Room>,
 }
 
 impl Interior {
@@ -28,9 +173,21 @@ impl Interior {
             relative_width: width / image_width,
             height,
             relative_height: height / image_height,
+            floors: 1,
+            openings: vec![],
+            rooms: vec![],
         }
     }
 
+    pub fn set_layout(&mut self, floors: u8, openings: Vec<(GridPoint, OpeningType)>) {
+        self.floors = floors.max(1);
+        self.openings = openings;
+    }
+
+    pub fn set_rooms(&mut self, rooms: Vec<Room>) {
+        self.rooms = rooms;
+    }
+
     pub fn x(&self) -> f32 {
         self.x
     }
@@ -62,4 +219,63 @@ impl Interior {
     pub fn relative_height(&self) -> f32 {
         self.relative_height
     }
+
+    pub fn floors(&self) -> u8 {
+        self.floors
+    }
+
+    pub fn openings(&self) -> &Vec<(GridPoint, OpeningType)> {
+        &self.openings
+    }
+
+    /// Interior have a layout when openings are defined (walls are closed elsewhere)
+    pub fn has_layout(&self) -> bool {
+        !self.openings.is_empty()
+    }
+
+    pub fn rooms(&self) -> &Vec<Room> {
+        &self.rooms
+    }
+
+    /// Index of the room containing point
+    pub fn room(&self, point: &WorldPoint) -> Option<usize> {
+        self.rooms.iter().position(|room| room.contains(point))
+    }
+
+    /// True if points are in the same room (or in the interior if it has no rooms)
+    pub fn same_space(&self, a: &WorldPoint, b: &WorldPoint) -> bool {
+        if !self.contains(a) || !self.contains(b) {
+            return false;
+        }
+
+        self.rooms.is_empty() || self.room(a) == self.room(b)
+    }
+
+    /// Floor where soldier with this behavior stands : soldiers holding position (defending,
+    /// hiding or firing) are posted at the upper floor, others stay at the ground floor
+    pub fn posted_floor(&self, behavior: &Behavior) -> u8 {
+        match behavior {
+            Behavior::Defend(_)
+            | Behavior::Hide(_)
+            | Behavior::SuppressFire(_)
+            | Behavior::EngageSoldier(_)
+            | Behavior::EngageVehicle(_) => self.floors - 1,
+            _ => 0,
+        }
+    }
+
+    pub fn contains(&self, point: &WorldPoint) -> bool {
+        point.x >= self.x
+            && point.x <= self.x + self.width
+            && point.y >= self.y
+            && point.y <= self.y + self.height
+    }
+
+    /// True if interiors share some surface (touching borders don't overlap)
+    pub fn overlaps(&self, other: &Interior) -> bool {
+        self.x < other.x + other.width
+            && other.x < self.x + self.width
+            && self.y < other.y + other.height
+            && other.y < self.y + self.height
+    }
 }
@@ -1,16 +1,27 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
-use self::{decor::Decor, interior::Interior, spawn::SpawnZone, terrain::TerrainTile};
+use self::{
+    decor::Decor,
+    interior::{Interior, OpeningType},
+    spawn::SpawnZone,
+    terrain::{TerrainTile, TileType},
+};
 use crate::{
-    config::ServerConfig,
+    config::{ServerConfig, ELEVATION_CLIMB_COST, ELEVATION_PIXEL_STEPS, INTERIOR_FLOOR_HEIGHT},
+    entity::soldier::Soldier,
     game::{
         control::MapControl,
+        environment::Environment,
         flag::{Flag, FlagName},
     },
-    physics::path::{Direction, PathMode},
+    physics::{
+        los::{LosCache, SightTile},
+        path::{Direction, PathMode},
+    },
     types::{GridPoint, VehicleSize, WorldPoint},
     utils::grid_points_for_square,
 };
+use bresenham::Bresenham;
 use oc_core::spawn::SpawnZoneName;
 use strum::IntoEnumIterator;
 
@@ -37,6 +48,15 @@ pub struct Map {
     tile_height: u32,
     decor: Decor,
     flags: Vec<Flag>,
+    elevated: bool,
+    // Index of interior containing each grid point
+    interior_grid_points: HashMap<GridPoint, usize>,
+    openings: HashMap<GridPoint, OpeningType>,
+    // Original type of tiles changed during battle
+    original_tile_types: HashMap<GridPoint, TileType>,
+    environment: Environment,
+    // Shared between clones because computed lines only depend on map ground
+    los: Arc<LosCache>,
 }
 
 impl Map {
@@ -56,6 +76,31 @@ impl Map {
         decor: Decor,
         flags: Vec<Flag>,
     ) -> Self {
+        let elevated = terrain_tiles.iter().any(|tile| tile.elevation != 0.0)
+            || interiors.iter().any(|interior| interior.floors() > 1);
+        let mut interior_grid_points = HashMap::new();
+        let mut openings = HashMap::new();
+        for (i, interior) in interiors.iter().enumerate() {
+            let start_x = (interior.x() / tile_width as f32) as i32;
+            let start_y = (interior.y() / tile_height as f32) as i32;
+            let end_x = (((interior.x() + interior.width()) / tile_width as f32).ceil() as i32 - 1)
+                .max(start_x);
+            let end_y = (((interior.y() + interior.height()) / tile_height as f32).ceil() as i32
+                - 1)
+            .max(start_y);
+            for x in start_x..=end_x {
+                for y in start_y..=end_y {
+                    // Overlapping interiors are refused by map reader, first one is kept
+                    interior_grid_points
+                        .entry(GridPoint::new(x, y))
+                        .or_insert(i);
+                }
+            }
+            for (grid_point, opening) in interior.openings() {
+                openings.insert(*grid_point, *opening);
+            }
+        }
+
         Self {
             name,
             background_image_path,
@@ -72,6 +117,12 @@ impl Map {
             tile_height,
             decor,
             flags,
+            elevated,
+            interior_grid_points,
+            openings,
+            original_tile_types: HashMap::new(),
+            environment: Environment::default(),
+            los: Arc::new(LosCache::new()),
         }
     }
 
@@ -95,6 +146,35 @@ impl Map {
         &self.interiors
     }
 
+    pub fn interior(&self, grid_point: &GridPoint) -> Option<&Interior> {
+        self.interior_grid_points
+            .get(grid_point)
+            .and_then(|i| self.interiors.get(*i))
+    }
+
+    pub fn opening(&self, grid_point: &GridPoint) -> Option<&OpeningType> {
+        self.openings.get(grid_point)
+    }
+
+    /// True if grid point is an interior wall which can't be crossed on foot (only doors
+    /// or breaches can be)
+    pub fn closed_wall(&self, grid_point: &GridPoint) -> bool {
+        let is_wall = self
+            .terrain_tile(grid_point)
+            .map(|tile| tile.type_ == TileType::BrickWall)
+            .unwrap_or(false);
+        let has_layout = self
+            .interior(grid_point)
+            .map(|interior| interior.has_layout())
+            .unwrap_or(false);
+        let walkable = self
+            .opening(grid_point)
+            .map(|opening| opening.walkable())
+            .unwrap_or(false);
+
+        is_wall && has_layout && !walkable
+    }
+
     pub fn width(&self) -> u32 {
         self.width
     }
@@ -127,6 +207,159 @@ impl Map {
         &self.terrain_tiles
     }
 
+    /// False if map is flat (no elevation layer or all elevations to zero)
+    pub fn elevated(&self) -> bool {
+        self.elevated
+    }
+
+    pub fn terrain_tile(&self, grid_point: &GridPoint) -> Option<&TerrainTile> {
+        if !self.contains(grid_point) {
+            return None;
+        }
+
+        self.terrain_tiles
+            .get((grid_point.y * self.width as i32 + grid_point.x) as usize)
+    }
+
+    /// Change tile type (by example when destroyed)
+    pub fn set_tile_type(&mut self, grid_point: &GridPoint, type_: TileType) {
+        if !self.contains(grid_point) {
+            return;
+        }
+
+        let i = (grid_point.y * self.width as i32 + grid_point.x) as usize;
+        if let Some(tile) = self.terrain_tiles.get_mut(i) {
+            let original = std::mem::replace(&mut tile.type_, type_);
+            self.original_tile_types
+                .entry(*grid_point)
+                .or_insert(original);
+        }
+    }
+
+    /// Current type of tiles changed during battle, sorted by position
+    pub fn tile_changes(&self) -> Vec<(GridPoint, TileType)> {
+        let mut changes: Vec<(GridPoint, TileType)> = self
+            .original_tile_types
+            .keys()
+            .filter_map(|grid_point| {
+                self.terrain_tile(grid_point)
+                    .map(|tile| (*grid_point, tile.type_.clone()))
+            })
+            .collect();
+        changes.sort_by_key(|(grid_point, _)| (grid_point.y, grid_point.x));
+        changes
+    }
+
+    /// Restore original tiles then apply given changes
+    pub fn apply_tile_changes(&mut self, changes: &[(GridPoint, TileType)]) {
+        for (grid_point, original) in std::mem::take(&mut self.original_tile_types) {
+            let i = (grid_point.y * self.width as i32 + grid_point.x) as usize;
+            if let Some(tile) = self.terrain_tiles.get_mut(i) {
+                tile.type_ = original;
+            }
+        }
+
+        for (grid_point, type_) in changes {
+            self.set_tile_type(grid_point, type_.clone());
+        }
+    }
+
+    /// Weather and daylight of the battle
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    pub fn set_environment(&mut self, environment: Environment) {
+        self.environment = environment;
+    }
+
+    pub fn los(&self) -> &LosCache {
+        &self.los
+    }
+
+    /// Tiles crossed by sight line between points, observers being at given building floors
+    /// (see `LosCache`)
+    pub fn sight_line(
+        &self,
+        from: &WorldPoint,
+        from_floor: u8,
+        to: &WorldPoint,
+        to_floor: u8,
+    ) -> Vec<SightTile> {
+        self.los.line(self, (from, from_floor), (to, to_floor))
+    }
+
+    /// Ground elevation (in meters) at grid point, zero outside map
+    pub fn elevation(&self, grid_point: &GridPoint) -> f32 {
+        if !self.elevated {
+            return 0.0;
+        }
+
+        self.terrain_tile(grid_point)
+            .map(|tile| tile.elevation)
+            .unwrap_or(0.0)
+    }
+
+    /// Elevation (in meters) of observers at grid point and building floor (if inside one)
+    pub fn sight_elevation(&self, grid_point: &GridPoint, floor: u8) -> f32 {
+        let floor = self
+            .interior(grid_point)
+            .map(|interior| floor.min(interior.floors() - 1))
+            .unwrap_or(0);
+        self.elevation(grid_point) + floor as f32 * INTERIOR_FLOOR_HEIGHT
+    }
+
+    /// Building floor where soldier stands (ground floor if not inside building)
+    pub fn soldier_floor(&self, soldier: &Soldier) -> u8 {
+        self.interior(&self.grid_point_from_world_point(&soldier.world_point()))
+            .map(|interior| interior.posted_floor(soldier.behavior()))
+            .unwrap_or(0)
+    }
+
+    pub fn world_point_elevation(&self, world_point: &WorldPoint) -> f32 {
+        self.elevation(&self.grid_point_from_world_point(world_point))
+    }
+
+    /// True if ground between points is higher than the straight line between them.
+    /// Heights (in meters) are relative to ground of each point.
+    pub fn elevation_masks(
+        &self,
+        from: &WorldPoint,
+        from_height: f32,
+        to: &WorldPoint,
+        to_height: f32,
+    ) -> bool {
+        if !self.elevated {
+            return false;
+        }
+
+        let from_grid_point = self.grid_point_from_world_point(from);
+        let to_grid_point = self.grid_point_from_world_point(to);
+        let from_altitude = self.elevation(&from_grid_point) + from_height;
+        let to_altitude = self.elevation(&to_grid_point) + to_height;
+        let pixels: Vec<(isize, isize)> = Bresenham::new(
+            (from.x as isize, from.y as isize),
+            (to.x as isize, to.y as isize),
+        )
+        .collect();
+
+        for (i, (pixel_x, pixel_y)) in pixels.iter().enumerate().step_by(ELEVATION_PIXEL_STEPS) {
+            let grid_point = self
+                .grid_point_from_world_point(&WorldPoint::new(*pixel_x as f32, *pixel_y as f32));
+            if grid_point == from_grid_point || grid_point == to_grid_point {
+                continue;
+            }
+
+            let progress = i as f32 / pixels.len() as f32;
+            let line_altitude = from_altitude + (to_altitude - from_altitude) * progress;
+            if self.elevation(&grid_point) > line_altitude {
+                return true;
+            }
+        }
+
+        false
+    }
+
     pub fn flags(&self) -> &Vec<Flag> {
         &self.flags
     }
@@ -196,10 +429,19 @@ impl Map {
                     }
                 }
 
+                if let PathMode::Walk = path_mode {
+                    if self.closed_wall(&GridPoint::new(new_x, new_y)) {
+                        continue;
+                    }
+                }
+
                 let cost = match path_mode {
-                    PathMode::Walk => next_tile.type_().pedestrian_cost(),
+                    PathMode::Walk => next_tile.type_().pedestrian_cost(&self.environment),
                     PathMode::Drive(_size) => from.1.angle_cost(&direction),
                 };
+                // Climb is slower than walk on flat ground
+                let climb = (next_tile.elevation - self.elevation(&from.0)).max(0.0);
+                let cost = cost + (climb * ELEVATION_CLIMB_COST) as i32;
 
                 successors.push(((GridPoint::new(new_x, new_y), direction), cost))
             }
@@ -328,3 +570,240 @@ pub fn find_arbitrary_cover_grid_points<'a>(
 
     tiles
 }
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use crate::{
+        behavior::Behavior,
+        types::{Angle, WorldPaths},
+    };
+
+    use super::{interior::Room, *};
+
+    /// One row map of 10 tiles (10 pixels each) with given elevations
+    fn map(elevations: [f32; 10]) -> Map {
+        let terrain_tiles = elevations
+            .iter()
+            .enumerate()
+            .map(|(x, elevation)| {
+                TerrainTile::from_str_id("ShortGrass", 10, 10, 0.1, 0.1, x as u32, 0, 0, 0)
+                    .unwrap()
+                    .elevation(*elevation)
+            })
+            .collect();
+
+        Map::new(
+            "TestMap".to_string(),
+            PathBuf::from("."),
+            PathBuf::from("."),
+            PathBuf::from("."),
+            vec![],
+            vec![],
+            10,
+            1,
+            terrain_tiles,
+            10,
+            10,
+            Decor::new(vec![], vec![]),
+            vec![],
+        )
+    }
+
+    /// One row map of 10 tiles (10 pixels each) with a wall at x=4, inside an interior
+    fn building_map(openings: Option<Vec<(GridPoint, OpeningType)>>) -> Map {
+        let terrain_tiles = (0..10)
+            .map(|x| {
+                let id = if x == 4 { "BrickWall" } else { "ShortGrass" };
+                TerrainTile::from_str_id(id, 10, 10, 0.1, 0.1, x as u32, 0, 0, 0).unwrap()
+            })
+            .collect();
+        let mut interior = Interior::new(30., 0., 40., 10., 100., 10.);
+        if let Some(openings) = openings {
+            interior.set_layout(1, openings);
+        }
+
+        Map::new(
+            "TestMap".to_string(),
+            PathBuf::from("."),
+            PathBuf::from("."),
+            PathBuf::from("."),
+            vec![interior],
+            vec![],
+            10,
+            1,
+            terrain_tiles,
+            10,
+            10,
+            Decor::new(vec![], vec![]),
+            vec![],
+        )
+    }
+
+    #[rstest]
+    #[case(None, true)]
+    #[case(Some(vec![(GridPoint::new(4, 0), OpeningType::Door)]), true)]
+    #[case(Some(vec![(GridPoint::new(4, 0), OpeningType::Window)]), false)]
+    #[case(Some(vec![(GridPoint::new(6, 0), OpeningType::Door)]), false)]
+    fn walls_are_crossed_by_doors(
+        #[case] openings: Option<Vec<(GridPoint, OpeningType)>>,
+        #[case] crossable: bool,
+    ) {
+        // Given
+        let map = building_map(openings);
+
+        // When
+        let successors = map.successors(&(GridPoint::new(3, 0), Direction::Est), &PathMode::Walk);
+
+        // Then
+        assert_eq!(
+            successors
+                .iter()
+                .any(|((grid_point, _), _)| grid_point == &GridPoint::new(4, 0)),
+            crossable
+        );
+    }
+
+    #[rstest]
+    #[case(GridPoint::new(2, 0), false)]
+    #[case(GridPoint::new(3, 0), true)]
+    #[case(GridPoint::new(6, 0), true)]
+    #[case(GridPoint::new(7, 0), false)]
+    fn interior_covers_its_tiles_only(#[case] grid_point: GridPoint, #[case] inside: bool) {
+        // Given
+        let map = building_map(None);
+
+        // When
+        let interior = map.interior(&grid_point);
+
+        // Then
+        assert_eq!(interior.is_some(), inside)
+    }
+
+    #[rstest]
+    #[case(Behavior::MoveTo(WorldPaths::new(vec![])), 0.)]
+    #[case(Behavior::Defend(Angle(0.)), INTERIOR_FLOOR_HEIGHT)]
+    fn holding_soldiers_are_posted_at_upper_floor(
+        #[case] behavior: Behavior,
+        #[case] expected: f32,
+    ) {
+        // Given
+        let mut map = building_map(None);
+        map.interiors[0].set_layout(2, vec![]);
+        let grid_point = GridPoint::new(5, 0);
+
+        // When
+        let floor = map.interiors[0].posted_floor(&behavior);
+
+        // Then
+        assert_eq!(map.sight_elevation(&grid_point, floor), expected);
+        assert_eq!(map.sight_elevation(&GridPoint::new(1, 0), floor), 0.);
+    }
+
+    #[rstest]
+    #[case(WorldPoint::new(35., 5.), true)]
+    #[case(WorldPoint::new(65., 5.), false)]
+    #[case(WorldPoint::new(15., 5.), false)]
+    fn interior_rooms_split_space(#[case] point: WorldPoint, #[case] same: bool) {
+        // Given
+        let mut interior = Interior::new(30., 0., 40., 10., 100., 10.);
+        interior.set_rooms(vec![
+            Room::new(30., 0., 20., 10., 100., 10.),
+            Room::new(50., 0., 20., 10., 100., 10.),
+        ]);
+
+        // When
+        let same_space = interior.same_space(&WorldPoint::new(40., 5.), &point);
+
+        // Then
+        assert_eq!(same_space, same)
+    }
+
+    #[rstest]
+    #[case([0., 0., 0., 0., 0., 0., 0., 0., 0., 0.], false)]
+    #[case([0., 0., 0., 0., 5., 0., 0., 0., 0., 0.], true)]
+    #[case([10., 9., 8., 7., 5., 0., 0., 0., 0., 0.], false)]
+    #[case([0., 1., 2., 3., 4., 3., 2., 1., 0., 0.], true)]
+    fn ridge_masks_points(#[case] elevations: [f32; 10], #[case] masks: bool) {
+        // Given
+        let map = map(elevations);
+
+        // When
+        let masked = map.elevation_masks(
+            &WorldPoint::new(5., 5.),
+            1.5,
+            &WorldPoint::new(85., 5.),
+            1.5,
+        );
+
+        // Then
+        assert_eq!(masked, masks)
+    }
+
+    #[rstest]
+    fn sight_line_is_cached_by_tiles() {
+        // Given
+        let map = map([0., 0., 0., 0., 5., 0., 0., 0., 0., 0.]);
+
+        // When
+        let line = map.sight_line(&WorldPoint::new(2., 3.), 0, &WorldPoint::new(97., 3.), 0);
+        let same_line = map.sight_line(&WorldPoint::new(5., 5.), 0, &WorldPoint::new(95., 5.), 0);
+        let other_line = map.sight_line(&WorldPoint::new(15., 5.), 0, &WorldPoint::new(95., 5.), 0);
+
+        // Then
+        assert_eq!(map.los().len(), 2);
+        assert_eq!(line.len(), 10);
+        assert_eq!(same_line.len(), 10);
+        assert_eq!(other_line.len(), 9);
+        assert!(line[4].masked);
+        assert_eq!(line[0].world_point, WorldPoint::new(5., 5.));
+        assert_eq!(line[1].world_point, WorldPoint::new(10., 5.));
+    }
+
+    #[rstest]
+    fn sight_line_is_kept_when_tile_changes() {
+        // Given
+        let mut map = map([5., 0., 0., 0., 0., 0., 0., 0., 0., 5.]);
+        let line = map.sight_line(&WorldPoint::new(5., 5.), 0, &WorldPoint::new(95., 5.), 0);
+
+        // When
+        map.set_tile_type(&GridPoint::new(5, 0), TileType::Trunk);
+        let changed_line =
+            map.sight_line(&WorldPoint::new(5., 5.), 0, &WorldPoint::new(95., 5.), 0);
+
+        // Then
+        assert_eq!(map.los().len(), 1);
+        assert!(line[5].overlooks(&TileType::BrickWall));
+        assert!(!changed_line[5].overlooks(&TileType::Trunk));
+    }
+
+    #[rstest]
+    fn tile_changes_can_be_restored() {
+        // Given
+        let mut map = map([0.; 10]);
+        let mut other = map.clone();
+        map.set_tile_type(&GridPoint::new(3, 0), TileType::Dirt);
+        map.set_tile_type(&GridPoint::new(1, 0), TileType::MiddleRock);
+        map.set_tile_type(&GridPoint::new(3, 0), TileType::Mud);
+        other.set_tile_type(&GridPoint::new(5, 0), TileType::Dirt);
+
+        // When
+        let changes = map.tile_changes();
+        other.apply_tile_changes(&changes);
+
+        // Then
+        assert_eq!(
+            changes,
+            vec![
+                (GridPoint::new(1, 0), TileType::MiddleRock),
+                (GridPoint::new(3, 0), TileType::Mud)
+            ]
+        );
+        assert_eq!(other.tile_changes(), changes);
+        assert_eq!(
+            other.terrain_tile(&GridPoint::new(5, 0)).unwrap().type_,
+            TileType::ShortGrass
+        );
+    }
+}
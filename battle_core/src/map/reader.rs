@@ -12,13 +12,16 @@ use tiled::{
     TileLayer, Tileset,
 };
 
-use crate::game::flag::{Flag, FlagName};
+use crate::{
+    game::flag::{Flag, FlagName},
+    types::{GridPoint, WorldPoint},
+};
 
 use super::{
     decor::{Decor, DecorTile},
-    interior::Interior,
+    interior::{Interior, OpeningType, Room},
     spawn::SpawnZone,
-    terrain::{TerrainTile, TerrainTileError},
+    terrain::{TerrainTile, TerrainTileError, TileType},
     Map,
 };
 
@@ -27,12 +30,17 @@ type DecorTilesets = (Vec<Arc<Tileset>>, HashMap<usize, usize>);
 const BACKGROUND_IMAGE_LAYER_NAME: &str = "background_image";
 const INTERIORS_IMAGE_LAYER_NAME: &str = "interiors_image";
 const INTERIORS_ZONES_LAYER_NAME: &str = "interiors_zones";
+const INTERIORS_OPENINGS_LAYER_NAME: &str = "interiors_openings";
+const INTERIORS_ROOMS_LAYER_NAME: &str = "interiors_rooms";
+const INTERIOR_FLOORS_PROPERTY_KEY: &str = "FLOORS";
 const SPAWN_ZONES_LAYER_NAME: &str = "spawn_zones";
 const FLAGS_LAYER_NAME: &str = "flags";
 const DECOR_LAYER_NAME: &str = "decor";
 const TERRAIN_LAYER_NAME: &str = "terrain";
 const TERRAIN_TILESET_NAME: &str = "terrain";
 const TILE_ID_PROPERTY_KEY: &str = "ID";
+const ELEVATION_LAYER_NAME: &str = "elevation";
+const TILE_ELEVATION_PROPERTY_KEY: &str = "ELEVATION";
 
 #[derive(Debug)]
 pub enum MapReaderError {
@@ -87,6 +95,7 @@ pub struct MapReader {
     resources: PathBuf,
     name: String,
     map: TiledMap,
+    lenient: bool,
 }
 
 impl MapReader {
@@ -108,9 +117,73 @@ impl MapReader {
             resources: resources.to_path_buf(),
             name: name.to_string(),
             map,
+            lenient: false,
         })
     }
 
+    /// Read missing interiors zones, spawn zones, flags and decor layers as empty (to inspect incomplete maps)
+    pub fn lenient(mut self) -> Self {
+        self.lenient = true;
+        self
+    }
+
+    fn skipped(&self, name: &str) -> bool {
+        self.lenient && !self.map.layers().any(|layer| layer.name == name)
+    }
+
+    /// Names of required layers which are not in the map
+    pub fn missing_layers(&self) -> Vec<&'static str> {
+        [
+            BACKGROUND_IMAGE_LAYER_NAME,
+            INTERIORS_IMAGE_LAYER_NAME,
+            INTERIORS_ZONES_LAYER_NAME,
+            SPAWN_ZONES_LAYER_NAME,
+            FLAGS_LAYER_NAME,
+            DECOR_LAYER_NAME,
+            TERRAIN_LAYER_NAME,
+        ]
+        .into_iter()
+        .filter(|name| !self.map.layers().any(|layer| &layer.name == name))
+        .collect()
+    }
+
+    /// Terrain tiles used in terrain layer (by tile id) with missing or unknown terrain ID
+    pub fn unknown_terrain_ids(&self) -> Result<Vec<(u32, String)>, MapReaderError> {
+        let layer = self.terrain_layer()?;
+        let terrain_tileset = self.terrain_tileset()?;
+        let mut used = vec![];
+        for y in 0..layer.height() {
+            for x in 0..layer.width() {
+                if let Some(layer_tile_data) = layer.get_tile_data(x as i32, y as i32) {
+                    if !used.contains(&layer_tile_data.id()) {
+                        used.push(layer_tile_data.id());
+                    }
+                }
+            }
+        }
+        used.sort();
+
+        let mut unknown = vec![];
+        for tile_id in used {
+            match terrain_tileset
+                .get_tile(tile_id)
+                .and_then(|tile| tile.properties.get(TILE_ID_PROPERTY_KEY).cloned())
+            {
+                Some(tiled::PropertyValue::StringValue(id)) => {
+                    if let Err(error) = TileType::from_str(&id) {
+                        unknown.push((tile_id, error.to_string()))
+                    }
+                }
+                _ => unknown.push((
+                    tile_id,
+                    format!("Missing {} string property", TILE_ID_PROPERTY_KEY),
+                )),
+            }
+        }
+
+        Ok(unknown)
+    }
+
     fn layer(&self, name: &str) -> Result<Layer, MapReaderError> {
         match self
             .map
@@ -197,12 +270,154 @@ impl MapReader {
         }
     }
 
+    /// Openings layer is optional : interiors without openings have no layout
+    fn interiors_openings_layer(&self) -> Result<Option<ObjectLayer<'_>>, MapReaderError> {
+        if !self
+            .map
+            .layers()
+            .any(|layer| layer.name == INTERIORS_OPENINGS_LAYER_NAME)
+        {
+            return Ok(None);
+        }
+
+        match self.layer(INTERIORS_OPENINGS_LAYER_NAME)?.layer_type() {
+            LayerType::ObjectLayer(layer) => Ok(Some(layer)),
+            _ => Result::Err(MapReaderError::InvalidLayer(format!(
+                "Layer '{}' in map {} is not an object layer",
+                INTERIORS_OPENINGS_LAYER_NAME, self.name,
+            ))),
+        }
+    }
+
+    /// Rooms layer is optional : interiors without rooms are a single space
+    fn interiors_rooms_layer(&self) -> Result<Option<ObjectLayer<'_>>, MapReaderError> {
+        if !self
+            .map
+            .layers()
+            .any(|layer| layer.name == INTERIORS_ROOMS_LAYER_NAME)
+        {
+            return Ok(None);
+        }
+
+        match self.layer(INTERIORS_ROOMS_LAYER_NAME)?.layer_type() {
+            LayerType::ObjectLayer(layer) => Ok(Some(layer)),
+            _ => Result::Err(MapReaderError::InvalidLayer(format!(
+                "Layer '{}' in map {} is not an object layer",
+                INTERIORS_ROOMS_LAYER_NAME, self.name,
+            ))),
+        }
+    }
+
+    /// Rooms rectangles, each one inside an interior zone
+    fn interiors_rooms(&self) -> Result<Vec<Room>, MapReaderError> {
+        let layer = match self.interiors_rooms_layer()? {
+            Some(layer) => layer,
+            None => return Ok(vec![]),
+        };
+        let interiors_image = self.interiors_image()?;
+        let mut rooms = vec![];
+
+        for object in layer.objects() {
+            match object.shape {
+                tiled::ObjectShape::Rect { width, height } => rooms.push(Room::new(
+                    object.x,
+                    object.y,
+                    width,
+                    height,
+                    interiors_image.width as f32,
+                    interiors_image.height as f32,
+                )),
+                _ => {
+                    return Result::Err(MapReaderError::InvalidLayer(format!(
+                        "Layer '{}' in map {} contains non Rect shapes, this is not supported now",
+                        INTERIORS_ROOMS_LAYER_NAME, self.name,
+                    )))
+                }
+            }
+        }
+
+        Ok(rooms)
+    }
+
+    /// Grid points of doors and windows, named "Door" or "Window" (points or rectangles)
+    fn interiors_openings(&self) -> Result<Vec<(GridPoint, OpeningType)>, MapReaderError> {
+        let layer = match self.interiors_openings_layer()? {
+            Some(layer) => layer,
+            None => return Ok(vec![]),
+        };
+        let tile_width = self.tile_width()? as f32;
+        let tile_height = self.tile_height()? as f32;
+        let mut openings = vec![];
+
+        for object in layer.objects() {
+            let opening = OpeningType::from_str(&object.name).map_err(|error| {
+                MapReaderError::InvalidLayer(format!(
+                    "Layer '{}' in map {} : {}",
+                    INTERIORS_OPENINGS_LAYER_NAME, self.name, error,
+                ))
+            })?;
+            let (width, height) = match object.shape {
+                tiled::ObjectShape::Point(_, _) => (0., 0.),
+                tiled::ObjectShape::Rect { width, height } => (width, height),
+                _ => {
+                    return Result::Err(MapReaderError::InvalidLayer(format!(
+                        "Layer '{}' in map {} contains non Point or Rect shapes, this is not supported now",
+                        INTERIORS_OPENINGS_LAYER_NAME, self.name,
+                    )))
+                }
+            };
+            let start_x = (object.x / tile_width) as i32;
+            let start_y = (object.y / tile_height) as i32;
+            let end_x = (((object.x + width) / tile_width).ceil() as i32 - 1).max(start_x);
+            let end_y = (((object.y + height) / tile_height).ceil() as i32 - 1).max(start_y);
+            for x in start_x..=end_x {
+                for y in start_y..=end_y {
+                    openings.push((GridPoint::new(x, y), opening));
+                }
+            }
+        }
+
+        Ok(openings)
+    }
+
     fn interiors(&self) -> Result<Vec<Interior>, MapReaderError> {
         let interiors_image = self.interiors_image()?;
+        let openings = self.interiors_openings()?;
+        let mut rooms = self.interiors_rooms()?;
+        let tile_width = self.tile_width()? as f32;
+        let tile_height = self.tile_height()? as f32;
         let mut interiors = vec![];
 
+        if self.skipped(INTERIORS_ZONES_LAYER_NAME) {
+            return Ok(interiors);
+        }
+
         for object in self.interiors_zones_layer()?.objects() {
-            interiors.push(match object.shape {
+            let floors = match object.properties.get(INTERIOR_FLOORS_PROPERTY_KEY) {
+                Some(tiled::PropertyValue::IntValue(floors)) => {
+                    u8::try_from(*floors).map_err(|_| {
+                        MapReaderError::InvalidLayer(format!(
+                            "Object '{}' in layer '{}' in map {} has invalid {} value : {}",
+                            object.id(),
+                            INTERIORS_ZONES_LAYER_NAME,
+                            self.name,
+                            INTERIOR_FLOORS_PROPERTY_KEY,
+                            floors,
+                        ))
+                    })?
+                }
+                None => 1,
+                _ => {
+                    return Result::Err(MapReaderError::InvalidLayer(format!(
+                        "Object '{}' in layer '{}' in map {} should contains {} int property",
+                        object.id(),
+                        INTERIORS_ZONES_LAYER_NAME,
+                        self.name,
+                        INTERIOR_FLOORS_PROPERTY_KEY,
+                    )))
+                }
+            };
+            let mut interior = match object.shape {
                 tiled::ObjectShape::Rect { width, height } => Interior::new(
                     object.x,
                     object.y,
@@ -217,7 +432,65 @@ impl MapReader {
                         INTERIORS_ZONES_LAYER_NAME, self.name,
                     )))
                 }
-            })
+            };
+
+            // Openings are in walls, at the border of interior zone
+            let interior_openings = openings
+                .iter()
+                .filter(|(grid_point, _)| {
+                    let x = (grid_point.x as f32 + 0.5) * tile_width;
+                    let y = (grid_point.y as f32 + 0.5) * tile_height;
+                    x >= interior.x() - tile_width
+                        && x <= interior.x() + interior.width() + tile_width
+                        && y >= interior.y() - tile_height
+                        && y <= interior.y() + interior.height() + tile_height
+                })
+                .cloned()
+                .collect();
+            interior.set_layout(floors, interior_openings);
+
+            // Rooms belong to the interior containing their center
+            let (interior_rooms, others) = rooms.into_iter().partition(|room: &Room| {
+                interior.contains(&WorldPoint::new(
+                    room.x() + room.width() / 2.,
+                    room.y() + room.height() / 2.,
+                ))
+            });
+            rooms = others;
+            interior.set_rooms(interior_rooms);
+
+            if interiors
+                .iter()
+                .any(|other: &Interior| other.overlaps(&interior))
+            {
+                return Result::Err(MapReaderError::InvalidLayer(format!(
+                    "Object '{}' in layer '{}' in map {} overlaps another interior",
+                    object.id(),
+                    INTERIORS_ZONES_LAYER_NAME,
+                    self.name,
+                )));
+            }
+            if interior.rooms().iter().enumerate().any(|(i, room)| {
+                interior.rooms()[i + 1..]
+                    .iter()
+                    .any(|other| room.overlaps(other))
+            }) {
+                return Result::Err(MapReaderError::InvalidLayer(format!(
+                    "Layer '{}' in map {} contains overlapping rooms in interior '{}'",
+                    INTERIORS_ROOMS_LAYER_NAME,
+                    self.name,
+                    object.id(),
+                )));
+            }
+
+            interiors.push(interior);
+        }
+
+        if !rooms.is_empty() {
+            return Result::Err(MapReaderError::InvalidLayer(format!(
+                "Layer '{}' in map {} contains rooms outside of interiors",
+                INTERIORS_ROOMS_LAYER_NAME, self.name,
+            )));
         }
 
         Ok(interiors)
@@ -227,6 +500,10 @@ impl MapReader {
         let background_image = self.background_image()?;
         let mut spawn_zones = vec![];
 
+        if self.skipped(SPAWN_ZONES_LAYER_NAME) {
+            return Ok(spawn_zones);
+        }
+
         for object in self.spawn_zones_layer()?.objects() {
             let spawn_zone_name = SpawnZoneName::from_str(&object.name)?;
             if !spawn_zone_name.allowed_for_zone_object() {
@@ -261,6 +538,10 @@ impl MapReader {
     fn flags(&self) -> Result<Vec<Flag>, MapReaderError> {
         let mut flags = vec![];
 
+        if self.skipped(FLAGS_LAYER_NAME) {
+            return Ok(flags);
+        }
+
         for object in self.flags_layer()?.objects() {
             let flag_name = FlagName(object.name.clone());
 
@@ -312,6 +593,70 @@ impl MapReader {
         }
     }
 
+    /// Elevation layer is optional : map without it is flat
+    fn elevation_layer(&self) -> Result<Option<FiniteTileLayer<'_>>, MapReaderError> {
+        if !self
+            .map
+            .layers()
+            .any(|layer| layer.name == ELEVATION_LAYER_NAME)
+        {
+            return Ok(None);
+        }
+
+        match self.layer(ELEVATION_LAYER_NAME)?.layer_type() {
+            LayerType::TileLayer(layer) => match layer{
+                TileLayer::Finite(layer) => Ok(Some(layer)),
+                TileLayer::Infinite(_) => Result::Err(MapReaderError::InvalidLayer(format!(
+                    "Layer '{}' in map {} is an infinite tile layer, but on finite layer is supported",
+                    ELEVATION_LAYER_NAME, self.name,
+                ))),
+            },
+            _ => Result::Err(MapReaderError::InvalidLayer(format!(
+                "Layer '{}' in map {} is not an tile layer",
+                ELEVATION_LAYER_NAME, self.name,
+            ))),
+        }
+    }
+
+    /// Elevation (in meters) of each tile, from the elevation tiles property
+    fn elevations(&self) -> Result<Vec<f32>, MapReaderError> {
+        let width = self.width()?;
+        let height = self.height()?;
+        let mut elevations = vec![0.0; (width * height) as usize];
+        let layer = match self.elevation_layer()? {
+            Some(layer) => layer,
+            None => return Ok(elevations),
+        };
+
+        for y in 0..height.min(layer.height()) {
+            for x in 0..width.min(layer.width()) {
+                let layer_tile_data = match layer.get_tile_data(x as i32, y as i32) {
+                    Some(layer_tile_data) => layer_tile_data,
+                    None => continue,
+                };
+                let tileset = &self.map.tilesets()[layer_tile_data.tileset_index()];
+                let elevation = match tileset
+                    .get_tile(layer_tile_data.id())
+                    .and_then(|tile| tile.properties.get(TILE_ELEVATION_PROPERTY_KEY).cloned())
+                {
+                    Some(tiled::PropertyValue::FloatValue(elevation)) => elevation,
+                    Some(tiled::PropertyValue::IntValue(elevation)) => elevation as f32,
+                    _ => {
+                        return Result::Err(MapReaderError::TileError(format!(
+                        "Tile '{}' in elevation layer in map {} should contains {} number property",
+                        layer_tile_data.id(),
+                        self.name,
+                        TILE_ELEVATION_PROPERTY_KEY,
+                    )))
+                    }
+                };
+                elevations[(y * width + x) as usize] = elevation;
+            }
+        }
+
+        Ok(elevations)
+    }
+
     fn width(&self) -> Result<u32, MapReaderError> {
         Ok(self.terrain_layer()?.width())
     }
@@ -359,6 +704,7 @@ impl MapReader {
         let layer = self.terrain_layer()?;
         let terrain_tileset = self.terrain_tileset()?;
         let terrain_image = self.terrain_image()?;
+        let elevations = self.elevations()?;
         let mut tiles = vec![];
 
         for y in 0..layer.height() {
@@ -423,7 +769,8 @@ impl MapReader {
                     y,
                     tile_x,
                     tile_y,
-                )?;
+                )?
+                .elevation(elevations[(y * layer.width() + x) as usize]);
 
                 tiles.push(terrain_tile)
             }
@@ -478,6 +825,10 @@ impl MapReader {
     }
 
     fn decor(&self) -> Result<Decor, MapReaderError> {
+        if self.skipped(DECOR_LAYER_NAME) {
+            return Ok(Decor::new(vec![], vec![]));
+        }
+
         let decor_layer = self.decor_layer()?;
         let (_, tilesets_positions) = self.decor_tilesets()?;
         let images = self.decor_images()?;
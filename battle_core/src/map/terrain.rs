@@ -1,8 +1,14 @@
 use std::{fmt::Display, str::FromStr};
 
-use crate::{game::posture::Posture, types::Coverage};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
 
-#[derive(Clone)]
+use crate::{
+    game::{environment::Environment, posture::Posture},
+    types::Coverage,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, EnumIter, strum_macros::Display)]
 pub enum TileType {
     ShortGrass,
     MiddleGrass,
@@ -19,6 +25,7 @@ pub enum TileType {
     MiddleWoodLogs,
     Hedge,
     MiddleRock,
+    Crater,
 }
 
 impl FromStr for TileType {
@@ -41,14 +48,16 @@ impl FromStr for TileType {
             "MiddleWoodLogs" => Ok(Self::MiddleWoodLogs),
             "Hedge" => Ok(Self::Hedge),
             "MiddleRock" => Ok(Self::MiddleRock),
+            "Crater" => Ok(Self::Crater),
             _ => Result::Err(TerrainTileError::UnknownId(s.to_string())),
         }
     }
 }
 
 impl TileType {
-    pub fn pedestrian_cost(&self) -> i32 {
-        match self {
+    /// Cost to cross tile on foot, increased by environment (by example mud under rain)
+    pub fn pedestrian_cost(&self, environment: &Environment) -> i32 {
+        let cost = match self {
             TileType::ShortGrass => 10,
             TileType::MiddleGrass => 10,
             TileType::HighGrass => 10,
@@ -64,7 +73,9 @@ impl TileType {
             TileType::MiddleWoodLogs => 30,
             TileType::Hedge => 20,
             TileType::MiddleRock => 25,
-        }
+            TileType::Crater => 15,
+        };
+        (cost as f32 * environment.ground_factor(self)) as i32
     }
 
     pub fn block_vehicle(&self) -> bool {
@@ -79,13 +90,79 @@ impl TileType {
             | TileType::Underbrush
             | TileType::LightUnderbrush
             | TileType::MiddleWoodLogs
-            | TileType::Hedge => false,
+            | TileType::Hedge
+            | TileType::Crater => false,
             TileType::BrickWall | TileType::Trunk | TileType::DeepWater | TileType::MiddleRock => {
                 true
             }
         }
     }
 
+    /// Tile type after destruction (by explosion or vehicle), None if indestructible
+    pub fn degraded(&self) -> Option<TileType> {
+        match self {
+            // Wall becomes rubble
+            TileType::BrickWall => Some(TileType::MiddleRock),
+            // Tree falls
+            TileType::Trunk => Some(TileType::MiddleWoodLogs),
+            TileType::MiddleWoodLogs | TileType::Hedge => Some(TileType::Dirt),
+            TileType::ShortGrass
+            | TileType::MiddleGrass
+            | TileType::HighGrass
+            | TileType::Dirt
+            | TileType::Mud
+            | TileType::Concrete
+            | TileType::Water
+            | TileType::DeepWater
+            | TileType::Underbrush
+            | TileType::LightUnderbrush
+            | TileType::MiddleRock
+            | TileType::Crater => None,
+        }
+    }
+
+    /// Explosion on this tile leave a crater
+    pub fn craterable(&self) -> bool {
+        matches!(
+            self,
+            TileType::ShortGrass
+                | TileType::MiddleGrass
+                | TileType::HighGrass
+                | TileType::Dirt
+                | TileType::Mud
+                | TileType::Underbrush
+                | TileType::LightUnderbrush
+        )
+    }
+
+    /// Vehicle driving on this tile destroy it
+    pub fn crushable(&self) -> bool {
+        matches!(self, TileType::Hedge | TileType::MiddleWoodLogs)
+    }
+
+    /// Height (in meters) of the obstacle of this tile. Sight line passing enough above it
+    /// is not affected by its opacity.
+    pub fn obstacle_height(&self) -> f32 {
+        match self {
+            TileType::ShortGrass => 0.1,
+            TileType::MiddleGrass => 0.4,
+            TileType::HighGrass => 1.0,
+            TileType::Dirt => 0.0,
+            TileType::Mud => 0.0,
+            TileType::Concrete => 0.0,
+            TileType::BrickWall => 3.0,
+            TileType::Trunk => 10.0,
+            TileType::Water => 0.0,
+            TileType::DeepWater => 0.0,
+            TileType::Underbrush => 1.5,
+            TileType::LightUnderbrush => 0.8,
+            TileType::MiddleWoodLogs => 0.8,
+            TileType::Hedge => 1.5,
+            TileType::MiddleRock => 1.0,
+            TileType::Crater => 0.5,
+        }
+    }
+
     pub fn coverage(&self, posture: &Posture) -> Option<Coverage> {
         match posture {
             Posture::StandUp => match self {
@@ -104,6 +181,7 @@ impl TileType {
                 TileType::MiddleWoodLogs => Some(Coverage(0.2)),
                 TileType::Hedge => Some(Coverage(0.15)),
                 TileType::MiddleRock => Some(Coverage(0.2)),
+                TileType::Crater => None,
             },
             Posture::Flat => match self {
                 TileType::ShortGrass => None,
@@ -121,6 +199,7 @@ impl TileType {
                 TileType::MiddleWoodLogs => Some(Coverage(0.7)),
                 TileType::Hedge => Some(Coverage(0.15)),
                 TileType::MiddleRock => Some(Coverage(0.9)),
+                TileType::Crater => Some(Coverage(0.8)),
             },
         }
     }
@@ -149,6 +228,8 @@ pub struct TerrainTile {
     pub y: u32,
     pub tile_x: u32,
     pub tile_y: u32,
+    /// Height (in meters) of the ground
+    pub elevation: f32,
 }
 
 impl TerrainTile {
@@ -174,9 +255,15 @@ impl TerrainTile {
             y,
             tile_x,
             tile_y,
+            elevation: 0.0,
         })
     }
 
+    pub fn elevation(mut self, elevation: f32) -> Self {
+        self.elevation = elevation;
+        self
+    }
+
     pub fn type_(&self) -> &TileType {
         &self.type_
     }
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{game::Side, state::battle::message::BattleStateMessage};
+
+/// Messages exchanged by peers running the battle in lockstep mode. Server only relay
+/// them to all peers (including the sender).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum LockstepMessage {
+    /// Orders given by side players, to apply at given frame
+    Orders(Side, u64, Vec<BattleStateMessage>),
+    /// Battle state checksum of side peer at given frame
+    Checksum(Side, u64, u64),
+    /// Side peer stopped to run the battle
+    Finished(Side),
+    /// Local peer battle state differ from another peer since given frame
+    Desync(u64),
+}
+
+impl LockstepMessage {
+    /// Side of the peer which sent this message, if known
+    pub fn side(&self) -> Option<&Side> {
+        match self {
+            LockstepMessage::Orders(side, _, _)
+            | LockstepMessage::Checksum(side, _, _)
+            | LockstepMessage::Finished(side) => Some(side),
+            LockstepMessage::Desync(_) => None,
+        }
+    }
+}
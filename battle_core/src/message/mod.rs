@@ -4,12 +4,17 @@ use crate::{
     config::ChangeConfigMessage,
     deployment::Deployment,
     game::control::MapControl,
-    state::{battle::message::BattleStateMessage, client::ClientStateMessage},
+    state::{
+        battle::message::BattleStateMessage,
+        client::ClientStateMessage,
+        lobby::{BattleSetup, LobbyMessage, LobbyState},
+    },
     sync::BattleStateCopy,
 };
 
-use self::network::NetworkMessage;
+use self::{lockstep::LockstepMessage, network::NetworkMessage};
 
+pub mod lockstep;
 pub mod network;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -24,15 +29,20 @@ pub enum InputMessage {
     LoadDeployment(Deployment),
     LoadControl((MapControl, MapControl)),
     RequireCompleteSync,
-    SetBattleState(BattleStateCopy),
+    SetBattleState(Box<BattleStateCopy>),
     BattleState(BattleStateMessage),
     ChangeConfig(ChangeConfigMessage),
+    Lobby(LobbyMessage),
+    Lockstep(LockstepMessage),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum OutputMessage {
-    LoadFromCopy(BattleStateCopy),
+    LoadFromCopy(Box<BattleStateCopy>),
     BattleState(BattleStateMessage),
     ClientState(ClientStateMessage),
     ChangeConfig(ChangeConfigMessage),
+    Lobby(LobbyState),
+    BattleSetup(BattleSetup),
+    Lockstep(LockstepMessage),
 }
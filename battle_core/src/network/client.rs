@@ -1,4 +1,4 @@
-use crossbeam_channel::{Receiver, Sender};
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
 use serde::{Deserialize, Serialize};
 
 use std::{
@@ -6,24 +6,32 @@ use std::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
-    thread,
+    thread::{self, JoinHandle},
+    time::Duration,
 };
 
-use crate::message::{InputMessage, OutputMessage};
+use crate::{
+    config::NETWORK_POLL_TIMEOUT_MS,
+    message::{InputMessage, OutputMessage},
+};
 
-use super::error::NetworkError;
+use super::{
+    error::NetworkError,
+    transport::{Inbound, Outbound, Transport},
+};
 
 /// Network exchange logic
 /// Important note : zmq PUB socket have a limited buffer size,
 /// so we need to send messages by group instead one by one.
 pub struct Client {
-    req_address: String,
-    sub_address: String,
+    transport: Box<dyn Transport>,
     input_sender: Sender<Vec<InputMessage>>,
     input_receiver: Receiver<Vec<InputMessage>>,
     output_sender: Sender<Vec<OutputMessage>>,
     output_receiver: Receiver<Vec<OutputMessage>>,
     sync_required: Arc<AtomicBool>,
+    stop_required: Arc<AtomicBool>,
+    handles: Vec<JoinHandle<()>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -32,34 +40,47 @@ struct Envelope {
     messages: Vec<OutputMessage>,
 }
 
-// TODO : When server/client is closing : end threads properly
 impl Client {
     pub fn new(
-        req_address: String,
-        sub_address: String,
+        transport: Box<dyn Transport>,
         input_sender: Sender<Vec<InputMessage>>,
         input_receiver: Receiver<Vec<InputMessage>>,
         output_sender: Sender<Vec<OutputMessage>>,
         output_receiver: Receiver<Vec<OutputMessage>>,
         sync_required: Arc<AtomicBool>,
+        stop_required: Arc<AtomicBool>,
     ) -> Self {
         Self {
-            req_address,
-            sub_address,
+            transport,
             input_sender,
             input_receiver,
             output_sender,
             output_receiver,
             sync_required,
+            stop_required,
+            handles: vec![],
         }
     }
 
     pub fn connect(&mut self) -> Result<(), NetworkError> {
-        self.start_req()?;
-        self.start_sub()?;
+        let (outbound, inbound) = self.transport.connect()?;
+        let req = self.start_req(outbound);
+        self.handles.push(req);
+        let sub = self.start_sub(inbound);
+        self.handles.push(sub);
         Ok(())
     }
 
+    /// Wait client threads end. Threads end when stop is required.
+    pub fn join(&mut self) {
+        for handle in self.handles.drain(..) {
+            let name = handle.thread().name().unwrap_or("?").to_string();
+            if handle.join().is_err() {
+                println!("Client thread {} panicked", name);
+            }
+        }
+    }
+
     /// Return received messages from remote :
     ///  - As server : messages from clients
     ///  - As client : messages from server
@@ -71,22 +92,27 @@ impl Client {
         messages
     }
 
-    fn start_req(&self) -> Result<(), NetworkError> {
+    fn start_req(&self, mut outbound: Box<dyn Outbound>) -> JoinHandle<()> {
         let thread_send_receiver = self.input_receiver.clone();
-        let server_rep_address = self.req_address.clone();
-
-        let zmq_context = zmq::Context::new();
-        let socket = zmq_context.socket(zmq::REQ)?;
-        socket.connect(&server_rep_address)?;
+        let stop_required_ = self.stop_required.clone();
 
         thread::Builder::new()
             .name("client_req".to_string())
             .spawn(move || {
+                let timeout = Duration::from_millis(NETWORK_POLL_TIMEOUT_MS as u64);
                 loop {
                     // Wait messages to send
-                    let messages: Vec<InputMessage> = thread_send_receiver
-                        .recv()
-                        .expect("Channel was closed when try to receive messages to send");
+                    let messages: Vec<InputMessage> =
+                        match thread_send_receiver.recv_timeout(timeout) {
+                            Ok(messages) => messages,
+                            Err(RecvTimeoutError::Timeout) => {
+                                if stop_required_.load(Ordering::Relaxed) {
+                                    break;
+                                }
+                                continue;
+                            }
+                            Err(RecvTimeoutError::Disconnected) => break,
+                        };
 
                     // Encode messages to send
                     let messages_bytes = match bincode::serialize(&messages) {
@@ -98,49 +124,39 @@ impl Client {
                     };
 
                     // Send messages to server
-                    if let Err(error) = socket.send(messages_bytes, 0) {
-                        println!("Error while sending messages : {}", error);
-                        // Don't expect a response if send error
-                        continue;
+                    match outbound.send(&messages_bytes) {
+                        Ok(_) => {}
+                        Err(NetworkError::Closed) => break,
+                        Err(error) => println!("Error while sending messages : {}", error),
                     };
-
-                    let _response = match socket.recv_bytes(0) {
-                        Ok(response) => response,
-                        Err(error) => {
-                            println!("Error while receiving server REP : {}", error);
-                            // Don't expect a response if send error
-                            continue;
-                        }
-                    };
-
-                    // Don't check the response content. The server ACK is only required here.
                 }
-            })
-            .unwrap();
 
-        Ok(())
+                println!("Client REQ finished")
+            })
+            .unwrap()
     }
 
-    fn start_sub(&self) -> Result<(), NetworkError> {
+    fn start_sub(&self, mut inbound: Box<dyn Inbound>) -> JoinHandle<()> {
         let thread_receive_sender = self.output_sender.clone();
         let thread_input_sender = self.input_sender.clone();
-        let server_pub_address = self.sub_address.clone();
-
         let mut last_counter: u64 = 0;
-        let zmq_context = zmq::Context::new();
-        let socket = zmq_context.socket(zmq::SUB)?;
-        socket.connect(&server_pub_address)?;
-        // TODO : subscribe with client ID and ALL (to receive all messages except global sync of other clients)
-        socket.set_subscribe(b"")?;
 
         let sync_required_ = self.sync_required.clone();
+        let stop_required_ = self.stop_required.clone();
         thread::Builder::new()
             .name("client_sub".to_string())
             .spawn(move || {
+                let timeout = Duration::from_millis(NETWORK_POLL_TIMEOUT_MS as u64);
                 loop {
+                    if stop_required_.load(Ordering::Relaxed) {
+                        break;
+                    }
+
                     // Receive server messages
-                    let envelope_bytes = match socket.recv_bytes(0) {
-                        Ok(envelope_bytes) => envelope_bytes,
+                    let envelope_bytes = match inbound.receive(timeout) {
+                        Ok(Some(envelope_bytes)) => envelope_bytes,
+                        Ok(None) => continue,
+                        Err(NetworkError::Closed) => break,
                         Err(error) => {
                             println!("Error while receiving server messages : {}", error);
                             // Waiting again if receive error
@@ -158,25 +174,30 @@ impl Client {
                     };
 
                     // Send through channel the decoded messages
-                    thread_receive_sender
-                        .send(envelope.messages)
-                        .expect("Channel was closed when try to send received messages");
+                    if thread_receive_sender.send(envelope.messages).is_err() {
+                        println!("Channel was closed when try to send received messages");
+                        break;
+                    }
 
                     // Check no message(s) was lost, if yes, require sync from server
                     if last_counter != 0 && last_counter + 1 != envelope.id {
                         println!("WARNING :: Network :: message(s) lost, require global Sync");
                         sync_required_.swap(true, Ordering::Relaxed);
-                        thread_input_sender
+                        if thread_input_sender
                             .send(vec![InputMessage::RequireCompleteSync])
-                            .expect("Channel was closed when try to send server sync requirement");
+                            .is_err()
+                        {
+                            println!("Channel was closed when try to send server sync requirement");
+                            break;
+                        }
                     }
 
                     // Update the last counter
                     last_counter = envelope.id;
                 }
-            })
-            .unwrap();
 
-        Ok(())
+                println!("Client SUB finished")
+            })
+            .unwrap()
     }
 }
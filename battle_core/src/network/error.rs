@@ -5,6 +5,7 @@ pub enum NetworkError {
     NetworkError(String),
     ReceiveError(String),
     SendError(String),
+    Closed,
 }
 
 impl From<zmq::Error> for NetworkError {
@@ -13,6 +14,12 @@ impl From<zmq::Error> for NetworkError {
     }
 }
 
+impl From<std::io::Error> for NetworkError {
+    fn from(error: std::io::Error) -> Self {
+        Self::NetworkError(error.to_string())
+    }
+}
+
 impl Display for NetworkError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -23,6 +30,7 @@ impl Display for NetworkError {
                 f.write_str(&format!("ReceiveError: {}", message))
             }
             NetworkError::SendError(message) => f.write_str(&format!("SendError: {}", message)),
+            NetworkError::Closed => f.write_str("Closed"),
         }
     }
 }
@@ -1,4 +1,4 @@
-use crossbeam_channel::{Receiver, Sender};
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
 use serde::{Deserialize, Serialize};
 
 use std::{
@@ -6,22 +6,35 @@ use std::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
-    thread,
+    thread::{self, JoinHandle},
+    time::Duration,
 };
 
-use crate::message::{network::NetworkMessage, InputMessage, Message, OutputMessage};
+use crate::{
+    config::NETWORK_POLL_TIMEOUT_MS,
+    game::Side,
+    message::{InputMessage, OutputMessage},
+    state::battle::{
+        message::BattleStateMessage,
+        phase::{EndReason, Phase, Victorious},
+    },
+};
 
-use super::error::NetworkError;
+use super::{
+    error::NetworkError,
+    transport::{Inbound, Outbound, Transport},
+};
 
-/// Network exchange logic
+/// Network exchange logic. Server can listen on several transports at the same time
+/// (by example, in process for embedded gui and zmq for remote clients).
 /// Important note : zmq PUB socket have a limited buffer size,
 /// so we need to send messages by group instead one by one.
 pub struct Server {
-    rep_address: String,
-    pub_address: String,
+    transports: Vec<Box<dyn Transport>>,
     output_receiver: Receiver<Vec<OutputMessage>>,
     input_sender: Sender<Vec<InputMessage>>,
     stop_required: Arc<AtomicBool>,
+    handles: Vec<JoinHandle<()>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -36,52 +49,66 @@ impl Envelope {
     }
 }
 
-// TODO : When server/client is closing : end threads properly
 impl Server {
     pub fn new(
-        rep_address: String,
-        pub_address: String,
+        transports: Vec<Box<dyn Transport>>,
         output_receiver: Receiver<Vec<OutputMessage>>,
         input_sender: Sender<Vec<InputMessage>>,
         stop_required: Arc<AtomicBool>,
     ) -> Self {
         Self {
-            rep_address,
-            pub_address,
+            transports,
             output_receiver,
             input_sender,
             stop_required,
+            handles: vec![],
         }
     }
 
-    pub fn serve(&self) -> Result<(), NetworkError> {
-        self.start_rep()?;
-        self.start_pub()?;
+    pub fn serve(&mut self) -> Result<(), NetworkError> {
+        let mut outbounds = vec![];
+        for transport in &self.transports {
+            let (inbound, outbound) = transport.listen()?;
+            let rep = self.start_rep(inbound);
+            self.handles.push(rep);
+            outbounds.push(outbound);
+        }
+
+        let pub_ = self.start_pub(outbounds);
+        self.handles.push(pub_);
         Ok(())
     }
 
-    fn start_rep(&self) -> Result<(), NetworkError> {
-        let thread_input_sender = self.input_sender.clone();
-        let server_rep_address = self.rep_address.clone();
-
-        let zmq_context = zmq::Context::new();
-        let socket = zmq_context.socket(zmq::REP)?;
-        socket.bind(&server_rep_address)?;
+    /// Wait server threads end. Threads end when stop is required : pending messages
+    /// are sent before sockets are closed.
+    pub fn join(&mut self) {
+        for handle in self.handles.drain(..) {
+            let name = handle.thread().name().unwrap_or("?").to_string();
+            if handle.join().is_err() {
+                println!("Server thread {} panicked", name);
+            }
+        }
+    }
 
-        let ok = bincode::serialize(&Message::Network(NetworkMessage::Acknowledge)).unwrap();
+    fn start_rep(&self, mut inbound: Box<dyn Inbound>) -> JoinHandle<()> {
+        let thread_input_sender = self.input_sender.clone();
         let stop_required_ = self.stop_required.clone();
+
         thread::Builder::new()
             .name("server_rep".to_string())
             .spawn(move || {
+                let timeout = Duration::from_millis(NETWORK_POLL_TIMEOUT_MS as u64);
                 loop {
-                    // Receive client REQ messages bytes
-                    let messages_bytes = match socket.recv_bytes(0) {
-                        Ok(message_bytes) => message_bytes,
-                        Err(error) => {
-                            if stop_required_.load(Ordering::Relaxed) {
-                                break;
-                            }
+                    if stop_required_.load(Ordering::Relaxed) {
+                        break;
+                    }
 
+                    // Receive client messages bytes
+                    let messages_bytes = match inbound.receive(timeout) {
+                        Ok(Some(message_bytes)) => message_bytes,
+                        Ok(None) => continue,
+                        Err(NetworkError::Closed) => break,
+                        Err(error) => {
                             println!("Error while receiving bytes : {}", error);
                             continue;
                         }
@@ -96,74 +123,223 @@ impl Server {
                         }
                     };
 
-                    // Send client expected acknowledgement
-                    socket.send(&ok, 0).unwrap_or_else(|e| {
-                        println!("Error while sending acknowledgement : {}", e)
-                    });
-
                     // Send through channel the decoded messages
-                    thread_input_sender.send(messages).unwrap_or_else(|_| {
-                        panic!("Channel was closed when try to send received messages")
-                    });
+                    if thread_input_sender.send(messages).is_err() {
+                        println!("Channel was closed when try to send received messages");
+                        break;
+                    }
                 }
 
                 println!("Server REP finished")
             })
-            .unwrap();
-
-        Ok(())
+            .unwrap()
     }
 
-    fn start_pub(&self) -> Result<(), NetworkError> {
+    fn start_pub(&self, outbounds: Vec<Box<dyn Outbound>>) -> JoinHandle<()> {
         let thread_output_receiver = self.output_receiver.clone();
-        let server_pub_address = self.pub_address.clone();
-
-        let mut pub_counter: u64 = 0;
-        let zmq_context = zmq::Context::new();
-        let socket = zmq_context.socket(zmq::PUB)?;
-        socket.bind(&server_pub_address)?;
-
         let stop_required_ = self.stop_required.clone();
+
         thread::Builder::new()
             .name("server_pub".to_string())
             .spawn(move || {
-                loop {
-                    // Increment counter to permit client to know if some messages have been lost
-                    pub_counter += 1;
+                let mut publisher = Publisher::new(outbounds);
+                let timeout = Duration::from_millis(NETWORK_POLL_TIMEOUT_MS as u64);
 
+                loop {
                     // Retrieve messages to sent to clients
-                    let messages: Vec<OutputMessage> = match thread_output_receiver.recv() {
-                        Ok(messages) => messages,
-                        Err(error) => {
-                            if !stop_required_.load(Ordering::Relaxed) {
-                                println!(
-                                    "Channel was closed when try to receive messages to send : {}",
-                                    error
-                                )
+                    let messages: Vec<OutputMessage> =
+                        match thread_output_receiver.recv_timeout(timeout) {
+                            Ok(messages) => messages,
+                            Err(RecvTimeoutError::Timeout) => {
+                                if stop_required_.load(Ordering::Relaxed) {
+                                    break;
+                                }
+                                continue;
                             }
-                            break;
-                        }
-                    };
+                            Err(RecvTimeoutError::Disconnected) => break,
+                        };
 
-                    // Prepare the data to send to clients
-                    let envelope = Envelope::new(pub_counter, messages);
-                    let messages_bytes = match bincode::serialize(&envelope) {
-                        Ok(messages_bytes) => messages_bytes,
-                        Err(error) => {
-                            println!("Error while encoding messages to send : {}", error);
-                            continue;
-                        }
-                    };
+                    publisher.publish(messages);
+                }
 
-                    // Finally send messages to clients
-                    if let Err(error) = socket.send(&messages_bytes, 0) {
-                        println!("Error while sending messages : {}", error);
-                    };
+                // Flush messages produced before stop
+                while let Ok(messages) = thread_output_receiver.try_recv() {
+                    publisher.publish(messages);
+                }
+
+                // Inform clients the battle will not continue
+                if publisher.battle_running {
+                    publisher.publish(vec![OutputMessage::BattleState(
+                        BattleStateMessage::SetPhase(Phase::End(
+                            Victorious(Side::All),
+                            EndReason::Aborted,
+                        )),
+                    )]);
                 }
+
                 println!("Server PUB finished");
             })
-            .unwrap();
+            .unwrap()
+    }
+}
 
-        Ok(())
+struct Publisher {
+    outbounds: Vec<Box<dyn Outbound>>,
+    counter: u64,
+    battle_running: bool,
+}
+
+impl Publisher {
+    fn new(outbounds: Vec<Box<dyn Outbound>>) -> Self {
+        Self {
+            outbounds,
+            counter: 0,
+            battle_running: false,
+        }
+    }
+
+    fn publish(&mut self, messages: Vec<OutputMessage>) {
+        // Increment counter to permit client to know if some messages have been lost
+        self.counter += 1;
+
+        for message in &messages {
+            match message {
+                OutputMessage::BattleSetup(_) => self.battle_running = true,
+                OutputMessage::BattleState(BattleStateMessage::SetPhase(Phase::End(_, _)))
+                | OutputMessage::Lobby(_) => self.battle_running = false,
+                _ => {}
+            }
+        }
+
+        // Prepare the data to send to clients
+        let envelope = Envelope::new(self.counter, messages);
+        let messages_bytes = match bincode::serialize(&envelope) {
+            Ok(messages_bytes) => messages_bytes,
+            Err(error) => {
+                println!("Error while encoding messages to send : {}", error);
+                return;
+            }
+        };
+
+        // Finally send messages to clients
+        for outbound in self.outbounds.iter_mut() {
+            if let Err(error) = outbound.send(&messages_bytes) {
+                println!("Error while sending messages : {}", error);
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossbeam_channel::unbounded;
+    use rstest::*;
+
+    use crate::network::{
+        client::Client,
+        transport::{channel::ChannelTransport, tcp::TcpTransport, zmq::ZmqTransport},
+    };
+
+    use super::*;
+
+    // Server and client transports
+    type Transports = (Box<dyn Transport>, Box<dyn Transport>);
+
+    fn zmq() -> Transports {
+        (
+            Box::new(ZmqTransport::new(
+                "tcp://127.0.0.1:42655",
+                "tcp://127.0.0.1:42656",
+            )),
+            Box::new(ZmqTransport::new(
+                "tcp://127.0.0.1:42655",
+                "tcp://127.0.0.1:42656",
+            )),
+        )
+    }
+
+    fn channel() -> Transports {
+        let transport = ChannelTransport::new();
+        (Box::new(transport.clone()), Box::new(transport))
+    }
+
+    fn tcp() -> Transports {
+        (
+            Box::new(TcpTransport::new("127.0.0.1:42657")),
+            Box::new(TcpTransport::new("127.0.0.1:42657")),
+        )
+    }
+
+    #[rstest]
+    #[case(zmq)]
+    #[case(channel)]
+    #[case(tcp)]
+    fn server_can_be_restarted_on_same_transport(#[case] transports: fn() -> Transports) {
+        for _ in 0..2 {
+            // Given
+            let (server_transport, client_transport) = transports();
+            let stop_required = Arc::new(AtomicBool::new(false));
+            let (server_input_sender, server_input_receiver) = unbounded();
+            let (server_output_sender, server_output_receiver) = unbounded();
+            let (client_input_sender, client_input_receiver) = unbounded();
+            let (client_output_sender, client_output_receiver) = unbounded();
+            let mut server = Server::new(
+                vec![server_transport],
+                server_output_receiver,
+                server_input_sender,
+                stop_required.clone(),
+            );
+            let mut client = Client::new(
+                client_transport,
+                client_input_sender.clone(),
+                client_input_receiver,
+                client_output_sender,
+                client_output_receiver.clone(),
+                Arc::new(AtomicBool::new(false)),
+                stop_required.clone(),
+            );
+
+            // When
+            server.serve().unwrap();
+            client.connect().unwrap();
+            client_input_sender
+                .send(vec![InputMessage::RequireCompleteSync])
+                .unwrap();
+
+            // Then
+            let received = server_input_receiver
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap();
+            assert!(matches!(
+                received.as_slice(),
+                [InputMessage::RequireCompleteSync]
+            ));
+            // Client subscription can be effective after first publications
+            let mut received = None;
+            for _ in 0..50 {
+                server_output_sender
+                    .send(vec![OutputMessage::BattleState(
+                        BattleStateMessage::SetAReady(true),
+                    )])
+                    .unwrap();
+                if let Ok(messages) =
+                    client_output_receiver.recv_timeout(Duration::from_millis(100))
+                {
+                    received = Some(messages);
+                    break;
+                }
+            }
+            assert!(matches!(
+                received.as_deref(),
+                Some([OutputMessage::BattleState(BattleStateMessage::SetAReady(
+                    true
+                ))])
+            ));
+
+            stop_required.store(true, Ordering::Relaxed);
+            drop(server_output_sender);
+            client.join();
+            server.join();
+        }
     }
 }
@@ -0,0 +1,93 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender};
+
+use crate::network::error::NetworkError;
+
+use super::{Connected, Inbound, Listening, Outbound, Transport};
+
+/// In process transport : server and clients are in the same process. Clone it to give
+/// it to server and clients.
+#[derive(Clone)]
+pub struct ChannelTransport {
+    inputs_sender: Sender<Vec<u8>>,
+    inputs_receiver: Receiver<Vec<u8>>,
+    subscribers: Arc<Mutex<Vec<Sender<Vec<u8>>>>>,
+}
+
+impl ChannelTransport {
+    pub fn new() -> Self {
+        let (inputs_sender, inputs_receiver) = unbounded();
+        Self {
+            inputs_sender,
+            inputs_receiver,
+            subscribers: Arc::new(Mutex::new(vec![])),
+        }
+    }
+}
+
+impl Default for ChannelTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transport for ChannelTransport {
+    fn listen(&self) -> Result<Listening, NetworkError> {
+        Ok((
+            Box::new(ChannelInbound(self.inputs_receiver.clone())),
+            Box::new(ChannelPublisher(self.subscribers.clone())),
+        ))
+    }
+
+    fn connect(&self) -> Result<Connected, NetworkError> {
+        let (sender, receiver) = unbounded();
+        self.subscribers
+            .lock()
+            .map_err(|error| NetworkError::NetworkError(error.to_string()))?
+            .push(sender);
+
+        Ok((
+            Box::new(ChannelOutbound(self.inputs_sender.clone())),
+            Box::new(ChannelInbound(receiver)),
+        ))
+    }
+}
+
+pub struct ChannelInbound(Receiver<Vec<u8>>);
+
+impl Inbound for ChannelInbound {
+    fn receive(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, NetworkError> {
+        match self.0.recv_timeout(timeout) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(NetworkError::Closed),
+        }
+    }
+}
+
+pub struct ChannelOutbound(Sender<Vec<u8>>);
+
+impl Outbound for ChannelOutbound {
+    fn send(&mut self, bytes: &[u8]) -> Result<(), NetworkError> {
+        self.0
+            .send(bytes.to_vec())
+            .map_err(|_| NetworkError::Closed)
+    }
+}
+
+pub struct ChannelPublisher(Arc<Mutex<Vec<Sender<Vec<u8>>>>>);
+
+impl Outbound for ChannelPublisher {
+    fn send(&mut self, bytes: &[u8]) -> Result<(), NetworkError> {
+        // Disconnected clients are forgotten
+        self.0
+            .lock()
+            .map_err(|error| NetworkError::NetworkError(error.to_string()))?
+            .retain(|subscriber| subscriber.send(bytes.to_vec()).is_ok());
+        Ok(())
+    }
+}
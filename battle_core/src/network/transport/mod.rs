@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use super::error::NetworkError;
+
+pub mod channel;
+pub mod tcp;
+pub mod zmq;
+
+/// Server side receiver of clients messages and sender of messages to all clients
+pub type Listening = (Box<dyn Inbound>, Box<dyn Outbound>);
+/// Client side sender of messages to server and receiver of server messages
+pub type Connected = (Box<dyn Outbound>, Box<dyn Inbound>);
+
+/// Way to exchange messages bytes between server and clients. Server side listen and
+/// client side connect. Messages are grouped by the server and client (see `Envelope`),
+/// transports only move bytes.
+pub trait Transport: Send {
+    fn listen(&self) -> Result<Listening, NetworkError>;
+
+    fn connect(&self) -> Result<Connected, NetworkError>;
+}
+
+pub trait Inbound: Send {
+    /// Wait next received bytes. Return None if nothing received before timeout.
+    fn receive(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, NetworkError>;
+}
+
+pub trait Outbound: Send {
+    fn send(&mut self, bytes: &[u8]) -> Result<(), NetworkError>;
+}
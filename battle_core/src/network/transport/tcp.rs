@@ -0,0 +1,278 @@
+use std::{
+    io::{ErrorKind, Read, Write},
+    net::{Shutdown, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender};
+
+use crate::{config::NETWORK_POLL_TIMEOUT_MS, network::error::NetworkError};
+
+use super::{Connected, Inbound, Listening, Outbound, Transport};
+
+// Protect against corrupted frame length
+const MAXIMUM_FRAME_LENGTH: usize = 256 * 1024 * 1024;
+// Client not reading its frames in this delay is disconnected (to not block others)
+const WRITE_TIMEOUT_MS: u64 = 1000;
+
+/// Transport based on plain TCP streams. Each message bytes are sent as a frame prefixed
+/// by its length.
+pub struct TcpTransport {
+    address: String,
+}
+
+impl TcpTransport {
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: address.to_string(),
+        }
+    }
+}
+
+impl Transport for TcpTransport {
+    fn listen(&self) -> Result<Listening, NetworkError> {
+        let listener = TcpListener::bind(&self.address)?;
+        listener.set_nonblocking(true)?;
+
+        let (inputs_sender, inputs_receiver) = unbounded();
+        let clients: Arc<Mutex<Vec<TcpClient>>> = Arc::new(Mutex::new(vec![]));
+        let closed = Arc::new(AtomicBool::new(false));
+
+        let clients_ = clients.clone();
+        let closed_ = closed.clone();
+        let handle = thread::Builder::new()
+            .name("tcp_listener".to_string())
+            .spawn(move || {
+                let poll = Duration::from_millis(NETWORK_POLL_TIMEOUT_MS as u64);
+                while !closed_.load(Ordering::Relaxed) {
+                    let stream = match listener.accept() {
+                        Ok((stream, _)) => stream,
+                        Err(error) if error.kind() == ErrorKind::WouldBlock => {
+                            thread::sleep(poll);
+                            continue;
+                        }
+                        Err(error) => {
+                            println!("Error while accepting tcp connection : {}", error);
+                            continue;
+                        }
+                    };
+
+                    if let Err(error) = register(&stream, &clients_, inputs_sender.clone()) {
+                        println!("Error while registering tcp connection : {}", error);
+                    }
+                }
+            })?;
+
+        Ok((
+            Box::new(TcpServerInbound {
+                receiver: inputs_receiver,
+                closed,
+                handle: Some(handle),
+                clients: clients.clone(),
+            }),
+            Box::new(TcpPublisher(clients)),
+        ))
+    }
+
+    fn connect(&self) -> Result<Connected, NetworkError> {
+        let stream = TcpStream::connect(&self.address)?;
+        let (sender, receiver) = unbounded();
+        let reader = read_frames(stream.try_clone()?, sender)?;
+
+        Ok((
+            Box::new(TcpOutbound(stream.try_clone()?)),
+            Box::new(TcpInbound {
+                receiver,
+                client: TcpClient::new(stream, reader),
+            }),
+        ))
+    }
+}
+
+/// Connected stream with the thread reading its frames
+struct TcpClient {
+    stream: TcpStream,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl TcpClient {
+    fn new(stream: TcpStream, reader: JoinHandle<()>) -> Self {
+        Self {
+            stream,
+            reader: Some(reader),
+        }
+    }
+
+    /// Shutdown the connection and wait its reader thread finish
+    fn close(&mut self) {
+        let _ = self.stream.shutdown(Shutdown::Both);
+        if let Some(reader) = self.reader.take() {
+            if reader.join().is_err() {
+                println!("Tcp reader thread panicked");
+            }
+        }
+    }
+}
+
+fn register(
+    stream: &TcpStream,
+    clients: &Arc<Mutex<Vec<TcpClient>>>,
+    sender: Sender<Vec<u8>>,
+) -> Result<(), NetworkError> {
+    stream.set_nonblocking(false)?;
+    stream.set_nodelay(true)?;
+    stream.set_write_timeout(Some(Duration::from_millis(WRITE_TIMEOUT_MS)))?;
+    let reader = read_frames(stream.try_clone()?, sender)?;
+    clients
+        .lock()
+        .map_err(|error| NetworkError::NetworkError(error.to_string()))?
+        .push(TcpClient::new(stream.try_clone()?, reader));
+    Ok(())
+}
+
+/// Read frames from stream until it is closed (by remote or by local shutdown)
+fn read_frames(
+    mut stream: TcpStream,
+    sender: Sender<Vec<u8>>,
+) -> Result<JoinHandle<()>, NetworkError> {
+    Ok(thread::Builder::new()
+        .name("tcp_reader".to_string())
+        .spawn(move || loop {
+            let mut length = [0; 4];
+            if stream.read_exact(&mut length).is_err() {
+                break;
+            }
+
+            let length = u32::from_be_bytes(length) as usize;
+            if length > MAXIMUM_FRAME_LENGTH {
+                println!("Tcp frame too long ({} bytes), close connection", length);
+                break;
+            }
+
+            let mut bytes = vec![0; length];
+            if stream.read_exact(&mut bytes).is_err() || sender.send(bytes).is_err() {
+                break;
+            }
+        })?)
+}
+
+fn write_frame(stream: &mut TcpStream, bytes: &[u8]) -> Result<(), NetworkError> {
+    stream.write_all(&(bytes.len() as u32).to_be_bytes())?;
+    stream.write_all(bytes)?;
+    Ok(())
+}
+
+fn receive(
+    receiver: &Receiver<Vec<u8>>,
+    timeout: Duration,
+) -> Result<Option<Vec<u8>>, NetworkError> {
+    match receiver.recv_timeout(timeout) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(RecvTimeoutError::Timeout) => Ok(None),
+        Err(RecvTimeoutError::Disconnected) => Err(NetworkError::Closed),
+    }
+}
+
+struct TcpServerInbound {
+    receiver: Receiver<Vec<u8>>,
+    closed: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+    clients: Arc<Mutex<Vec<TcpClient>>>,
+}
+
+impl Inbound for TcpServerInbound {
+    fn receive(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, NetworkError> {
+        // Listener thread keep a sender, so channel can't be disconnected here
+        match receive(&self.receiver, timeout) {
+            Err(NetworkError::Closed) => Ok(None),
+            result => result,
+        }
+    }
+}
+
+impl Drop for TcpServerInbound {
+    fn drop(&mut self) {
+        // Stop accepting connections and release the listening port
+        self.closed.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                println!("Tcp listener thread panicked");
+            }
+        }
+
+        // Listener is stopped, so no more client can be registered
+        if let Ok(mut clients) = self.clients.lock() {
+            for client in clients.iter_mut() {
+                client.close();
+            }
+            clients.clear();
+        }
+    }
+}
+
+struct TcpPublisher(Arc<Mutex<Vec<TcpClient>>>);
+
+impl Outbound for TcpPublisher {
+    fn send(&mut self, bytes: &[u8]) -> Result<(), NetworkError> {
+        // Disconnected or too slow clients are forgotten
+        self.0
+            .lock()
+            .map_err(|error| NetworkError::NetworkError(error.to_string()))?
+            .retain_mut(|client| {
+                let sent = write_frame(&mut client.stream, bytes).is_ok();
+                if !sent {
+                    client.close();
+                }
+                sent
+            });
+        Ok(())
+    }
+}
+
+impl Drop for TcpPublisher {
+    fn drop(&mut self) {
+        // Shutdown connections make their reader threads finish
+        if let Ok(mut clients) = self.0.lock() {
+            for client in clients.iter_mut() {
+                client.close();
+            }
+            clients.clear();
+        }
+    }
+}
+
+struct TcpOutbound(TcpStream);
+
+impl Outbound for TcpOutbound {
+    fn send(&mut self, bytes: &[u8]) -> Result<(), NetworkError> {
+        write_frame(&mut self.0, bytes)
+    }
+}
+
+impl Drop for TcpOutbound {
+    fn drop(&mut self) {
+        let _ = self.0.shutdown(Shutdown::Both);
+    }
+}
+
+struct TcpInbound {
+    receiver: Receiver<Vec<u8>>,
+    client: TcpClient,
+}
+
+impl Inbound for TcpInbound {
+    fn receive(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, NetworkError> {
+        receive(&self.receiver, timeout)
+    }
+}
+
+impl Drop for TcpInbound {
+    fn drop(&mut self) {
+        self.client.close();
+    }
+}
@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use crate::{
+    config::{NETWORK_ACK_TIMEOUT_MS, NETWORK_LINGER_MS},
+    message::{network::NetworkMessage, Message},
+    network::error::NetworkError,
+};
+
+use super::{Connected, Inbound, Listening, Outbound, Transport};
+
+/// Transport based on zmq REQ/REP (clients to server) and PUB/SUB (server to clients) sockets
+/// Important note : zmq PUB socket have a limited buffer size,
+/// so we need to send messages by group instead one by one.
+pub struct ZmqTransport {
+    rep_address: String,
+    pub_address: String,
+}
+
+impl ZmqTransport {
+    pub fn new(rep_address: &str, pub_address: &str) -> Self {
+        Self {
+            rep_address: rep_address.to_string(),
+            pub_address: pub_address.to_string(),
+        }
+    }
+}
+
+impl Transport for ZmqTransport {
+    fn listen(&self) -> Result<Listening, NetworkError> {
+        let zmq_context = zmq::Context::new();
+
+        let rep = zmq_context.socket(zmq::REP)?;
+        rep.set_linger(NETWORK_LINGER_MS)?;
+        rep.bind(&self.rep_address)?;
+
+        let pub_ = zmq_context.socket(zmq::PUB)?;
+        pub_.set_linger(NETWORK_LINGER_MS)?;
+        pub_.bind(&self.pub_address)?;
+
+        let ack = bincode::serialize(&Message::Network(NetworkMessage::Acknowledge))
+            .expect("Acknowledge message must be serializable");
+        Ok((
+            Box::new(ZmqRep { socket: rep, ack }),
+            Box::new(ZmqPub(pub_)),
+        ))
+    }
+
+    fn connect(&self) -> Result<Connected, NetworkError> {
+        let zmq_context = zmq::Context::new();
+
+        let req = zmq_context.socket(zmq::REQ)?;
+        // Permit to send again if an acknowledgement has not been received
+        req.set_req_relaxed(true)?;
+        req.set_req_correlate(true)?;
+        req.set_rcvtimeo(NETWORK_ACK_TIMEOUT_MS)?;
+        req.set_linger(NETWORK_LINGER_MS)?;
+        req.connect(&self.rep_address)?;
+
+        let sub = zmq_context.socket(zmq::SUB)?;
+        sub.set_linger(NETWORK_LINGER_MS)?;
+        sub.connect(&self.pub_address)?;
+        // TODO : subscribe with client ID and ALL (to receive all messages except global sync of other clients)
+        sub.set_subscribe(b"")?;
+
+        Ok((Box::new(ZmqReq(req)), Box::new(ZmqSub(sub))))
+    }
+}
+
+fn receive(socket: &zmq::Socket, timeout: Duration) -> Result<Option<Vec<u8>>, NetworkError> {
+    socket.set_rcvtimeo(timeout.as_millis() as i32)?;
+    match socket.recv_bytes(0) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(zmq::Error::EAGAIN) => Ok(None),
+        Err(error) => Err(NetworkError::ReceiveError(error.to_string())),
+    }
+}
+
+struct ZmqRep {
+    socket: zmq::Socket,
+    ack: Vec<u8>,
+}
+
+impl Inbound for ZmqRep {
+    fn receive(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, NetworkError> {
+        let bytes = receive(&self.socket, timeout)?;
+
+        // Send client expected acknowledgement
+        if bytes.is_some() {
+            self.socket
+                .send(&self.ack, 0)
+                .map_err(|error| NetworkError::SendError(error.to_string()))?;
+        }
+
+        Ok(bytes)
+    }
+}
+
+struct ZmqPub(zmq::Socket);
+
+impl Outbound for ZmqPub {
+    fn send(&mut self, bytes: &[u8]) -> Result<(), NetworkError> {
+        self.0
+            .send(bytes, 0)
+            .map_err(|error| NetworkError::SendError(error.to_string()))
+    }
+}
+
+struct ZmqReq(zmq::Socket);
+
+impl Outbound for ZmqReq {
+    fn send(&mut self, bytes: &[u8]) -> Result<(), NetworkError> {
+        self.0
+            .send(bytes, 0)
+            .map_err(|error| NetworkError::SendError(error.to_string()))?;
+
+        // Don't check the response content. The server ACK is only required here.
+        self.0
+            .recv_bytes(0)
+            .map_err(|error| NetworkError::ReceiveError(error.to_string()))?;
+
+        Ok(())
+    }
+}
+
+struct ZmqSub(zmq::Socket);
+
+impl Inbound for ZmqSub {
+    fn receive(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, NetworkError> {
+        receive(&self.0, timeout)
+    }
+}
@@ -15,6 +15,9 @@ pub enum PendingOrder {
     Defend(SquadUuid),
     Hide(SquadUuid),
     EngageOrFire(SquadUuid),
+    SmokeScreen(SquadUuid),
+    /// Bounding overwatch with another squad
+    BoundTo(SquadUuid),
 }
 
 impl PendingOrder {
@@ -35,6 +38,8 @@ impl PendingOrder {
             PendingOrder::Defend(squad_index) => squad_index,
             PendingOrder::Hide(squad_index) => squad_index,
             PendingOrder::EngageOrFire(squad_index) => squad_index,
+            PendingOrder::SmokeScreen(squad_index) => squad_index,
+            PendingOrder::BoundTo(squad_index) => squad_index,
         }
     }
 
@@ -46,6 +51,8 @@ impl PendingOrder {
             PendingOrder::Defend(_) => vec![],
             PendingOrder::Hide(_) => vec![],
             PendingOrder::EngageOrFire(_) => vec![],
+            PendingOrder::SmokeScreen(_) => vec![],
+            PendingOrder::BoundTo(_) => vec![],
         }
     }
 
@@ -57,6 +64,8 @@ impl PendingOrder {
             PendingOrder::Defend(_) => &None,
             PendingOrder::Hide(_) => &None,
             PendingOrder::EngageOrFire(_) => &None,
+            PendingOrder::SmokeScreen(_) => &None,
+            PendingOrder::BoundTo(_) => &None,
         }
     }
 
@@ -72,6 +81,15 @@ impl PendingOrder {
     pub fn is_hide(&self) -> bool {
         matches!(self, Self::Hide(_))
     }
+
+    /// Defend and hide orders can be given as ambush
+    pub fn can_ambush(&self) -> bool {
+        matches!(self, Self::Defend(_) | Self::Hide(_))
+    }
+
+    pub fn is_bound(&self) -> bool {
+        matches!(self, Self::BoundTo(_))
+    }
 }
 
 impl Display for PendingOrder {
@@ -83,6 +101,8 @@ impl Display for PendingOrder {
             PendingOrder::Defend(_) => f.write_str("Defend"),
             PendingOrder::Hide(_) => f.write_str("Hide"),
             PendingOrder::EngageOrFire(_) => f.write_str("EngageOrFire"),
+            PendingOrder::SmokeScreen(_) => f.write_str("SmokeScreen"),
+            PendingOrder::BoundTo(_) => f.write_str("BoundTo"),
         }
     }
 }
@@ -97,6 +117,8 @@ pub enum Order {
     Hide(Angle),
     EngageSquad(SquadUuid),
     SuppressFire(WorldPoint),
+    /// Throw smoke grenades to the point
+    SmokeScreen(WorldPoint),
 }
 
 impl Order {
@@ -109,6 +131,7 @@ impl Order {
             Order::Hide(_) => Some(OrderMarker::Hide),
             Order::EngageSquad(_) => Some(OrderMarker::EngageSquad),
             Order::SuppressFire(_) => Some(OrderMarker::SuppressFire),
+            Order::SmokeScreen(_) => Some(OrderMarker::SmokeScreen),
             Order::Idle => None,
         }
     }
@@ -119,6 +142,7 @@ impl Order {
             Order::Defend(angle) => Some(*angle),
             Order::Hide(angle) => Some(*angle),
             Order::SuppressFire(_) => None,
+            Order::SmokeScreen(_) => None,
             Order::EngageSquad(_) => None,
             Order::Idle => None,
         }
@@ -140,6 +164,7 @@ impl Order {
             Order::Idle => {}
             Order::EngageSquad(_) => {}
             Order::SuppressFire(_) => {}
+            Order::SmokeScreen(_) => {}
         }
 
         false
@@ -168,6 +193,7 @@ impl Display for Order {
             Order::Idle => f.write_str("Idle"),
             Order::EngageSquad(_) => f.write_str("Engage"),
             Order::SuppressFire(_) => f.write_str("SuppressFire"),
+            Order::SmokeScreen(_) => f.write_str("SmokeScreen"),
         }
     }
 }
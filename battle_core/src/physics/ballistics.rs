@@ -0,0 +1,357 @@
+use bresenham::Bresenham;
+use glam::Vec2;
+use rand::Rng;
+
+use crate::{
+    behavior::feeling::UNDER_FIRE_MAX,
+    config::{
+        ServerConfig, BULLET_HIT_CHANCE, BULLET_HIT_DISTANCE_MILLIMETERS, BULLET_HIT_FLAT_FACTOR,
+        COVERAGE_PIXEL_STEPS, COVERAGE_TILE_STEPS, DISPERSION_FLAT_FACTOR,
+        DISPERSION_MOVING_FACTOR,
+    },
+    entity::soldier::Soldier,
+    game::{posture::Posture, role::SoldierRole, weapon::Weapon},
+    map::Map,
+    types::{Coverage, Distance, Skill, WorldPoint},
+};
+
+use super::{event::bullet::BulletFire, utils::DISTANCE_TO_METERS_COEFFICIENT};
+
+/// Dispersion cone of a shot, according to weapon and shooter conditions
+pub struct ShotDispersion {
+    weapon_dispersion: f32,
+    effective_range: Distance,
+    distance: Distance,
+    skill: Skill,
+    role: SoldierRole,
+    posture: Posture,
+    moving: bool,
+    under_fire: u32,
+}
+
+impl ShotDispersion {
+    pub fn new(weapon: &Weapon, distance: Distance) -> Self {
+        Self {
+            weapon_dispersion: weapon.dispersion(),
+            effective_range: weapon.effective_range(),
+            distance,
+            skill: Skill::default(),
+            role: SoldierRole::default(),
+            posture: Posture::StandUp,
+            moving: false,
+            under_fire: 0,
+        }
+    }
+
+    pub fn from_soldier(soldier: &Soldier, weapon: &Weapon, distance: Distance) -> Self {
+        Self::new(weapon, distance)
+            .skill(*soldier.skill())
+            .role(*soldier.role())
+            .posture(soldier.behavior().posture())
+            .moving(soldier.behavior().world_paths().is_some())
+            .under_fire(*soldier.under_fire().value())
+    }
+
+    pub fn skill(mut self, skill: Skill) -> Self {
+        self.skill = skill;
+        self
+    }
+
+    pub fn role(mut self, role: SoldierRole) -> Self {
+        self.role = role;
+        self
+    }
+
+    pub fn posture(mut self, posture: Posture) -> Self {
+        self.posture = posture;
+        self
+    }
+
+    pub fn moving(mut self, moving: bool) -> Self {
+        self.moving = moving;
+        self
+    }
+
+    pub fn under_fire(mut self, under_fire: u32) -> Self {
+        self.under_fire = under_fire;
+        self
+    }
+
+    /// Half angle (radians) of the cone
+    pub fn angle(&self) -> f32 {
+        let skill = 2.0 - self.skill.0.clamp(0.0, 1.0);
+        let posture = match self.posture {
+            Posture::StandUp => 1.0,
+            Posture::Flat => DISPERSION_FLAT_FACTOR,
+        };
+        let moving = if self.moving {
+            DISPERSION_MOVING_FACTOR
+        } else {
+            1.0
+        };
+        let under_fire = 1.0 + self.under_fire.min(UNDER_FIRE_MAX) as f32 / UNDER_FIRE_MAX as f32;
+        let effective_range = ((self.effective_range.millimeters() as f32
+            * self.role.effective_range_factor()) as i64)
+            .max(1);
+        let beyond_range = 1.0
+            + (self.distance.millimeters() - effective_range).max(0) as f32
+                / effective_range as f32;
+
+        self.weapon_dispersion
+            * self.role.dispersion_factor()
+            * skill
+            * posture
+            * moving
+            * under_fire
+            * beyond_range
+    }
+
+    /// End of a shot aimed from `from` to `target`, deviated by `deviation` part of the cone
+    /// (from -1.0 to 1.0) and which keeps going `extension` after the target
+    pub fn shot_end(
+        &self,
+        from: &WorldPoint,
+        target: &WorldPoint,
+        deviation: f32,
+        extension: &Distance,
+    ) -> WorldPoint {
+        let aim = target.to_vec2() - from.to_vec2();
+        if aim.length() == 0. {
+            return *target;
+        }
+
+        let direction =
+            Vec2::from_angle(self.angle() * deviation.clamp(-1.0, 1.0)).rotate(aim.normalize());
+        let extension = extension.millimeters() as f32 / 1000. / DISTANCE_TO_METERS_COEFFICIENT;
+        WorldPoint::from_vec2(from.to_vec2() + direction * (aim.length() + extension))
+    }
+}
+
+/// Chance of a bullet passing at `offset` of a soldier to hit his body. Silhouette is thinner
+/// at its edges and smaller when lying.
+pub fn hit_chance(posture: &Posture, offset: &Distance) -> f32 {
+    let band = BULLET_HIT_DISTANCE_MILLIMETERS as f32;
+    let silhouette = 2.0 * (1.0 - offset.millimeters() as f32 / band).clamp(0.0, 1.0);
+    let posture = match posture {
+        Posture::StandUp => 1.0,
+        Posture::Flat => BULLET_HIT_FLAT_FACTOR,
+    };
+
+    BULLET_HIT_CHANCE * silhouette * posture
+}
+
+/// Tile crossed by a bullet path which stops it (opaque) or may stop it (cover)
+#[derive(Debug, Clone)]
+pub struct BulletObstacle {
+    /// Distance (in pixels) from shooter
+    pub distance: f32,
+    pub point: WorldPoint,
+    /// None when tile is opaque
+    pub coverage: Option<Coverage>,
+}
+
+impl BulletObstacle {
+    pub fn stops(&self, rng: &mut impl Rng) -> bool {
+        match &self.coverage {
+            None => true,
+            Some(coverage) => rng.gen::<f32>() <= coverage.0,
+        }
+    }
+}
+
+/// Opaque and cover tiles crossed by bullet path, from the nearest of shooter. Tiles of
+/// shooter own cover are ignored.
+pub fn bullet_obstacles(
+    config: &ServerConfig,
+    map: &Map,
+    bullet_fire: &BulletFire,
+) -> Vec<BulletObstacle> {
+    let from = bullet_fire.from();
+    let shooter_grid_point = map.grid_point_from_world_point(from);
+    let pixels = Bresenham::new(
+        (from.x as isize, from.y as isize),
+        (bullet_fire.to().x as isize, bullet_fire.to().y as isize),
+    );
+
+    let mut obstacles = vec![];
+    let mut previous_grid_point = None;
+    for (pixel_x, pixel_y) in pixels.step_by(COVERAGE_PIXEL_STEPS) {
+        let point = WorldPoint::new(pixel_x as f32, pixel_y as f32);
+        let grid_point = map.grid_point_from_world_point(&point);
+        let shooter_cover = (grid_point.x - shooter_grid_point.x).abs()
+            < COVERAGE_TILE_STEPS as i32
+            && (grid_point.y - shooter_grid_point.y).abs() < COVERAGE_TILE_STEPS as i32;
+        if shooter_cover || previous_grid_point == Some(grid_point) {
+            continue;
+        }
+        previous_grid_point = Some(grid_point);
+
+        if let Some(tile) = map.terrain_tile(&grid_point) {
+            let coverage = if config.terrain_tile_opacity(&tile.type_) >= 1.0 {
+                None
+            } else if let Some(coverage) = tile.type_.coverage(&Posture::StandUp) {
+                Some(coverage)
+            } else {
+                continue;
+            };
+            obstacles.push(BulletObstacle {
+                distance: from.to_vec2().distance(point.to_vec2()),
+                point,
+                coverage,
+            });
+        }
+    }
+
+    obstacles
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use rstest::*;
+
+    use super::*;
+    use crate::{
+        game::weapon::GunFireSoundType,
+        map::{decor::Decor, terrain::TerrainTile},
+        physics::utils::distance_between_points,
+    };
+
+    #[rstest]
+    fn dispersion_grows_with_shooter_conditions() {
+        // Given
+        let weapon = Weapon::MosinNagantM1924(true, None);
+        let distance = Distance::from_meters(100);
+        let calm = ShotDispersion::new(&weapon, distance).posture(Posture::Flat);
+
+        // When
+        let moving = ShotDispersion::new(&weapon, distance)
+            .posture(Posture::Flat)
+            .moving(true);
+        let suppressed = ShotDispersion::new(&weapon, distance)
+            .posture(Posture::Flat)
+            .under_fire(UNDER_FIRE_MAX);
+        let beyond_range =
+            ShotDispersion::new(&weapon, Distance::from_meters(1000)).posture(Posture::Flat);
+        let skilled = ShotDispersion::new(&weapon, distance)
+            .posture(Posture::Flat)
+            .skill(Skill(1.0));
+
+        // Then
+        assert!(moving.angle() > calm.angle());
+        assert!(suppressed.angle() > calm.angle());
+        assert!(beyond_range.angle() > calm.angle());
+        assert!(skilled.angle() < calm.angle());
+    }
+
+    #[rstest]
+    fn sniper_is_accurate_farther() {
+        // Given
+        let weapon = Weapon::MosinNagantM1924(true, None);
+        let distance = Distance::from_meters(800);
+
+        // When
+        let rifleman = ShotDispersion::new(&weapon, distance);
+        let sniper = ShotDispersion::new(&weapon, distance).role(SoldierRole::Sniper);
+
+        // Then
+        assert!(sniper.angle() < rifleman.angle() * SoldierRole::Sniper.dispersion_factor());
+    }
+
+    #[rstest]
+    fn shot_end_extends_aimed_ray() {
+        // Given
+        let weapon = Weapon::MosinNagantM1924(true, None);
+        let from = WorldPoint::new(0., 0.);
+        let target = WorldPoint::new(100., 0.);
+        let dispersion =
+            ShotDispersion::new(&weapon, distance_between_points(&from, &target)).moving(true);
+
+        // When
+        let straight = dispersion.shot_end(&from, &target, 0., &Distance::from_meters(30));
+        let deviated = dispersion.shot_end(&from, &target, 1., &Distance::from_meters(30));
+
+        // Then
+        assert!((straight.x - 200.).abs() < 0.01);
+        assert!(straight.y.abs() < 0.01);
+        assert!(deviated.y.abs() > 0.);
+        assert!((deviated.to_vec2().length() - 200.).abs() < 0.01);
+    }
+
+    #[rstest]
+    #[case(Posture::StandUp, 0, 0.4)]
+    #[case(Posture::StandUp, 150, 0.2)]
+    #[case(Posture::StandUp, 300, 0.0)]
+    #[case(Posture::Flat, 0, 0.16)]
+    #[case(Posture::Flat, 500, 0.0)]
+    fn hit_chance_by_silhouette(
+        #[case] posture: Posture,
+        #[case] offset: i64,
+        #[case] expected: f32,
+    ) {
+        // When
+        let chance = hit_chance(&posture, &Distance::from_millimeters(offset));
+
+        // Then
+        assert!((chance - expected).abs() < 0.001, "{}", chance);
+    }
+
+    /// 20x20 tiles (10 pixels each) map with a hedge on column 5 and a brick wall on column 10
+    fn walled_map() -> Map {
+        let terrain_tiles = (0..20 * 20)
+            .map(|i| {
+                let (x, y) = (i % 20, i / 20);
+                let id = match x {
+                    5 => "Hedge",
+                    10 => "BrickWall",
+                    _ => "ShortGrass",
+                };
+                TerrainTile::from_str_id(id, 10, 10, 0.1, 0.1, x, y, 0, 0).unwrap()
+            })
+            .collect();
+        Map::new(
+            "TestMap".to_string(),
+            PathBuf::from("."),
+            PathBuf::from("."),
+            PathBuf::from("."),
+            vec![],
+            vec![],
+            20,
+            20,
+            terrain_tiles,
+            10,
+            10,
+            Decor::new(vec![], vec![]),
+            vec![],
+        )
+    }
+
+    #[rstest]
+    #[case(WorldPoint::new(15., 55.), vec![Some(0.15), None])]
+    #[case(WorldPoint::new(65., 55.), vec![None])]
+    #[case(WorldPoint::new(95., 55.), vec![])]
+    fn bullet_obstacles_on_path(#[case] from: WorldPoint, #[case] expected: Vec<Option<f32>>) {
+        // Given
+        let weapon = Weapon::MosinNagantM1924(true, None);
+        let bullet_fire = BulletFire::new(
+            from,
+            WorldPoint::new(195., 55.),
+            weapon.ammunition(),
+            GunFireSoundType::MosinNagant,
+        );
+
+        // When
+        let obstacles = bullet_obstacles(&ServerConfig::default(), &walled_map(), &bullet_fire);
+
+        // Then
+        let coverages: Vec<Option<f32>> = obstacles
+            .iter()
+            .map(|obstacle| obstacle.coverage.as_ref().map(|coverage| coverage.0))
+            .collect();
+        assert_eq!(coverages, expected);
+        assert!(obstacles
+            .windows(2)
+            .all(|pair| pair[0].distance < pair[1].distance));
+    }
+}
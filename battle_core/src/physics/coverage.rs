@@ -2,7 +2,7 @@ use bresenham::Bresenham;
 use rand::Rng;
 
 use crate::{
-    config::{COVERAGE_PIXEL_STEPS, COVERAGE_TILE_STEPS},
+    config::{COVERAGE_PIXEL_STEPS, COVERAGE_TILE_STEPS, ELEVATION_EYE_HEIGHT, INTERIOR_COVERAGE},
     entity::soldier::Soldier,
     map::Map,
     types::WorldPoint,
@@ -25,7 +25,28 @@ impl<'a> SoldierCovered<'a> {
         }
     }
 
-    pub fn compute(&self) -> bool {
+    pub fn compute(&self, rng: &mut impl Rng) -> bool {
+        // Soldier behind a ridge (or on reverse slope) is covered by the ground itself
+        if self.map.elevation_masks(
+            self.bullet_fire.from(),
+            ELEVATION_EYE_HEIGHT,
+            &self.soldier.world_point(),
+            self.soldier.behavior().posture().height(),
+        ) {
+            return true;
+        }
+
+        // Soldier inside building is protected by its walls from fire outside of its room
+        let soldier_point = self.soldier.world_point();
+        let soldier_grid_point = self.map.grid_point_from_world_point(&soldier_point);
+        if let Some(interior) = self.map.interior(&soldier_grid_point) {
+            if !interior.same_space(&soldier_point, self.bullet_fire.from())
+                && rng.gen::<f32>() <= INTERIOR_COVERAGE
+            {
+                return true;
+            }
+        }
+
         let pixels = Bresenham::new(
             (
                 self.bullet_fire.from().x as isize,
@@ -48,7 +69,6 @@ impl<'a> SoldierCovered<'a> {
                 .get((grid_point.y * self.map.width() as i32 + grid_point.x) as usize)
             {
                 if let Some(coverage) = tile.type_().coverage(&self.soldier.behavior().posture()) {
-                    let mut rng = rand::thread_rng();
                     let value: f32 = rng.gen();
                     return value <= coverage.0;
                 }
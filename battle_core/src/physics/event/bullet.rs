@@ -1,6 +1,9 @@
 use crate::{
-    game::weapon::{Ammunition, GunFireSoundType},
-    types::{Precision, SoldierIndex, WorldPoint},
+    game::{
+        weapon::{Ammunition, GunFireSoundType},
+        Side,
+    },
+    types::WorldPoint,
 };
 use serde::{Deserialize, Serialize};
 
@@ -10,16 +13,15 @@ pub struct BulletFire {
     end: u64,
     from: WorldPoint,
     to: WorldPoint,
-    target: Option<(SoldierIndex, Precision)>,
     ammunition: Ammunition,
     gun_fire_sound_type: GunFireSoundType,
+    side: Option<Side>,
 }
 
 impl BulletFire {
     pub fn new(
         from: WorldPoint,
         to: WorldPoint,
-        target: Option<(SoldierIndex, Precision)>,
         ammunition: Ammunition,
         gun_fire_sound_type: GunFireSoundType,
     ) -> Self {
@@ -28,17 +30,27 @@ impl BulletFire {
             end: 0,
             from,
             to,
-            target,
             ammunition,
             gun_fire_sound_type,
+            side: None,
         }
     }
 
+    pub fn fired_by(mut self, side: Side) -> Self {
+        self.side = Some(side);
+        self
+    }
+
     pub fn init(&mut self, start_frame_i: u64) {
         self.start = start_frame_i;
         self.end = start_frame_i + 2;
     }
 
+    /// Same bullet fire, stopped at `to`
+    pub fn until(&self, to: WorldPoint) -> Self {
+        Self { to, ..self.clone() }
+    }
+
     pub fn point(&self) -> &WorldPoint {
         &self.to
     }
@@ -74,4 +86,9 @@ impl BulletFire {
     pub fn gun_fire_sound_type(&self) -> &GunFireSoundType {
         &self.gun_fire_sound_type
     }
+
+    /// Side of the shooter (if fired by a soldier)
+    pub fn side(&self) -> Option<&Side> {
+        self.side.as_ref()
+    }
 }
@@ -0,0 +1,210 @@
+use crate::{
+    config::{
+        CLOUD_DISSIPATION_START, CLOUD_DRIFT, CLOUD_EXPANSION_DURATION, CLOUD_FIRE_OPACITY,
+        CLOUD_SMOKE_OPACITY, CLOUD_START_RAYON_RATIO, CLOUD_STEP_DURATION, TARGET_FPS,
+    },
+    physics::utils::{distance_between_points, DISTANCE_TO_METERS_COEFFICIENT},
+    types::{Distance, WorldPoint},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CloudType {
+    Smoke,
+    Fire,
+}
+
+impl CloudType {
+    /// Opacity added by each tile of the cloud crossed by a sight line
+    pub fn opacity(&self) -> f32 {
+        match self {
+            CloudType::Smoke => CLOUD_SMOKE_OPACITY,
+            CloudType::Fire => CLOUD_FIRE_OPACITY,
+        }
+    }
+
+    /// Cloud is moved by wind
+    pub fn drifts(&self) -> bool {
+        match self {
+            CloudType::Smoke => true,
+            CloudType::Fire => false,
+        }
+    }
+}
+
+/// Temporary area reducing visibility (smoke, fire). Cloud expands when appearing, can
+/// drift with wind, then dissipates.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cloud {
+    start: u64,
+    end: u64,
+    origin: WorldPoint,
+    point: WorldPoint,
+    type_: CloudType,
+    maximum_rayon: Distance,
+    rayon: Distance,
+    density: f32,
+    duration: u64,
+    step: u64,
+}
+
+impl Cloud {
+    /// Cloud of `rayon` around point during `duration` seconds
+    pub fn new(point: WorldPoint, type_: CloudType, rayon: Distance, duration: u64) -> Self {
+        let mut cloud = Self {
+            start: 0,
+            end: 0,
+            origin: point,
+            point,
+            type_,
+            maximum_rayon: rayon,
+            rayon,
+            density: 1.0,
+            duration,
+            step: 0,
+        };
+        cloud.compute(0);
+        cloud
+    }
+
+    pub fn init(&mut self, start_frame_i: u64) {
+        self.start = start_frame_i;
+        self.end = start_frame_i + self.duration * TARGET_FPS;
+        self.step = 0;
+        self.compute(0);
+    }
+
+    /// Update cloud shape according to current frame. Return true if changed.
+    pub fn update(&mut self, frame_i: u64) -> bool {
+        let elapsed = frame_i.saturating_sub(self.start) / TARGET_FPS;
+        let step = elapsed / CLOUD_STEP_DURATION;
+        if step == self.step {
+            return false;
+        }
+
+        self.step = step;
+        self.compute(step * CLOUD_STEP_DURATION);
+        true
+    }
+
+    fn compute(&mut self, elapsed: u64) {
+        let expansion = (elapsed as f32 / CLOUD_EXPANSION_DURATION as f32).min(1.0);
+        let ratio = CLOUD_START_RAYON_RATIO + (1.0 - CLOUD_START_RAYON_RATIO) * expansion;
+        self.rayon =
+            Distance::from_millimeters((self.maximum_rayon.millimeters() as f32 * ratio) as i64);
+
+        if self.type_.drifts() {
+            let drift = elapsed as f32 / DISTANCE_TO_METERS_COEFFICIENT;
+            self.point = WorldPoint::new(
+                self.origin.x + CLOUD_DRIFT.0 * drift,
+                self.origin.y + CLOUD_DRIFT.1 * drift,
+            );
+        }
+
+        let progress = (elapsed as f32 / self.duration.max(1) as f32).min(1.0);
+        self.density = if progress < CLOUD_DISSIPATION_START {
+            1.0
+        } else {
+            (1.0 - progress) / (1.0 - CLOUD_DISSIPATION_START)
+        };
+    }
+
+    pub fn point(&self) -> &WorldPoint {
+        &self.point
+    }
+
+    pub fn type_(&self) -> &CloudType {
+        &self.type_
+    }
+
+    pub fn rayon(&self) -> &Distance {
+        &self.rayon
+    }
+
+    /// From 1.0 (full cloud) to 0.0 (dissipated)
+    pub fn density(&self) -> f32 {
+        self.density
+    }
+
+    pub fn step(&self) -> u64 {
+        self.step
+    }
+
+    /// Opacity added by each tile of the cloud crossed by a sight line
+    pub fn opacity(&self) -> f32 {
+        self.type_.opacity() * self.density
+    }
+
+    pub fn contains(&self, point: &WorldPoint) -> bool {
+        distance_between_points(&self.point, point) <= self.rayon
+    }
+
+    pub fn finished(&self, frame_i: u64) -> bool {
+        frame_i >= self.end
+    }
+
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    pub fn end(&self) -> u64 {
+        self.end
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(WorldPoint::new(100., 100.), true)]
+    #[case(WorldPoint::new(105., 100.), true)]
+    #[case(WorldPoint::new(120., 100.), false)]
+    fn cloud_contains_point(#[case] point: WorldPoint, #[case] expected: bool) {
+        // Given
+        let mut cloud = Cloud::new(
+            WorldPoint::new(100., 100.),
+            CloudType::Fire,
+            Distance::from_meters(3),
+            10,
+        );
+        cloud.init(0);
+        cloud.update(CLOUD_EXPANSION_DURATION * TARGET_FPS);
+
+        // When
+        let contains = cloud.contains(&point);
+
+        // Then
+        assert_eq!(contains, expected)
+    }
+
+    #[rstest]
+    fn smoke_expands_drifts_and_dissipates() {
+        // Given
+        let mut cloud = Cloud::new(
+            WorldPoint::new(0., 0.),
+            CloudType::Smoke,
+            Distance::from_meters(10),
+            100,
+        );
+        cloud.init(0);
+        let start_rayon = *cloud.rayon();
+
+        // When
+        cloud.update(50 * TARGET_FPS);
+
+        // Then
+        assert!(cloud.rayon() > &start_rayon);
+        assert!(cloud.point().x > 0.);
+        assert_eq!(cloud.density(), 1.0);
+
+        // When
+        cloud.update(90 * TARGET_FPS);
+
+        // Then
+        assert!(cloud.density() < 0.5);
+        assert!(cloud.opacity() < CLOUD_SMOKE_OPACITY);
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{config::TARGET_FPS, game::explosive::ExplosiveType, types::WorldPoint};
+use crate::{
+    config::TARGET_FPS,
+    game::{explosive::ExplosiveType, Side},
+    types::WorldPoint,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,6 +11,7 @@ pub struct Explosion {
     end: u64,
     point: WorldPoint,
     explosive_type: ExplosiveType,
+    side: Option<Side>,
 }
 
 impl Explosion {
@@ -16,9 +21,15 @@ impl Explosion {
             end: 0,
             point,
             explosive_type: type_,
+            side: None,
         }
     }
 
+    pub fn fired_by(mut self, side: Side) -> Self {
+        self.side = Some(side);
+        self
+    }
+
     pub fn init(&mut self, start_frame_i: u64) {
         self.start = start_frame_i;
         self.end = start_frame_i + (self.explosive_type.sprite().duration() as u64 * TARGET_FPS);
@@ -51,4 +62,9 @@ impl Explosion {
     pub fn explosive_type(&self) -> &ExplosiveType {
         &self.explosive_type
     }
+
+    /// Side of the shooter (if fired by a soldier)
+    pub fn side(&self) -> Option<&Side> {
+        self.side.as_ref()
+    }
 }
@@ -1,2 +1,3 @@
 pub mod bullet;
+pub mod cloud;
 pub mod explosion;
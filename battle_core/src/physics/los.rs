@@ -0,0 +1,232 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::Mutex,
+};
+
+use bresenham::Bresenham;
+
+use crate::{
+    config::{ELEVATION_EYE_HEIGHT, LOS_CACHE_CAPACITY, LOS_CACHE_SHARDS, VISIBILITY_PIXEL_STEPS},
+    map::{terrain::TileType, Map},
+    types::{GridPoint, WorldPoint},
+};
+
+/// Sight line start and end tiles, with observers building floor
+type LineKey = ((i32, i32, u8), (i32, i32, u8));
+
+/// Tile crossed by a sight line. Only static map geometry is considered here : tile type
+/// (which can change during battle) and opacity are applied when line is used.
+#[derive(Debug, Clone, Copy)]
+pub struct SightTile {
+    pub grid_point: GridPoint,
+    /// First pixel of the line in this tile
+    pub world_point: WorldPoint,
+    /// Ground is higher than sight line
+    pub masked: bool,
+    /// Height (in meters) of sight line above eyes of someone standing on the tile (zero on
+    /// flat maps)
+    pub clearance: f32,
+}
+
+impl SightTile {
+    /// Sight line pass over the obstacle of this tile type
+    pub fn overlooks(&self, type_: &TileType) -> bool {
+        self.clearance > type_.obstacle_height()
+    }
+}
+
+/// Sight lines between tiles centers, computed once then reused. Lines don't depend on tile
+/// types, so they stay true when tiles change. Lines are spread in shards (locked
+/// independently) where least recently used lines are forgotten first.
+pub struct LosCache {
+    shards: Vec<Mutex<LosShard>>,
+}
+
+impl Default for LosCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LosCache {
+    pub fn new() -> Self {
+        Self::with_capacity(LOS_CACHE_CAPACITY)
+    }
+
+    /// Cache keeping `capacity` lines at most
+    pub fn with_capacity(capacity: usize) -> Self {
+        // Each shard keep two generations of lines
+        let shard_capacity = (capacity / LOS_CACHE_SHARDS / 2).max(1);
+        Self {
+            shards: (0..LOS_CACHE_SHARDS)
+                .map(|_| Mutex::new(LosShard::new(shard_capacity)))
+                .collect(),
+        }
+    }
+
+    fn shard(&self, key: &LineKey) -> &Mutex<LosShard> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        &self.shards[hasher.finish() as usize % self.shards.len()]
+    }
+
+    /// Sight line tiles, ordered from `from` to `to` (points with their building floor)
+    pub fn line(
+        &self,
+        map: &Map,
+        (from, from_floor): (&WorldPoint, u8),
+        (to, to_floor): (&WorldPoint, u8),
+    ) -> Vec<SightTile> {
+        let from = map.grid_point_from_world_point(from);
+        let to = map.grid_point_from_world_point(to);
+        let key = ((from.x, from.y, from_floor), (to.x, to.y, to_floor));
+        let shard = self.shard(&key);
+
+        let cached = shard
+            .lock()
+            .expect("Los cache lock must not be poisoned")
+            .get(&key);
+        match cached {
+            Some(line) => line,
+            None => {
+                // Computed out of the lock to not block other threads
+                let line = compute(map, &key);
+                shard
+                    .lock()
+                    .expect("Los cache lock must not be poisoned")
+                    .insert(key, line.clone());
+                line
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| {
+                shard
+                    .lock()
+                    .expect("Los cache lock must not be poisoned")
+                    .len()
+            })
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Lines of a cache shard. Used lines are kept in recent generation, and the old generation
+/// is forgotten when recent one is full (approximation of least recently used eviction).
+struct LosShard {
+    capacity: usize,
+    recent: HashMap<LineKey, Vec<SightTile>>,
+    old: HashMap<LineKey, Vec<SightTile>>,
+}
+
+impl LosShard {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            recent: HashMap::new(),
+            old: HashMap::new(),
+        }
+    }
+
+    fn get(&mut self, key: &LineKey) -> Option<Vec<SightTile>> {
+        if let Some(line) = self.recent.get(key) {
+            return Some(line.clone());
+        }
+
+        let line = self.old.remove(key)?;
+        self.insert(*key, line.clone());
+        Some(line)
+    }
+
+    fn insert(&mut self, key: LineKey, line: Vec<SightTile>) {
+        if self.recent.len() >= self.capacity {
+            self.old = std::mem::take(&mut self.recent);
+        }
+        self.recent.insert(key, line);
+    }
+
+    fn len(&self) -> usize {
+        self.recent.len() + self.old.len()
+    }
+}
+
+fn compute(map: &Map, key: &LineKey) -> Vec<SightTile> {
+    let ((from_x, from_y, from_floor), (to_x, to_y, to_floor)) = *key;
+    let from_grid_point = GridPoint::new(from_x, from_y);
+    let to_grid_point = GridPoint::new(to_x, to_y);
+    let from = map.world_point_from_grid_point(from_grid_point);
+    let to = map.world_point_from_grid_point(to_grid_point);
+    let pixels: Vec<(isize, isize)> = Bresenham::new(
+        (from.x as isize, from.y as isize),
+        (to.x as isize, to.y as isize),
+    )
+    .collect();
+
+    // Sight line altitude, from eyes to eyes (upper floors of buildings included)
+    let from_altitude = map.sight_elevation(&from_grid_point, from_floor) + ELEVATION_EYE_HEIGHT;
+    let to_altitude = map.sight_elevation(&to_grid_point, to_floor) + ELEVATION_EYE_HEIGHT;
+
+    let mut tiles: Vec<SightTile> = vec![];
+    for (i, (pixel_x, pixel_y)) in pixels.iter().enumerate().step_by(VISIBILITY_PIXEL_STEPS) {
+        let world_point = WorldPoint::new(*pixel_x as f32, *pixel_y as f32);
+        let grid_point = map.grid_point_from_world_point(&world_point);
+        // Line never come back on a previous tile
+        if tiles.last().map(|tile| tile.grid_point) == Some(grid_point) {
+            continue;
+        }
+        let terrain_tile = match map.terrain_tile(&grid_point) {
+            Some(tile) => tile,
+            None => continue,
+        };
+
+        // Hills block sight line, and sight line from high ground pass over obstacles
+        let progress = i as f32 / pixels.len() as f32;
+        let line_altitude = from_altitude + (to_altitude - from_altitude) * progress;
+        let ground_altitude = terrain_tile.elevation;
+        tiles.push(SightTile {
+            grid_point,
+            world_point,
+            masked: map.elevated() && ground_altitude > line_altitude,
+            clearance: if map.elevated() {
+                line_altitude - ground_altitude - ELEVATION_EYE_HEIGHT
+            } else {
+                0.
+            },
+        });
+    }
+
+    tiles
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn used_lines_are_kept_when_shard_is_full() {
+        // Given
+        let mut shard = LosShard::new(2);
+        let key = |i: i32| ((0, 0, 0), (i, 0, 0));
+        shard.insert(key(1), vec![]);
+        shard.insert(key(2), vec![]);
+
+        // When
+        shard.insert(key(3), vec![]);
+        shard.get(&key(1));
+        shard.insert(key(4), vec![]);
+
+        // Then
+        assert!(shard.get(&key(1)).is_some());
+        assert!(shard.get(&key(2)).is_none());
+        assert!(shard.len() <= 4);
+    }
+}
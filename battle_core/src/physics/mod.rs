@@ -1,5 +1,8 @@
+pub mod ballistics;
 pub mod coverage;
 pub mod event;
+pub mod los;
 pub mod path;
+pub mod spatial;
 pub mod utils;
 pub mod visibility;
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use crate::{
+    entity::soldier::Soldier,
+    types::{Distance, SoldierIndex, WorldPoint},
+};
+
+use super::utils::{distance_between_points, DISTANCE_TO_METERS_COEFFICIENT};
+
+/// Soldiers grouped by square cells of the world, to find soldiers around a point without
+/// considering all of them.
+pub struct SpatialIndex {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<(SoldierIndex, WorldPoint)>>,
+}
+
+impl SpatialIndex {
+    /// Index with cells of `cell_size` (world pixels)
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn from_soldiers<'a>(cell_size: f32, soldiers: impl Iterator<Item = &'a Soldier>) -> Self {
+        let mut index = Self::new(cell_size);
+        for soldier in soldiers {
+            index.insert(soldier.uuid(), soldier.world_point());
+        }
+        index
+    }
+
+    fn cell(&self, point: &WorldPoint) -> (i32, i32) {
+        (
+            (point.x / self.cell_size).floor() as i32,
+            (point.y / self.cell_size).floor() as i32,
+        )
+    }
+
+    pub fn insert(&mut self, soldier_index: SoldierIndex, point: WorldPoint) {
+        let cell = self.cell(&point);
+        self.cells
+            .entry(cell)
+            .or_default()
+            .push((soldier_index, point));
+    }
+
+    /// All indexed soldiers, sorted by soldier index
+    pub fn all(&self) -> Vec<SoldierIndex> {
+        let mut soldiers: Vec<SoldierIndex> = self
+            .cells
+            .values()
+            .flat_map(|cell| cell.iter().map(|(soldier_index, _)| *soldier_index))
+            .collect();
+        soldiers.sort_by_key(|soldier_index| soldier_index.0);
+        soldiers
+    }
+
+    /// Soldiers at `distance` or less from point, sorted by soldier index
+    pub fn around(&self, point: &WorldPoint, distance: &Distance) -> Vec<SoldierIndex> {
+        let rayon = distance.millimeters() as f32 / 1000. / DISTANCE_TO_METERS_COEFFICIENT;
+        let (from_x, from_y) = self.cell(&WorldPoint::new(point.x - rayon, point.y - rayon));
+        let (to_x, to_y) = self.cell(&WorldPoint::new(point.x + rayon, point.y + rayon));
+
+        let mut soldiers = vec![];
+        for x in from_x..=to_x {
+            for y in from_y..=to_y {
+                if let Some(cell) = self.cells.get(&(x, y)) {
+                    soldiers.extend(
+                        cell.iter()
+                            .filter(|(_, soldier_point)| {
+                                &distance_between_points(point, soldier_point) <= distance
+                            })
+                            .map(|(soldier_index, _)| *soldier_index),
+                    );
+                }
+            }
+        }
+
+        soldiers.sort_by_key(|soldier_index| soldier_index.0);
+        soldiers
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(WorldPoint::new(0., 0.), 10, vec![0, 1])]
+    #[case(WorldPoint::new(0., 0.), 100, vec![0, 1, 2])]
+    #[case(WorldPoint::new(1000., 1000.), 10, vec![3])]
+    #[case(WorldPoint::new(500., 500.), 10, vec![])]
+    fn soldiers_around_point(
+        #[case] point: WorldPoint,
+        #[case] meters: i64,
+        #[case] expected: Vec<usize>,
+    ) {
+        // Given
+        let mut index = SpatialIndex::new(50.);
+        index.insert(SoldierIndex(1), WorldPoint::new(10., 10.));
+        index.insert(SoldierIndex(0), WorldPoint::new(-5., 0.));
+        index.insert(SoldierIndex(2), WorldPoint::new(200., 0.));
+        index.insert(SoldierIndex(3), WorldPoint::new(1010., 1000.));
+
+        // When
+        let soldiers = index.around(&point, &Distance::from_meters(meters));
+
+        // Then
+        assert_eq!(
+            soldiers,
+            expected.into_iter().map(SoldierIndex).collect::<Vec<_>>()
+        )
+    }
+}
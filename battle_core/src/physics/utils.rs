@@ -9,3 +9,25 @@ pub fn distance_between_points(from: &WorldPoint, to: &WorldPoint) -> Distance {
         ((from.to_vec2().distance(to.to_vec2()) * DISTANCE_TO_METERS_COEFFICIENT) * 1000.) as i64,
     )
 }
+
+/// Position of `point` projection on the line from `from` (0.0) to `to` (1.0). Negative when
+/// behind `from`.
+pub fn projection_ratio(point: &WorldPoint, from: &WorldPoint, to: &WorldPoint) -> f32 {
+    let segment = to.to_vec2() - from.to_vec2();
+    let length_squared = segment.length_squared();
+    if length_squared == 0. {
+        return 0.;
+    }
+
+    (point.to_vec2() - from.to_vec2()).dot(segment) / length_squared
+}
+
+/// Point of the segment between `from` and `to` which is the nearest of `point`
+pub fn nearest_point_on_segment(
+    point: &WorldPoint,
+    from: &WorldPoint,
+    to: &WorldPoint,
+) -> WorldPoint {
+    let ratio = projection_ratio(point, from, to).clamp(0., 1.);
+    WorldPoint::from_vec2(from.to_vec2() + (to.to_vec2() - from.to_vec2()) * ratio)
+}
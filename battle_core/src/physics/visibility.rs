@@ -1,16 +1,15 @@
 use std::collections::HashMap;
 
-use bresenham::Bresenham;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    config::{ServerConfig, VISIBILITY_FIRSTS, VISIBILITY_PIXEL_STEPS},
+    config::{ServerConfig, ELEVATION_MASK_OPACITY, VISIBILITY_FIRSTS},
     entity::soldier::Soldier,
     map::Map,
     types::{Distance, GridPath, SoldierIndex, WorldPoint},
 };
 
-use super::utils::distance_between_points;
+use super::{event::cloud::Cloud, utils::distance_between_points};
 
 pub const VISIBLE_OPACITY_LIMIT: f32 = 0.5;
 
@@ -24,6 +23,21 @@ impl Visibilities {
         self.visibilities = value;
     }
 
+    pub fn update(
+        &mut self,
+        changed: HashMap<(SoldierIndex, SoldierIndex), Visibility>,
+        removed: &[(SoldierIndex, SoldierIndex)],
+    ) {
+        for soldiers in removed {
+            self.visibilities.remove(soldiers);
+        }
+        self.visibilities.extend(changed);
+    }
+
+    pub fn all(&self) -> &HashMap<(SoldierIndex, SoldierIndex), Visibility> {
+        &self.visibilities
+    }
+
     pub fn get(&self, soldiers: &(SoldierIndex, SoldierIndex)) -> Option<&Visibility> {
         self.visibilities.get(soldiers)
     }
@@ -74,12 +88,14 @@ impl Visibility {
         from_soldier: &Soldier,
         to_soldier: &Soldier,
         map: &Map,
+        clouds: &[Cloud],
     ) -> Self {
         let from_point = from_soldier.world_point();
         let to_point = to_soldier.world_point();
         let last_shoot_frame_i = to_soldier.last_shoot_frame_i();
 
-        let by_behavior_modifier: f32 = config.visibility_behavior_modifier(to_soldier.behavior());
+        let by_behavior_modifier: f32 = config.visibility_behavior_modifier(to_soldier.behavior())
+            + to_soldier.role().visibility_modifier();
 
         let exclude_lasts = if last_shoot_frame_i + config.visibility_by_last_frame_shoot >= frame_i
         {
@@ -91,15 +107,19 @@ impl Visibility {
         let (mut to_soldier_item_opacity, opacity_segments, path_final_opacity, break_point) =
             Self::between_points_raw(
                 config,
-                &from_point,
-                &to_point,
+                (&from_point, map.soldier_floor(from_soldier)),
+                (&to_point, map.soldier_floor(to_soldier)),
                 map,
+                clouds,
                 config.visibility_firsts,
                 exclude_lasts,
             );
 
         to_soldier_item_opacity -= by_behavior_modifier;
-        let visible = to_soldier_item_opacity < config.visible_starts_at;
+        let visible_starts_at = config.visible_starts_at
+            * map.environment().visible_starts_at_factor()
+            + from_soldier.role().spotting_bonus();
+        let visible = to_soldier_item_opacity < visible_starts_at;
 
         let distance =
             distance_between_points(&from_soldier.world_point(), &to_soldier.world_point());
@@ -122,6 +142,7 @@ impl Visibility {
         from_soldier: &Soldier,
         to_point: &WorldPoint,
         map: &Map,
+        clouds: &[Cloud],
         exclude_lasts: usize,
     ) -> Self {
         let from_point = from_soldier.world_point();
@@ -129,9 +150,10 @@ impl Visibility {
         let (to_soldier_item_opacity, opacity_segments, path_final_opacity, break_point) =
             Self::between_points_raw(
                 config,
-                &from_point,
-                to_point,
+                (&from_point, map.soldier_floor(from_soldier)),
+                (to_point, 0),
                 map,
+                clouds,
                 VISIBILITY_FIRSTS,
                 exclude_lasts,
             );
@@ -157,9 +179,18 @@ impl Visibility {
         from_point: &WorldPoint,
         to_point: &WorldPoint,
         map: &Map,
+        clouds: &[Cloud],
     ) -> Self {
         let (to_soldier_item_opacity, opacity_segments, path_final_opacity, break_point) =
-            Self::between_points_raw(config, from_point, to_point, map, VISIBILITY_FIRSTS, 0);
+            Self::between_points_raw(
+                config,
+                (from_point, 0),
+                (to_point, 0),
+                map,
+                clouds,
+                VISIBILITY_FIRSTS,
+                0,
+            );
 
         let visible = to_soldier_item_opacity < 0.5;
         let distance = distance_between_points(from_point, to_point);
@@ -177,12 +208,13 @@ impl Visibility {
         }
     }
 
-    // TODO : Optimize performances here
+    /// Opacities along sight line between points (with observers building floor)
     pub fn between_points_raw(
         config: &ServerConfig,
-        from_point: &WorldPoint,
-        to_point: &WorldPoint,
+        (from_point, from_floor): (&WorldPoint, u8),
+        (to_point, to_floor): (&WorldPoint, u8),
         map: &Map,
+        clouds: &[Cloud],
         exclude_firsts: usize,
         exclude_lasts: usize,
     ) -> (f32, Vec<(WorldPoint, f32)>, f32, Option<WorldPoint>) {
@@ -191,45 +223,51 @@ impl Visibility {
         let mut to_opacity: f32 = 0.0;
         let mut break_point = None;
         let _visible_by_bullet_fire = false;
+        let environment_opacity = map.environment().opacity();
 
-        // Compute line pixels
-        let pixels = Bresenham::new(
-            (from_point.x as isize, from_point.y as isize),
-            (to_point.x as isize, to_point.y as isize),
-        );
-
+        // Static part of the line comes from map cache
+        let line = map.sight_line(from_point, from_floor, to_point, to_floor);
         let mut grid_path: GridPath = GridPath::new();
-        let mut other: Vec<(WorldPoint, f32)> = vec![];
-        for (pixel_x, pixel_y) in pixels.step_by(VISIBILITY_PIXEL_STEPS) {
-            let grid_point =
-                map.grid_point_from_world_point(&WorldPoint::new(pixel_x as f32, pixel_y as f32));
-            if !grid_path.contains(&grid_point) {
-                let terrain_tile = match map
-                    .terrain_tiles()
-                    .get((grid_point.y * map.width() as i32 + grid_point.x) as usize)
-                {
-                    Some(tile) => tile,
-                    None => {
-                        continue;
-                    }
+        let mut other: Vec<(WorldPoint, f32, bool)> = vec![];
+        for tile in line {
+            let terrain_tile = match map.terrain_tile(&tile.grid_point) {
+                Some(terrain_tile) => terrain_tile,
+                None => continue,
+            };
+            let world_point = tile.world_point;
+            let grid_point_opacity = if grid_path.len() <= exclude_firsts {
+                0.0
+            } else {
+                // Smoke and fire are not passed over from high ground
+                let clouds_opacity: f32 = clouds
+                    .iter()
+                    .filter(|cloud| cloud.contains(&world_point))
+                    .map(|cloud| cloud.opacity())
+                    .sum();
+                // Windows and doors are openings in walls
+                let tile_opacity = match map.opening(&tile.grid_point) {
+                    Some(opening) => opening.opacity(),
+                    None => config.terrain_tile_opacity(&terrain_tile.type_),
                 };
-                let grid_point_opacity = if grid_path.len() <= exclude_firsts {
-                    0.0
+                // Fog and darkness are everywhere
+                if tile.overlooks(&terrain_tile.type_) {
+                    clouds_opacity + environment_opacity
                 } else {
-                    config.terrain_tile_opacity(&terrain_tile.type_)
-                };
-                grid_path.push(grid_point);
-                other.push((
-                    WorldPoint::new(pixel_x as f32, pixel_y as f32),
-                    grid_point_opacity,
-                ));
-            }
+                    tile_opacity + clouds_opacity + environment_opacity
+                }
+            };
+            grid_path.push(tile.grid_point);
+            other.push((world_point, grid_point_opacity, tile.masked));
         }
 
         let exclude_opacity_starts_at = grid_path.len() - exclude_lasts;
-        for (i, (_, (world_point, opacity))) in grid_path.points.iter().zip(other).enumerate() {
+        for (i, (_, (world_point, opacity, masked))) in
+            grid_path.points.iter().zip(other).enumerate()
+        {
             // Disable to_scene_item firsts if seen because firing
-            let opacity = if i < exclude_opacity_starts_at {
+            let opacity = if masked {
+                ELEVATION_MASK_OPACITY
+            } else if i < exclude_opacity_starts_at {
                 opacity
             } else {
                 0.
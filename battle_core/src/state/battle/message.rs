@@ -6,13 +6,23 @@ use serde::{Deserialize, Serialize};
 use crate::{
     behavior::{gesture::Gesture, Behavior},
     entity::soldier::WeaponClass,
-    game::flag::FlagsOwnership,
+    game::{
+        ambush::AmbushTrigger,
+        bounding::BoundingOverwatch,
+        environment::Environment,
+        flag::FlagsOwnership,
+        sector::{FireSector, NoFireArea},
+        squad::Formation,
+        target::TargetPolicy,
+        Side,
+    },
+    map::terrain::TileType,
     order::Order,
     physics::{
-        event::{bullet::BulletFire, explosion::Explosion},
+        event::{bullet::BulletFire, cloud::Cloud, explosion::Explosion},
         visibility::Visibility,
     },
-    types::{Angle, SoldierIndex, SquadUuid, VehicleIndex, WorldPoint},
+    types::{Angle, GridPoint, SoldierIndex, SquadUuid, VehicleIndex, WorldPoint},
 };
 
 use super::phase::Phase;
@@ -24,7 +34,13 @@ pub enum BattleStateMessage {
     Vehicle(VehicleIndex, VehicleMessage),
     PushBulletFire(BulletFire),
     PushExplosion(Explosion),
+    PushCloud(Cloud),
     SetVisibilities(HashMap<(SoldierIndex, SoldierIndex), Visibility>),
+    /// Changed visibilities and removed ones
+    UpdateVisibilities(
+        HashMap<(SoldierIndex, SoldierIndex), Visibility>,
+        Vec<(SoldierIndex, SoldierIndex)>,
+    ),
     SetPhase(Phase),
     SetAConnected(bool),
     SetBConnected(bool),
@@ -34,6 +50,28 @@ pub enum BattleStateMessage {
     SetBMorale(Morale),
     SetFlagsOwnership(FlagsOwnership),
     SetSquadLeader(SquadUuid, SoldierIndex),
+    SetSquadFormation(SquadUuid, Formation),
+    SetSquadTargetPolicy(SquadUuid, Option<TargetPolicy>),
+    AddFratricide(Side),
+    /// Squads ordered together (a single squad leaves its group)
+    SetSquadsGroup(Vec<SquadUuid>),
+    /// Squad holds its fire until triggered (None to open fire)
+    SetSquadAmbush(SquadUuid, Option<AmbushTrigger>),
+    /// Squad engages only targets in this sector (None to remove restriction)
+    SetSquadFireSector(SquadUuid, Option<FireSector>),
+    AddNoFireArea(NoFireArea),
+    /// Remove all no fire areas of this side
+    ClearNoFireAreas(Side),
+    /// Replace bounding overwatches of its squads
+    AddBoundingOverwatch(BoundingOverwatch),
+    /// Stop bounding overwatch of this squad
+    RemoveBoundingOverwatch(SquadUuid),
+    /// Moving and covering squads of this squad bounding overwatch exchange their role
+    SwapBoundingOverwatch(SquadUuid),
+    /// Map tile changed (by example destroyed)
+    SetTileType(GridPoint, TileType),
+    /// Weather or daylight changed
+    SetEnvironment(Environment),
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -51,6 +89,7 @@ pub enum SoldierMessage {
     ReloadWeapon(WeaponClass),
     WeaponShot(WeaponClass),
     SetLastShootFrameI(u64),
+    ThrowSmokeGrenade,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -58,6 +97,8 @@ pub enum VehicleMessage {
     SetWorldPosition(WorldPoint),
     SetChassisOrientation(Angle),
     // SetMainTurretOrientation(Angle),
+    /// Vehicle is out of combat and its crew bails out
+    KnockOut,
 }
 
 // TODO : Side effects should not exists : All side effects
@@ -67,4 +108,6 @@ pub enum SideEffect {
     // FIXME Systematic gui side
     RefreshEntityAnimation(SoldierIndex),
     SoldierFinishHisBehavior(SoldierIndex, Option<Order>),
+    RefreshTerrain,
+    RefreshVisibilities,
 }
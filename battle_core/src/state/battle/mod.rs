@@ -5,12 +5,22 @@ use oc_core::morale::Morale;
 use crate::{
     deployment::Deployment,
     entity::{soldier::Soldier, vehicle::Vehicle},
-    game::{control::MapControl, flag::FlagsOwnership, Side},
+    game::{
+        ambush::AmbushTrigger,
+        bounding::BoundingOverwatch,
+        control::MapControl,
+        flag::FlagsOwnership,
+        sector::{FireSector, NoFireArea},
+        squad::Formation,
+        stats::BattleStats,
+        target::TargetPolicy,
+        Side,
+    },
     graphics::vehicle::VehicleGraphicInfos,
     map::Map,
     order::Order,
     physics::{
-        event::{bullet::BulletFire, explosion::Explosion},
+        event::{bullet::BulletFire, cloud::Cloud, explosion::Explosion},
         path::{Direction, PathMode},
         visibility::Visibilities,
     },
@@ -45,8 +55,19 @@ pub struct BattleState {
     soldier_on_board: SoldiersOnBoard,
     vehicle_board: VehicleBoard,
     squads: HashMap<SquadUuid, SquadComposition>,
+    formations: HashMap<SquadUuid, Formation>,
+    target_policies: HashMap<SquadUuid, TargetPolicy>,
+    stats: BattleStats,
+    // Squads ordered together, synchronized at phase lines
+    groups: HashMap<SquadUuid, Vec<SquadUuid>>,
+    bounding_overwatches: Vec<BoundingOverwatch>,
+    // Squads holding their fire
+    ambushes: HashMap<SquadUuid, AmbushTrigger>,
+    fire_sectors: HashMap<SquadUuid, FireSector>,
+    no_fire_areas: Vec<NoFireArea>,
     bullet_fires: Vec<BulletFire>,
     explosions: Vec<Explosion>,
+    clouds: Vec<Cloud>,
     visibilities: Visibilities,
     a_connected: bool,
     b_connected: bool,
@@ -77,8 +98,17 @@ impl BattleState {
             soldier_on_board,
             vehicle_board,
             squads: HashMap::new(),
+            formations: HashMap::new(),
+            target_policies: HashMap::new(),
+            stats: BattleStats::default(),
+            groups: HashMap::new(),
+            bounding_overwatches: vec![],
+            ambushes: HashMap::new(),
+            fire_sectors: HashMap::new(),
+            no_fire_areas: vec![],
             bullet_fires: vec![],
             explosions: vec![],
+            clouds: vec![],
             visibilities: Visibilities::default(),
             a_connected: false,
             b_connected: false,
@@ -100,8 +130,17 @@ impl BattleState {
             soldier_on_board: HashMap::new(),
             vehicle_board: HashMap::new(),
             squads: HashMap::new(),
+            formations: HashMap::new(),
+            target_policies: HashMap::new(),
+            stats: BattleStats::default(),
+            groups: HashMap::new(),
+            bounding_overwatches: vec![],
+            ambushes: HashMap::new(),
+            fire_sectors: HashMap::new(),
+            no_fire_areas: vec![],
             bullet_fires: vec![],
             explosions: vec![],
+            clouds: vec![],
             visibilities: Visibilities::default(),
             a_connected: false, // TODO : should be in (server) Runner ?
             b_connected: false, // TODO : should be in (server) Runner ?
@@ -114,15 +153,30 @@ impl BattleState {
     }
 
     pub fn from_copy(copy: &BattleStateCopy, map: &Map) -> Self {
-        Self::new(
+        let mut map = map.clone();
+        map.apply_tile_changes(copy.tiles());
+        map.set_environment(*copy.environment());
+        let mut battle_state = Self::new(
             copy.frame_i(),
-            map.clone(),
+            map,
             copy.soldiers().clone(),
             copy.vehicles().clone(),
             copy.soldier_on_board().clone(),
             copy.phase().clone(),
             copy.flags().clone(),
-        )
+        );
+        battle_state.formations = copy.formations().iter().cloned().collect();
+        battle_state.target_policies = copy.target_policies().iter().cloned().collect();
+        battle_state.stats = copy.stats().clone();
+        for group in copy.groups() {
+            battle_state.set_group(group);
+        }
+        battle_state.bounding_overwatches = copy.bounding_overwatches().clone();
+        battle_state.ambushes = copy.ambushes().iter().cloned().collect();
+        battle_state.fire_sectors = copy.fire_sectors().iter().cloned().collect();
+        battle_state.no_fire_areas = copy.no_fire_areas().clone();
+        battle_state.clouds = copy.clouds().clone();
+        battle_state
     }
 
     pub fn resolve(&mut self) {
@@ -137,6 +191,10 @@ impl BattleState {
         let frame_i = replaced_frame_i.unwrap_or(self.frame_i);
         self.bullet_fires.retain(|b| !b.finished(frame_i));
         self.explosions.retain(|e| !e.finished(frame_i));
+        self.clouds.retain(|c| !c.finished(frame_i));
+        for cloud in self.clouds.iter_mut() {
+            cloud.update(frame_i);
+        }
     }
 
     pub fn frame_i(&self) -> &u64 {
@@ -183,6 +241,68 @@ impl BattleState {
         self.squads = squads;
     }
 
+    /// Formation chosen for squad (line by default)
+    pub fn squad_formation(&self, squad_uuid: &SquadUuid) -> Formation {
+        self.formations.get(squad_uuid).copied().unwrap_or_default()
+    }
+
+    pub fn stats(&self) -> &BattleStats {
+        &self.stats
+    }
+
+    /// Target policy chosen for squad, if it overrides its soldiers role policy
+    pub fn squad_target_policy(&self, squad_uuid: &SquadUuid) -> Option<&TargetPolicy> {
+        self.target_policies.get(squad_uuid)
+    }
+
+    pub fn soldier_target_policy(&self, soldier: &Soldier) -> TargetPolicy {
+        self.squad_target_policy(&soldier.squad_uuid())
+            .copied()
+            .unwrap_or_else(|| TargetPolicy::role_default(soldier))
+    }
+
+    /// Other squads ordered with this squad (still in the same group)
+    pub fn squad_group(&self, squad_uuid: &SquadUuid) -> Vec<SquadUuid> {
+        let group = match self.groups.get(squad_uuid) {
+            Some(group) => group,
+            None => return vec![],
+        };
+        group
+            .iter()
+            .filter(|other| *other != squad_uuid && self.groups.get(other) == Some(group))
+            .copied()
+            .collect()
+    }
+
+    /// Ambush trigger of squad, if it holds its fire
+    pub fn squad_ambush(&self, squad_uuid: &SquadUuid) -> Option<&AmbushTrigger> {
+        self.ambushes.get(squad_uuid)
+    }
+
+    /// Fire sector of squad, if it is restricted
+    pub fn squad_fire_sector(&self, squad_uuid: &SquadUuid) -> Option<&FireSector> {
+        self.fire_sectors.get(squad_uuid)
+    }
+
+    pub fn no_fire_areas(&self) -> &Vec<NoFireArea> {
+        &self.no_fire_areas
+    }
+
+    pub fn bounding_overwatches(&self) -> &Vec<BoundingOverwatch> {
+        &self.bounding_overwatches
+    }
+
+    /// Group given squads, a single squad is removed from its group
+    fn set_group(&mut self, squads: &[SquadUuid]) {
+        for squad_uuid in squads {
+            if squads.len() > 1 {
+                self.groups.insert(*squad_uuid, squads.to_vec());
+            } else {
+                self.groups.remove(squad_uuid);
+            }
+        }
+    }
+
     pub fn all_orders(&self, side: &Side) -> Vec<(SquadUuid, &Order)> {
         let mut orders: Vec<(SquadUuid, &Order)> = vec![];
 
@@ -218,6 +338,10 @@ impl BattleState {
         self.explosions.as_ref()
     }
 
+    pub fn clouds(&self) -> &Vec<Cloud> {
+        self.clouds.as_ref()
+    }
+
     pub fn soldier_on_board(&self) -> &SoldiersOnBoard {
         &self.soldier_on_board
     }
@@ -273,9 +397,25 @@ impl BattleState {
                 explosion.init(frame_i + 1);
                 self.explosions.push(explosion)
             }
+            BattleStateMessage::PushCloud(cloud) => {
+                let mut cloud = cloud.clone();
+                cloud.init(frame_i + 1);
+                self.clouds.push(cloud)
+            }
             BattleStateMessage::SetVisibilities(visibilities) => {
                 self.visibilities.set(visibilities.clone())
             }
+            BattleStateMessage::UpdateVisibilities(changed, removed) => {
+                self.visibilities.update(changed.clone(), removed)
+            }
+            BattleStateMessage::SetTileType(grid_point, type_) => {
+                self.map.set_tile_type(grid_point, type_.clone());
+                return vec![SideEffect::RefreshTerrain];
+            }
+            BattleStateMessage::SetEnvironment(environment) => {
+                self.map.set_environment(*environment);
+                return vec![SideEffect::RefreshVisibilities];
+            }
             BattleStateMessage::SetPhase(phase) => self.phase = phase.clone(),
             BattleStateMessage::SetAConnected(value) => self.a_connected = *value,
             BattleStateMessage::SetBConnected(value) => self.b_connected = *value,
@@ -284,6 +424,57 @@ impl BattleState {
             BattleStateMessage::SetFlagsOwnership(flags) => self.flags = flags.clone(),
             BattleStateMessage::SetAMorale(morale) => self.a_morale = morale.clone(),
             BattleStateMessage::SetBMorale(morale) => self.b_morale = morale.clone(),
+            BattleStateMessage::SetSquadFormation(squad_uuid, formation) => {
+                self.formations.insert(*squad_uuid, *formation);
+            }
+            BattleStateMessage::SetSquadTargetPolicy(squad_uuid, policy) => match policy {
+                Some(policy) => {
+                    self.target_policies.insert(*squad_uuid, *policy);
+                }
+                None => {
+                    self.target_policies.remove(squad_uuid);
+                }
+            },
+            BattleStateMessage::AddFratricide(side) => self.stats.add_fratricide(side),
+            BattleStateMessage::SetSquadsGroup(squads) => self.set_group(squads),
+            BattleStateMessage::SetSquadAmbush(squad_uuid, trigger) => match trigger {
+                Some(trigger) => {
+                    self.ambushes.insert(*squad_uuid, trigger.clone());
+                }
+                None => {
+                    self.ambushes.remove(squad_uuid);
+                }
+            },
+            BattleStateMessage::SetSquadFireSector(squad_uuid, sector) => match sector {
+                Some(sector) => {
+                    self.fire_sectors.insert(*squad_uuid, *sector);
+                }
+                None => {
+                    self.fire_sectors.remove(squad_uuid);
+                }
+            },
+            BattleStateMessage::AddNoFireArea(area) => self.no_fire_areas.push(area.clone()),
+            BattleStateMessage::ClearNoFireAreas(side) => {
+                self.no_fire_areas.retain(|area| area.side() != side)
+            }
+            BattleStateMessage::AddBoundingOverwatch(bounding_overwatch) => {
+                self.bounding_overwatches.retain(|b| {
+                    !b.contains(bounding_overwatch.moving())
+                        && !b.contains(bounding_overwatch.covering())
+                });
+                self.bounding_overwatches.push(bounding_overwatch.clone());
+            }
+            BattleStateMessage::RemoveBoundingOverwatch(squad_uuid) => {
+                self.bounding_overwatches
+                    .retain(|b| !b.contains(squad_uuid));
+            }
+            BattleStateMessage::SwapBoundingOverwatch(squad_uuid) => {
+                for bounding_overwatch in &mut self.bounding_overwatches {
+                    if bounding_overwatch.contains(squad_uuid) {
+                        bounding_overwatch.swap()
+                    }
+                }
+            }
             BattleStateMessage::SetSquadLeader(squad_uuid, soldier_index) => {
                 *self
                     .squads
@@ -321,6 +512,67 @@ impl BattleState {
         ]
     }
 
+    /// Chosen squad formations, sorted by squad
+    fn formations(&self) -> Vec<(SquadUuid, Formation)> {
+        let mut formations: Vec<(SquadUuid, Formation)> = self
+            .formations
+            .iter()
+            .map(|(squad_uuid, formation)| (*squad_uuid, *formation))
+            .collect();
+        formations.sort_by_key(|(squad_uuid, _)| squad_uuid.0);
+        formations
+    }
+
+    /// Chosen squad target policies, sorted by squad
+    fn target_policies(&self) -> Vec<(SquadUuid, TargetPolicy)> {
+        let mut target_policies: Vec<(SquadUuid, TargetPolicy)> = self
+            .target_policies
+            .iter()
+            .map(|(squad_uuid, policy)| (*squad_uuid, *policy))
+            .collect();
+        target_policies.sort_by_key(|(squad_uuid, _)| squad_uuid.0);
+        target_policies
+    }
+
+    /// Squads ambushes, sorted by squad
+    fn ambushes(&self) -> Vec<(SquadUuid, AmbushTrigger)> {
+        let mut ambushes: Vec<(SquadUuid, AmbushTrigger)> = self
+            .ambushes
+            .iter()
+            .map(|(squad_uuid, trigger)| (*squad_uuid, trigger.clone()))
+            .collect();
+        ambushes.sort_by_key(|(squad_uuid, _)| squad_uuid.0);
+        ambushes
+    }
+
+    /// Squads fire sectors, sorted by squad
+    fn fire_sectors(&self) -> Vec<(SquadUuid, FireSector)> {
+        let mut fire_sectors: Vec<(SquadUuid, FireSector)> = self
+            .fire_sectors
+            .iter()
+            .map(|(squad_uuid, sector)| (*squad_uuid, *sector))
+            .collect();
+        fire_sectors.sort_by_key(|(squad_uuid, _)| squad_uuid.0);
+        fire_sectors
+    }
+
+    /// Squad groups, each one once
+    fn groups(&self) -> Vec<Vec<SquadUuid>> {
+        let mut groups: Vec<Vec<SquadUuid>> = vec![];
+        for group in self.groups.values() {
+            if !groups.contains(group) {
+                groups.push(group.clone());
+            }
+        }
+        groups.sort_by_key(|group| {
+            group
+                .iter()
+                .map(|squad_uuid| squad_uuid.0)
+                .collect::<Vec<_>>()
+        });
+        groups
+    }
+
     pub fn copy(&self) -> BattleStateCopy {
         BattleStateCopy::new(
             self.frame_i,
@@ -329,6 +581,17 @@ impl BattleState {
             self.soldier_on_board.clone(),
             self.phase.clone(),
             self.flags.clone(),
+            self.map.tile_changes(),
+            *self.map.environment(),
+            self.formations(),
+            self.target_policies(),
+            self.stats.clone(),
+            self.groups(),
+            self.bounding_overwatches.clone(),
+            self.ambushes(),
+            self.fire_sectors(),
+            self.no_fire_areas.clone(),
+            self.clouds.clone(),
         )
     }
 
@@ -68,7 +68,7 @@ impl BattleState {
                         OrderMarkerIndex(0),
                     ));
                 }
-                Order::SuppressFire(point) => {
+                Order::SuppressFire(point) | Order::SmokeScreen(point) => {
                     marker_data.push((
                         order.clone(),
                         marker.clone().unwrap(), // FIXME unwrap to remove
@@ -44,6 +44,7 @@ impl BattleState {
             SoldierMessage::ReloadWeapon(class) => soldier.reload_weapon(class),
             SoldierMessage::WeaponShot(class) => soldier.weapon_shot(class),
             SoldierMessage::SetLastShootFrameI(frame_i) => soldier.set_last_shoot_frame_i(*frame_i),
+            SoldierMessage::ThrowSmokeGrenade => soldier.throw_smoke_grenade(),
         }
 
         vec![]
@@ -2,7 +2,7 @@ use std::collections::{HashMap, HashSet};
 
 use crate::{
     behavior::BehaviorMode,
-    types::{SoldierIndex, SquadComposition, SquadUuid},
+    types::{SoldierIndex, SquadComposition, SquadUuid, WorldPaths},
 };
 
 use super::BattleState;
@@ -56,8 +56,204 @@ impl BattleState {
             .collect()
     }
 
+    /// Squad leader must wait at phase line until other squads of its group reach it
+    pub fn squad_waits_at_phase_line(&self, squad_uuid: &SquadUuid, paths: &WorldPaths) -> bool {
+        if !paths.is_reaching_phase_line() {
+            return false;
+        }
+
+        self.squad_group(squad_uuid).iter().any(|other_squad_uuid| {
+            let other_leader = self.soldier(self.squad(*other_squad_uuid).leader());
+            other_leader.alive()
+                && other_leader
+                    .behavior()
+                    .world_paths()
+                    .map(|other_paths| other_paths.is_behind(paths))
+                    .unwrap_or(false)
+        })
+    }
+
     pub fn squad_behavior_mode(&self, squad_index: &SquadUuid) -> BehaviorMode {
         let squad = self.squad(*squad_index);
         self.soldier_behavior_mode(self.soldier(squad.leader()))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use rstest::*;
+
+    use crate::{
+        behavior::Behavior,
+        entity::soldier::Soldier,
+        game::{flag::FlagsOwnership, Side},
+        map::{decor::Decor, terrain::TerrainTile, Map},
+        state::battle::{
+            message::{BattleStateMessage, SoldierMessage},
+            phase::Phase,
+        },
+        types::{WorldPath, WorldPoint},
+    };
+
+    use super::*;
+
+    /// Three squads of one soldier on a 10x10 tiles short grass map
+    fn battle_state() -> BattleState {
+        let terrain_tiles = (0..10 * 10)
+            .map(|i| {
+                TerrainTile::from_str_id("ShortGrass", 10, 10, 0.1, 0.1, i % 10, i / 10, 0, 0)
+                    .unwrap()
+            })
+            .collect();
+        let map = Map::new(
+            "TestMap".to_string(),
+            PathBuf::from("."),
+            PathBuf::from("."),
+            PathBuf::from("."),
+            vec![],
+            vec![],
+            10,
+            10,
+            terrain_tiles,
+            10,
+            10,
+            Decor::new(vec![], vec![]),
+            vec![],
+        );
+        let soldiers = (0..3)
+            .map(|i| {
+                Soldier::new(
+                    SoldierIndex(i),
+                    WorldPoint::new(10. + i as f32 * 20., 10.),
+                    SquadUuid(i),
+                    Side::A,
+                    None,
+                    vec![],
+                )
+            })
+            .collect();
+        let mut battle_state = BattleState::new(
+            0,
+            map,
+            soldiers,
+            vec![],
+            HashMap::new(),
+            Phase::Battle,
+            FlagsOwnership::empty(),
+        );
+        battle_state.resolve();
+        battle_state
+    }
+
+    /// Paths with given remaining points count in each path
+    fn paths(points: &[usize]) -> WorldPaths {
+        WorldPaths::new(
+            points
+                .iter()
+                .map(|count| WorldPath::new(vec![WorldPoint::new(50., 50.); *count]))
+                .collect(),
+        )
+    }
+
+    fn move_to(battle_state: &mut BattleState, soldier_index: usize, points: &[usize]) {
+        battle_state.react(
+            &BattleStateMessage::Soldier(
+                SoldierIndex(soldier_index),
+                SoldierMessage::SetBehavior(Behavior::MoveTo(paths(points))),
+            ),
+            0,
+        );
+    }
+
+    #[rstest]
+    fn group_membership_after_regrouping_and_disbanding() {
+        // Given
+        let mut battle_state = battle_state();
+        let group = |squads: Vec<usize>| {
+            BattleStateMessage::SetSquadsGroup(squads.into_iter().map(SquadUuid).collect())
+        };
+
+        // When grouped
+        battle_state.react(&group(vec![0, 1, 2]), 0);
+
+        // Then
+        assert_eq!(
+            battle_state.squad_group(&SquadUuid(0)),
+            vec![SquadUuid(1), SquadUuid(2)]
+        );
+
+        // When squads 1 and 2 are regrouped together
+        battle_state.react(&group(vec![1, 2]), 0);
+
+        // Then
+        assert_eq!(battle_state.squad_group(&SquadUuid(0)), vec![]);
+        assert_eq!(battle_state.squad_group(&SquadUuid(1)), vec![SquadUuid(2)]);
+
+        // When squad 2 leaves its group
+        battle_state.react(&group(vec![2]), 0);
+
+        // Then
+        assert_eq!(battle_state.squad_group(&SquadUuid(1)), vec![]);
+        assert_eq!(battle_state.squad_group(&SquadUuid(2)), vec![]);
+    }
+
+    #[rstest]
+    // Other squad is still on its way to the phase line
+    #[case(&[2, 2], true, true, true)]
+    // Other squad reaches the phase line too
+    #[case(&[1, 2], true, true, false)]
+    // Other squad passed the phase line
+    #[case(&[2], true, true, false)]
+    // Other squad is not in the group
+    #[case(&[2, 2], false, true, false)]
+    // Other squad leader is dead
+    #[case(&[2, 2], true, false, false)]
+    fn squad_waits_at_phase_line(
+        #[case] other_points: &[usize],
+        #[case] grouped: bool,
+        #[case] other_alive: bool,
+        #[case] expected: bool,
+    ) {
+        // Given squad 0 reaching the phase line
+        let mut battle_state = battle_state();
+        if grouped {
+            battle_state.react(
+                &BattleStateMessage::SetSquadsGroup(vec![SquadUuid(0), SquadUuid(1)]),
+                0,
+            );
+        }
+        move_to(&mut battle_state, 0, &[1, 2]);
+        move_to(&mut battle_state, 1, other_points);
+        if !other_alive {
+            battle_state.react(
+                &BattleStateMessage::Soldier(SoldierIndex(1), SoldierMessage::SetAlive(false)),
+                0,
+            );
+        }
+
+        // When
+        let waits = battle_state.squad_waits_at_phase_line(&SquadUuid(0), &paths(&[1, 2]));
+
+        // Then
+        assert_eq!(waits, expected);
+    }
+
+    #[rstest]
+    fn squad_does_not_wait_before_phase_line() {
+        // Given
+        let mut battle_state = battle_state();
+        battle_state.react(
+            &BattleStateMessage::SetSquadsGroup(vec![SquadUuid(0), SquadUuid(1)]),
+            0,
+        );
+        move_to(&mut battle_state, 1, &[3, 2]);
+
+        // When
+        let waits = battle_state.squad_waits_at_phase_line(&SquadUuid(0), &paths(&[2, 2]));
+
+        // Then
+        assert!(!waits);
+    }
+}
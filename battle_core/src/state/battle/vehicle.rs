@@ -1,5 +1,6 @@
 use crate::{
     entity::vehicle::OnBoardPlace,
+    game::Side,
     graphics::vehicle::VehicleGraphicInfos,
     types::{SoldierIndex, VehicleIndex, WorldPoint},
     utils::apply_angle_on_point,
@@ -45,6 +46,17 @@ impl BattleState {
                 vehicle.set_chassis_orientation(*angle);
                 self.propagate_vehicle_position(*vehicle_index);
             }
+            VehicleMessage::KnockOut => {
+                vehicle.set_knocked_out(true);
+                let crew = self.vehicle_board.remove(vehicle_index).unwrap_or_default();
+                for (_, soldier_index) in &crew {
+                    self.soldier_on_board.remove(soldier_index);
+                }
+                return crew
+                    .iter()
+                    .map(|(_, soldier_index)| SideEffect::RefreshEntityAnimation(*soldier_index))
+                    .collect();
+            }
         }
 
         vec![]
@@ -82,6 +94,14 @@ impl BattleState {
         }
     }
 
+    /// Side of the vehicle crew (if any)
+    pub fn vehicle_side(&self, vehicle_index: VehicleIndex) -> Option<&Side> {
+        self.vehicle_board()
+            .get(&vehicle_index)
+            .and_then(|board| board.first())
+            .map(|(_, soldier_index)| self.soldier(*soldier_index).side())
+    }
+
     pub fn soldier_vehicle_place(&self, soldier_index: SoldierIndex) -> Option<&OnBoardPlace> {
         if let Some((_, place)) = self.soldier_board(soldier_index) {
             return Some(place);
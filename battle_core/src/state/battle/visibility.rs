@@ -58,8 +58,15 @@ impl BattleState {
             .iter()
             .map(|i| self.soldier(*i))
             .any(|s| {
-                Visibility::between_soldier_and_point(config, s, point, self.map(), exclude_lasts)
-                    .visible
+                Visibility::between_soldier_and_point(
+                    config,
+                    s,
+                    point,
+                    self.map(),
+                    self.clouds(),
+                    exclude_lasts,
+                )
+                .visible
             })
     }
 
@@ -70,7 +77,14 @@ impl BattleState {
         point: &WorldPoint,
         exclude_lasts: usize,
     ) -> bool {
-        Visibility::between_soldier_and_point(config, soldier, point, self.map(), exclude_lasts)
-            .visible
+        Visibility::between_soldier_and_point(
+            config,
+            soldier,
+            point,
+            self.map(),
+            self.clouds(),
+            exclude_lasts,
+        )
+        .visible
     }
 }
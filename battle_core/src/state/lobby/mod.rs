@@ -0,0 +1,418 @@
+use oc_core::spawn::SpawnZoneName;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    deployment::Deployment,
+    game::control::MapControl,
+    game::{
+        environment::{Environment, EnvironmentChange},
+        Side,
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum LobbyMessage {
+    Join(Side),
+    Leave(Side),
+    SetMapName(String),
+    SetDeployment(Deployment),
+    SetControl(Side, MapControl),
+    SetReady(Side, bool),
+    SetLockstep(bool),
+    SetAi(Side, bool),
+    SetEnvironments(Vec<EnvironmentChange>),
+}
+
+/// Battle settings agreed by clients in the lobby. Used by server to build the battle state
+/// and by clients to know which map and controls to display. In lockstep mode, each side
+/// client run the battle itself from this setup.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BattleSetup {
+    map_name: String,
+    deployment: Deployment,
+    a_control: MapControl,
+    b_control: MapControl,
+    sides: Vec<Side>,
+    ais: Vec<Side>,
+    lockstep: bool,
+    seed: u64,
+    environments: Vec<EnvironmentChange>,
+}
+
+impl BattleSetup {
+    pub fn new(
+        map_name: String,
+        deployment: Deployment,
+        a_control: MapControl,
+        b_control: MapControl,
+        sides: Vec<Side>,
+        ais: Vec<Side>,
+        lockstep: bool,
+    ) -> Self {
+        Self {
+            map_name,
+            deployment,
+            a_control,
+            b_control,
+            sides,
+            ais,
+            lockstep,
+            seed: 0,
+            environments: vec![],
+        }
+    }
+
+    pub fn map_name(&self) -> &str {
+        &self.map_name
+    }
+
+    pub fn deployment(&self) -> &Deployment {
+        &self.deployment
+    }
+
+    pub fn a_control(&self) -> &MapControl {
+        &self.a_control
+    }
+
+    pub fn b_control(&self) -> &MapControl {
+        &self.b_control
+    }
+
+    /// Sides played by connected clients
+    pub fn sides(&self) -> &Vec<Side> {
+        &self.sides
+    }
+
+    /// Sides played by AI
+    pub fn ais(&self) -> &Vec<Side> {
+        &self.ais
+    }
+
+    pub fn lockstep(&self) -> bool {
+        self.lockstep
+    }
+
+    /// Random generator seed of the battle simulation
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// Environment changes, sorted by start
+    pub fn environments(&self) -> &Vec<EnvironmentChange> {
+        &self.environments
+    }
+
+    pub fn set_environments(&mut self, mut environments: Vec<EnvironmentChange>) {
+        environments.sort_by_key(|change| change.start());
+        self.environments = environments;
+    }
+
+    /// Environment at battle state creation
+    pub fn environment(&self) -> Environment {
+        self.environments
+            .iter()
+            .rev()
+            .find(|change| change.start() == 0)
+            .map(|change| *change.environment())
+            .unwrap_or_default()
+    }
+}
+
+/// Negotiation state between connected clients before a battle. Any change of the proposed
+/// settings invalidates previous agreements : each connected side must be ready again.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct LobbyState {
+    map_name: Option<String>,
+    deployment: Option<Deployment>,
+    a_control: Option<MapControl>,
+    b_control: Option<MapControl>,
+    lockstep: bool,
+    environments: Vec<EnvironmentChange>,
+    a_ai: bool,
+    b_ai: bool,
+    a_connected: bool,
+    b_connected: bool,
+    a_ready: bool,
+    b_ready: bool,
+    error: Option<String>,
+}
+
+impl LobbyState {
+    pub fn map_name(&self) -> &Option<String> {
+        &self.map_name
+    }
+
+    pub fn deployment(&self) -> &Option<Deployment> {
+        &self.deployment
+    }
+
+    pub fn a_control(&self) -> &Option<MapControl> {
+        &self.a_control
+    }
+
+    pub fn b_control(&self) -> &Option<MapControl> {
+        &self.b_control
+    }
+
+    pub fn lockstep(&self) -> bool {
+        self.lockstep
+    }
+
+    pub fn environments(&self) -> &Vec<EnvironmentChange> {
+        &self.environments
+    }
+
+    /// True if side is played by AI
+    pub fn ai(&self, side: &Side) -> bool {
+        match side {
+            Side::A => self.a_ai,
+            Side::B => self.b_ai,
+            Side::All => self.a_ai && self.b_ai,
+        }
+    }
+
+    pub fn connected(&self, side: &Side) -> bool {
+        match side {
+            Side::A => self.a_connected,
+            Side::B => self.b_connected,
+            Side::All => self.a_connected && self.b_connected,
+        }
+    }
+
+    pub fn ready(&self, side: &Side) -> bool {
+        match side {
+            Side::A => self.a_ready,
+            Side::B => self.b_ready,
+            Side::All => self.a_ready && self.b_ready,
+        }
+    }
+
+    pub fn error(&self) -> &Option<String> {
+        &self.error
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+        self.reset_ready();
+    }
+
+    fn reset_ready(&mut self) {
+        self.a_ready = false;
+        self.b_ready = false;
+    }
+
+    fn proposal_changed(&mut self) {
+        self.error = None;
+        self.reset_ready();
+    }
+
+    pub fn react(&mut self, message: &LobbyMessage) {
+        match message {
+            LobbyMessage::Join(side) => match side {
+                Side::A => self.a_connected = true,
+                Side::B => self.b_connected = true,
+                Side::All => {}
+            },
+            LobbyMessage::Leave(side) => match side {
+                Side::A => {
+                    self.a_connected = false;
+                    self.a_ready = false;
+                }
+                Side::B => {
+                    self.b_connected = false;
+                    self.b_ready = false;
+                }
+                Side::All => {}
+            },
+            LobbyMessage::SetMapName(map_name) => {
+                self.map_name = Some(map_name.clone());
+                self.proposal_changed();
+            }
+            LobbyMessage::SetDeployment(deployment) => {
+                self.deployment = Some(deployment.clone());
+                self.proposal_changed();
+            }
+            LobbyMessage::SetControl(side, control) => {
+                match side {
+                    Side::A => self.a_control = Some(control.clone()),
+                    Side::B => self.b_control = Some(control.clone()),
+                    Side::All => {
+                        self.a_control = Some(control.clone());
+                        self.b_control = Some(control.clone());
+                    }
+                }
+                self.proposal_changed();
+            }
+            LobbyMessage::SetReady(side, ready) => match side {
+                Side::A => self.a_ready = self.a_connected && *ready,
+                Side::B => self.b_ready = self.b_connected && *ready,
+                Side::All => {}
+            },
+            LobbyMessage::SetLockstep(lockstep) => {
+                self.lockstep = *lockstep;
+                self.proposal_changed();
+            }
+            LobbyMessage::SetAi(side, ai) => {
+                match side {
+                    Side::A => self.a_ai = *ai,
+                    Side::B => self.b_ai = *ai,
+                    Side::All => {
+                        self.a_ai = *ai;
+                        self.b_ai = *ai;
+                    }
+                }
+                self.proposal_changed();
+            }
+            LobbyMessage::SetEnvironments(environments) => {
+                self.environments = environments.clone();
+                self.proposal_changed();
+            }
+        }
+    }
+
+    /// Return the battle setup if all proposals are done and each side is played by an AI
+    /// or by a connected and ready client. Side without proposed control control all the map.
+    pub fn agreed(&self) -> Option<BattleSetup> {
+        if ![Side::A, Side::B]
+            .iter()
+            .all(|side| self.ai(side) || (self.connected(side) && self.ready(side)))
+        {
+            return None;
+        }
+
+        let map_name = self.map_name.as_ref()?;
+        let deployment = self.deployment.as_ref()?;
+        let whole_map = MapControl::new(vec![SpawnZoneName::All]);
+        let sides = [Side::A, Side::B]
+            .into_iter()
+            .filter(|side| self.connected(side))
+            .collect();
+        let ais = [Side::A, Side::B]
+            .into_iter()
+            .filter(|side| self.ai(side))
+            .collect();
+        let mut setup = BattleSetup::new(
+            map_name.clone(),
+            deployment.clone(),
+            self.a_control.clone().unwrap_or(whole_map.clone()),
+            self.b_control.clone().unwrap_or(whole_map),
+            sides,
+            ais,
+            self.lockstep,
+        );
+        setup.set_environments(self.environments.clone());
+        Some(setup)
+    }
+
+    /// Lobby state to use when a battle is finished : proposals are kept as next battle
+    /// proposals but clients must join again.
+    pub fn after_battle(&self) -> Self {
+        Self {
+            map_name: self.map_name.clone(),
+            deployment: self.deployment.clone(),
+            a_control: self.a_control.clone(),
+            b_control: self.b_control.clone(),
+            lockstep: self.lockstep,
+            environments: self.environments.clone(),
+            a_ai: self.a_ai,
+            b_ai: self.b_ai,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use crate::types::SoldiersOnBoard;
+
+    use super::*;
+
+    #[fixture]
+    fn proposed() -> LobbyState {
+        let mut state = LobbyState::default();
+        state.react(&LobbyMessage::SetMapName("Demo1".to_string()));
+        state.react(&LobbyMessage::SetDeployment(Deployment::from((
+            vec![],
+            vec![],
+            SoldiersOnBoard::new(),
+        ))));
+        state
+    }
+
+    #[rstest]
+    #[case(vec![], false)]
+    #[case(vec![Side::A], false)]
+    #[case(vec![Side::A, Side::B], true)]
+    fn agreed_when_connected_sides_are_ready(
+        proposed: LobbyState,
+        #[case] sides: Vec<Side>,
+        #[case] expected: bool,
+    ) {
+        // Given
+        let mut state = proposed;
+        for side in &sides {
+            state.react(&LobbyMessage::Join(*side));
+        }
+
+        // When
+        for side in &sides {
+            state.react(&LobbyMessage::SetReady(*side, true));
+        }
+
+        // Then
+        assert_eq!(state.agreed().is_some(), expected)
+    }
+
+    #[rstest]
+    #[case(vec![], false)]
+    #[case(vec![Side::A], true)]
+    fn ai_battle_agreed_when_connected_sides_are_ready(
+        proposed: LobbyState,
+        #[case] sides: Vec<Side>,
+        #[case] expected: bool,
+    ) {
+        // Given
+        let mut state = proposed;
+        state.react(&LobbyMessage::SetAi(Side::B, true));
+
+        // When
+        for side in &sides {
+            state.react(&LobbyMessage::Join(*side));
+            state.react(&LobbyMessage::SetReady(*side, true));
+        }
+
+        // Then
+        let setup = state.agreed();
+        assert_eq!(setup.is_some(), expected);
+        if let Some(setup) = setup {
+            assert_eq!(setup.ais(), &vec![Side::B]);
+        }
+    }
+
+    #[rstest]
+    fn proposal_change_invalidate_agreement(proposed: LobbyState) {
+        // Given
+        let mut state = proposed;
+        state.react(&LobbyMessage::Join(Side::A));
+        state.react(&LobbyMessage::Join(Side::B));
+        state.react(&LobbyMessage::SetReady(Side::A, true));
+        state.react(&LobbyMessage::SetReady(Side::B, true));
+
+        // When
+        state.react(&LobbyMessage::SetControl(
+            Side::B,
+            MapControl::new(vec![SpawnZoneName::Est]),
+        ));
+
+        // Then
+        assert!(state.agreed().is_none());
+        assert!(!state.ready(&Side::A));
+        assert!(!state.ready(&Side::B));
+    }
+}
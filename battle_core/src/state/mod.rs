@@ -1,2 +1,3 @@
 pub mod battle;
 pub mod client;
+pub mod lobby;
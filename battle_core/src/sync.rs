@@ -1,10 +1,23 @@
+use std::{collections::hash_map::DefaultHasher, hash::Hasher};
+
 use serde::{Deserialize, Serialize};
 
 use crate::{
     entity::{soldier::Soldier, vehicle::Vehicle},
-    game::flag::FlagsOwnership,
+    game::{
+        ambush::AmbushTrigger,
+        bounding::BoundingOverwatch,
+        environment::Environment,
+        flag::FlagsOwnership,
+        sector::{FireSector, NoFireArea},
+        squad::Formation,
+        stats::BattleStats,
+        target::TargetPolicy,
+    },
+    map::terrain::TileType,
+    physics::event::cloud::Cloud,
     state::battle::phase::Phase,
-    types::SoldiersOnBoard,
+    types::{GridPoint, SoldiersOnBoard, SquadUuid},
 };
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -15,9 +28,21 @@ pub struct BattleStateCopy {
     soldier_on_board: SoldiersOnBoard,
     phase: Phase,
     flags: FlagsOwnership,
+    tiles: Vec<(GridPoint, TileType)>,
+    environment: Environment,
+    formations: Vec<(SquadUuid, Formation)>,
+    target_policies: Vec<(SquadUuid, TargetPolicy)>,
+    stats: BattleStats,
+    groups: Vec<Vec<SquadUuid>>,
+    bounding_overwatches: Vec<BoundingOverwatch>,
+    ambushes: Vec<(SquadUuid, AmbushTrigger)>,
+    fire_sectors: Vec<(SquadUuid, FireSector)>,
+    no_fire_areas: Vec<NoFireArea>,
+    clouds: Vec<Cloud>,
 }
 
 impl BattleStateCopy {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         frame_i: u64,
         soldiers: Vec<Soldier>,
@@ -25,6 +50,17 @@ impl BattleStateCopy {
         soldier_on_board: SoldiersOnBoard,
         phase: Phase,
         flags: FlagsOwnership,
+        tiles: Vec<(GridPoint, TileType)>,
+        environment: Environment,
+        formations: Vec<(SquadUuid, Formation)>,
+        target_policies: Vec<(SquadUuid, TargetPolicy)>,
+        stats: BattleStats,
+        groups: Vec<Vec<SquadUuid>>,
+        bounding_overwatches: Vec<BoundingOverwatch>,
+        ambushes: Vec<(SquadUuid, AmbushTrigger)>,
+        fire_sectors: Vec<(SquadUuid, FireSector)>,
+        no_fire_areas: Vec<NoFireArea>,
+        clouds: Vec<Cloud>,
     ) -> BattleStateCopy {
         Self {
             frame_i,
@@ -33,6 +69,17 @@ impl BattleStateCopy {
             soldier_on_board,
             phase,
             flags,
+            tiles,
+            environment,
+            formations,
+            target_policies,
+            stats,
+            groups,
+            bounding_overwatches,
+            ambushes,
+            fire_sectors,
+            no_fire_areas,
+            clouds,
         }
     }
 
@@ -59,4 +106,173 @@ impl BattleStateCopy {
     pub fn flags(&self) -> &FlagsOwnership {
         &self.flags
     }
+
+    /// Map tiles changed during battle
+    pub fn tiles(&self) -> &Vec<(GridPoint, TileType)> {
+        &self.tiles
+    }
+
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    /// Squad formations, sorted by squad
+    pub fn formations(&self) -> &Vec<(SquadUuid, Formation)> {
+        &self.formations
+    }
+
+    /// Squad target policies, sorted by squad
+    pub fn target_policies(&self) -> &Vec<(SquadUuid, TargetPolicy)> {
+        &self.target_policies
+    }
+
+    pub fn stats(&self) -> &BattleStats {
+        &self.stats
+    }
+
+    /// Squads ordered together
+    pub fn groups(&self) -> &Vec<Vec<SquadUuid>> {
+        &self.groups
+    }
+
+    pub fn bounding_overwatches(&self) -> &Vec<BoundingOverwatch> {
+        &self.bounding_overwatches
+    }
+
+    /// Squads ambushes, sorted by squad
+    pub fn ambushes(&self) -> &Vec<(SquadUuid, AmbushTrigger)> {
+        &self.ambushes
+    }
+
+    /// Squads fire sectors, sorted by squad
+    pub fn fire_sectors(&self) -> &Vec<(SquadUuid, FireSector)> {
+        &self.fire_sectors
+    }
+
+    pub fn no_fire_areas(&self) -> &Vec<NoFireArea> {
+        &self.no_fire_areas
+    }
+
+    /// Smoke and fire clouds
+    pub fn clouds(&self) -> &Vec<Cloud> {
+        &self.clouds
+    }
+
+    /// Hash of the copy content, identical for identical copies (used by lockstep peers to
+    /// detect desync). Peers must be built with same version to produce comparable hashes.
+    pub fn checksum(&self) -> u64 {
+        // Hash map iteration order is random, so sort it first
+        let mut soldier_on_board: Vec<_> = self.soldier_on_board.iter().collect();
+        soldier_on_board.sort_by_key(|(soldier_index, _)| soldier_index.0);
+
+        let bytes = bincode::serialize(&(
+            self.frame_i,
+            &self.soldiers,
+            &self.vehicles,
+            soldier_on_board,
+            &self.phase,
+            &self.flags,
+            &self.tiles,
+            &self.environment,
+            &self.formations,
+            &self.target_policies,
+            &self.groups,
+            &self.bounding_overwatches,
+            &self.ambushes,
+            &self.fire_sectors,
+            &self.no_fire_areas,
+        ))
+        .expect("Battle state copy must be serializable");
+        let stats_bytes =
+            bincode::serialize(&self.stats).expect("Battle stats must be serializable");
+        let clouds_bytes =
+            bincode::serialize(&self.clouds).expect("Battle clouds must be serializable");
+        let mut hasher = DefaultHasher::new();
+        hasher.write(&bytes);
+        hasher.write(&stats_bytes);
+        hasher.write(&clouds_bytes);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use crate::{
+        entity::vehicle::OnBoardPlace,
+        physics::event::cloud::CloudType,
+        types::{Distance, SoldierIndex, VehicleIndex, WorldPoint},
+    };
+
+    use super::*;
+
+    fn copy(soldier_on_board: SoldiersOnBoard, frame_i: u64) -> BattleStateCopy {
+        BattleStateCopy::new(
+            frame_i,
+            vec![],
+            vec![],
+            soldier_on_board,
+            Phase::Placement,
+            FlagsOwnership::empty(),
+            vec![],
+            Environment::default(),
+            vec![],
+            vec![],
+            BattleStats::default(),
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        )
+    }
+
+    #[rstest]
+    fn checksum_is_independent_of_hash_map_order() {
+        // Given
+        let places = [
+            OnBoardPlace::Driver,
+            OnBoardPlace::MainTurretGunner,
+            OnBoardPlace::MainCommandment,
+            OnBoardPlace::Passenger1,
+        ];
+        let soldier_on_board: SoldiersOnBoard = places
+            .iter()
+            .enumerate()
+            .map(|(i, place)| (SoldierIndex(i), (VehicleIndex(0), place.clone())))
+            .collect();
+        let reversed: SoldiersOnBoard = places
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, place)| (SoldierIndex(i), (VehicleIndex(0), place.clone())))
+            .collect();
+
+        // When
+        let checksum = copy(soldier_on_board.clone(), 0).checksum();
+
+        // Then
+        assert_eq!(checksum, copy(reversed, 0).checksum());
+        assert_ne!(checksum, copy(soldier_on_board, 1).checksum());
+    }
+
+    #[rstest]
+    fn checksum_depends_on_clouds() {
+        // Given
+        let without_cloud = copy(SoldiersOnBoard::new(), 0);
+        let mut with_cloud = without_cloud.clone();
+
+        // When
+        with_cloud.clouds.push(Cloud::new(
+            WorldPoint::new(10., 10.),
+            CloudType::Smoke,
+            Distance::from_meters(5),
+            10,
+        ));
+
+        // Then
+        assert_ne!(without_cloud.checksum(), with_cloud.checksum());
+    }
 }
@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 
 use glam::Vec2;
 
-use crate::entity::vehicle::OnBoardPlace;
+use crate::{config::SOLDIER_DEFAULT_SKILL, entity::vehicle::OnBoardPlace};
 
 pub trait Xy {
     fn from_xy(x: f32, y: f32) -> Self;
@@ -334,6 +334,10 @@ impl WorldPaths {
         }
     }
 
+    pub fn last_point(&self) -> Option<WorldPoint> {
+        self.paths.last().and_then(|path| path.last_point())
+    }
+
     pub fn remove_next_point(&mut self) -> Option<WorldPoint> {
         if let Some(path) = self.paths.first_mut() {
             let point = path
@@ -348,6 +352,18 @@ impl WorldPaths {
         None
     }
 
+    /// Next point is the end of a path which is not the last one
+    pub fn is_reaching_phase_line(&self) -> bool {
+        self.paths.len() > 1 && self.paths[0].points.len() == 1
+    }
+
+    /// These paths have not yet reached the phase line where `other` paths are
+    pub fn is_behind(&self, other: &WorldPaths) -> bool {
+        self.paths.len() > other.paths.len()
+            || (self.paths.len() == other.paths.len()
+                && self.paths.first().map(|p| p.points.len()).unwrap_or(0) > 1)
+    }
+
     pub fn is_last_point(&self) -> Option<bool> {
         if self.paths.is_empty() {
             None
@@ -556,12 +572,19 @@ impl Distance {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Precision(u8);
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Coverage(pub f32);
 
+/// Shooting skill of a soldier (from 0.0 to 1.0)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Skill(pub f32);
+
+impl Default for Skill {
+    fn default() -> Self {
+        Self(SOLDIER_DEFAULT_SKILL)
+    }
+}
+
 pub type SoldierBoard = (VehicleIndex, OnBoardPlace);
 pub type SoldiersOnBoard = HashMap<SoldierIndex, SoldierBoard>;
 pub type VehicleBoard = HashMap<VehicleIndex, Vec<(OnBoardPlace, SoldierIndex)>>;
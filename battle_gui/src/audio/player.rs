@@ -26,6 +26,29 @@ impl Player {
 
         match self.sounds.get_mut(sound) {
             Some(source) => {
+                source.set_volume(1.0);
+                source.play_detached(ctx)?;
+            }
+            None => {
+                println!("ERROR :: Unknown sound {:?}", sound)
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Play a battle sound with given volume (by example lowered by rain)
+    pub fn play_with_volume(
+        &mut self,
+        sound: &Sound,
+        volume: f32,
+        ctx: &mut Context,
+    ) -> GameResult {
+        puffin::profile_scope!("play_sound", sound.to_string());
+
+        match self.sounds.get_mut(sound) {
+            Some(source) => {
+                source.set_volume(volume);
                 source.play_detached(ctx)?;
             }
             None => {
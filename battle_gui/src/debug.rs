@@ -16,6 +16,7 @@ pub enum DebugPhysics {
     None,
     MosinNagantM1924GunFire,
     BrandtMle2731Shelling,
+    BrandtMle2731SmokeShelling,
 }
 
 impl Display for DebugPhysics {
@@ -24,6 +25,9 @@ impl Display for DebugPhysics {
             DebugPhysics::None => f.write_str("Normal"),
             DebugPhysics::MosinNagantM1924GunFire => f.write_str("GunFire (MosinNagantM1924)"),
             DebugPhysics::BrandtMle2731Shelling => f.write_str("Shelling (BrandtMle2731Shelling)"),
+            DebugPhysics::BrandtMle2731SmokeShelling => {
+                f.write_str("Smoke shelling (BrandtMle2731SmokeShelling)")
+            }
         }
     }
 }
@@ -35,6 +39,7 @@ impl DebugPhysics {
             DebugPhysics::None => None,
             DebugPhysics::MosinNagantM1924GunFire => None,
             DebugPhysics::BrandtMle2731Shelling => Some(ExplosiveType::FA19241927),
+            DebugPhysics::BrandtMle2731SmokeShelling => None,
         }
     }
 }
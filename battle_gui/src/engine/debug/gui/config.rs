@@ -4,8 +4,8 @@ use ggez::Context;
 use battle_core::config::{
     ChangeConfigMessage, FEELING_DECREASING_FREQ, INTERIORS_UPDATE_FREQ, SOLDIER_ANIMATE_FREQ,
     SOLDIER_UPDATE_FREQ, TARGET_FPS, TILE_TYPE_OPACITY_BRICK_WALL, TILE_TYPE_OPACITY_CONCRETE,
-    TILE_TYPE_OPACITY_DEEP_WATER, TILE_TYPE_OPACITY_DIRT, TILE_TYPE_OPACITY_HEDGE,
-    TILE_TYPE_OPACITY_HIGH_GRASS, TILE_TYPE_OPACITY_LIGHT_UNDERBRUSH,
+    TILE_TYPE_OPACITY_CRATER, TILE_TYPE_OPACITY_DEEP_WATER, TILE_TYPE_OPACITY_DIRT,
+    TILE_TYPE_OPACITY_HEDGE, TILE_TYPE_OPACITY_HIGH_GRASS, TILE_TYPE_OPACITY_LIGHT_UNDERBRUSH,
     TILE_TYPE_OPACITY_MIDDLE_GRASS, TILE_TYPE_OPACITY_MIDDLE_ROCK,
     TILE_TYPE_OPACITY_MIDDLE_WOOD_LOGS, TILE_TYPE_OPACITY_MUD, TILE_TYPE_OPACITY_SHORT_GRASS,
     TILE_TYPE_OPACITY_TRUNK, TILE_TYPE_OPACITY_UNDERBRUSH, TILE_TYPE_OPACITY_WATER,
@@ -353,8 +353,14 @@ impl Engine {
                         TILE_TYPE_OPACITY_MIDDLE_ROCK,
                         ChangeConfigMessage::TileTypeOpacityMiddleRock,
                     ),
+                    (
+                        "CRATER",
+                        &mut self.server_config.tile_type_opacity_crater,
+                        TILE_TYPE_OPACITY_CRATER,
+                        ChangeConfigMessage::TileTypeOpacityCrater,
+                    ),
                 ]
-                    as [(_, _, _, fn(_) -> _); 15]
+                    as [(_, _, _, fn(_) -> _); 16]
                 {
                     ui.label(format!("TILE_TYPE_OPACITY_{}", name));
                     if ui.button("reset").clicked() {
@@ -424,6 +430,18 @@ impl Engine {
                         ),
                     ));
                 }
+                ui.end_row();
+
+                ui.label("FRIENDLY_FIRE");
+                ui.label("");
+                if ui
+                    .checkbox(&mut self.server_config.friendly_fire, "")
+                    .changed()
+                {
+                    messages.push(EngineMessage::ChangeServerConfig(
+                        ChangeConfigMessage::FriendlyFire(self.server_config.friendly_fire),
+                    ));
+                }
             });
 
         messages
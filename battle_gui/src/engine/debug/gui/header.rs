@@ -87,6 +87,12 @@ impl Engine {
                         "BrandtMle2731",
                     )
                     .changed(),
+                    ui.radio_value(
+                        self.gui_state.debug_physics_mut(),
+                        DebugPhysics::BrandtMle2731SmokeShelling,
+                        "BrandtMle2731 (smoke)",
+                    )
+                    .changed(),
                 ];
 
                 if changes.iter().any(|v| *v) {
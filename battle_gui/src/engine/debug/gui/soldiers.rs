@@ -50,6 +50,14 @@ impl Engine {
         ui: &mut Ui,
         soldier_index: &SoldierIndex,
     ) -> Vec<EngineMessage> {
+        let soldier = self.battle_state.soldier(*soldier_index);
+        let squad_uuid = soldier.squad_uuid();
+        let target_policy = self.battle_state.soldier_target_policy(soldier);
+        let target_policy_source = if self.battle_state.squad_target_policy(&squad_uuid).is_some() {
+            "squad"
+        } else {
+            "role"
+        };
         let soldier = &mut self.battle_state.soldier_mut(*soldier_index);
         let mut messages = vec![];
 
@@ -108,6 +116,29 @@ impl Engine {
                 ui.label(format!("{}", soldier.behavior()));
                 ui.end_row();
 
+                ui.label("TargetPolicy");
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "{} ({})",
+                        target_policy.display(),
+                        target_policy_source
+                    ));
+                    if ui.button("next").clicked() {
+                        messages.push(EngineMessage::BattleState(
+                            BattleStateMessage::SetSquadTargetPolicy(
+                                squad_uuid,
+                                Some(target_policy.next()),
+                            ),
+                        ))
+                    }
+                    if ui.button("role").clicked() {
+                        messages.push(EngineMessage::BattleState(
+                            BattleStateMessage::SetSquadTargetPolicy(squad_uuid, None),
+                        ))
+                    }
+                });
+                ui.end_row();
+
                 ui.label("UnderFire");
                 ui.add(Slider::new(
                     soldier.under_fire_mut().value_mut(),
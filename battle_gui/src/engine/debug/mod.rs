@@ -5,15 +5,15 @@ use ggez::{
 
 use battle_core::{
     behavior::Behavior,
-    game::{
-        explosive::ExplosiveType,
-        squad::{squad_positions, Formation},
-        weapon::Weapon,
-        Side,
+    config::{SMOKE_SHELL_DURATION, SMOKE_SHELL_RAYON},
+    game::{explosive::ExplosiveType, squad::squad_positions, weapon::Weapon, Side},
+    physics::event::{
+        bullet::BulletFire,
+        cloud::{Cloud, CloudType},
+        explosion::Explosion,
     },
-    physics::event::{bullet::BulletFire, explosion::Explosion},
     state::battle::message::BattleStateMessage,
-    types::WorldPoint,
+    types::{Distance, WorldPoint},
 };
 
 use crate::{
@@ -92,7 +92,8 @@ impl Engine {
         for squad_id in &self.gui_state.selected_squads().1 {
             let squad = self.battle_state.squad(*squad_id);
             let leader = self.battle_state.soldier(squad.leader());
-            for (_, point) in squad_positions(squad, Formation::Line, leader, None) {
+            let formation = self.battle_state.squad_formation(squad_id);
+            for (_, point) in squad_positions(squad, formation, leader, None) {
                 let window_point = self.gui_state.window_point_from_world_point(point);
                 mesh_builder.circle(DrawMode::fill(), window_point.to_vec2(), 2.0, 2.0, YELLOW)?;
             }
@@ -276,7 +277,6 @@ impl Engine {
                         BattleStateMessage::PushBulletFire(BulletFire::new(
                             from,
                             to,
-                            None,
                             weapon.ammunition(),
                             weapon.gun_fire_sound_type(),
                         )),
@@ -292,6 +292,16 @@ impl Engine {
                     )),
                 ));
             }
+            DebugPhysics::BrandtMle2731SmokeShelling => {
+                messages.push(EngineMessage::BattleState(BattleStateMessage::PushCloud(
+                    Cloud::new(
+                        from,
+                        CloudType::Smoke,
+                        Distance::from_meters(SMOKE_SHELL_RAYON),
+                        SMOKE_SHELL_DURATION,
+                    ),
+                )));
+            }
         };
 
         messages
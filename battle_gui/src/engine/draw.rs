@@ -7,8 +7,9 @@ use ggez::{
 };
 
 use battle_core::{
+    config::SMOKE_GRENADE_MAXIMUM_THROW,
     entity::soldier::Soldier,
-    game::squad::{squad_positions, Formation},
+    game::squad::squad_positions,
     order::{marker::OrderMarker, Order, PendingOrder},
     physics::{utils::DISTANCE_TO_METERS_COEFFICIENT, visibility::Visibility},
     types::*,
@@ -50,11 +51,12 @@ impl Engine {
                 .soldiers_mut()
                 .extend(&self.gui_state.zoom, sprites);
 
+            let formation = self.battle_state.squad_formation(squad_index);
             let cursor_immobile_since =
                 self.gui_state.frame_i() - self.gui_state.last_cursor_move_frame();
             if cursor_immobile_since >= 15 {
                 for (member_id, formation_position) in
-                    squad_positions(squad, Formation::Line, leader, Some(cursor))
+                    squad_positions(squad, formation, leader, Some(cursor))
                 {
                     let soldier = self.battle_state.soldier(member_id);
                     let sprites = self.graphics.soldier_sprites(
@@ -246,6 +248,7 @@ impl Engine {
 
     pub fn generate_selection_meshes(&self, mesh_builder: &mut MeshBuilder) -> GameResult {
         self.generate_selected_entities_meshes(mesh_builder)?;
+        self.generate_no_fire_areas_meshes(mesh_builder)?;
 
         Ok(())
     }
@@ -412,6 +415,7 @@ impl Engine {
                     &squad_leader.world_point(),
                     &self.gui_state.current_cursor_world_point(),
                     self.battle_state.map(),
+                    self.battle_state.clouds(),
                 );
 
                 if let Some(break_point) = visibility.break_point {
@@ -440,6 +444,51 @@ impl Engine {
                     Angle(0.),
                 ))
             }
+            PendingOrder::SmokeScreen(_) => {
+                let pending_order_marker = self.pending_order_marker(pending_order);
+                let from_point = self
+                    .gui_state
+                    .window_point_from_world_point(squad_leader.world_point());
+                let to_point = self.gui_state.current_cursor_window_point();
+                let radius = (SMOKE_GRENADE_MAXIMUM_THROW as f32 / DISTANCE_TO_METERS_COEFFICIENT)
+                    * self.gui_state.zoom.factor();
+
+                mesh_builder.circle(
+                    DrawMode::Stroke(StrokeOptions::default()),
+                    from_point.to_vec2(),
+                    radius,
+                    1.0,
+                    Color::WHITE,
+                )?;
+                mesh_builder.line(
+                    &[from_point.to_vec2(), to_point.to_vec2()],
+                    2.,
+                    Color::WHITE,
+                )?;
+                draw_params.push(self.graphics.order_marker_draw_params(
+                    &pending_order_marker,
+                    *to_point,
+                    Angle(0.),
+                ))
+            }
+            PendingOrder::BoundTo(_) => {
+                let pending_order_marker = self.pending_order_marker(pending_order);
+                let from_point = self
+                    .gui_state
+                    .window_point_from_world_point(squad_leader.world_point());
+                let to_point = self.gui_state.current_cursor_window_point();
+
+                mesh_builder.line(
+                    &[from_point.to_vec2(), to_point.to_vec2()],
+                    2.,
+                    Color::YELLOW,
+                )?;
+                draw_params.push(
+                    self.graphics
+                        .order_marker_draw_params(&pending_order_marker, *to_point, Angle(0.))
+                        .scale(self.gui_state.zoom.to_vec2()),
+                )
+            }
         }
 
         Ok(draw_params)
@@ -452,6 +501,8 @@ impl Engine {
             PendingOrder::SneakTo(_, _, _) => OrderMarker::SneakTo,
             PendingOrder::Defend(_) => OrderMarker::Defend,
             PendingOrder::Hide(_) => OrderMarker::Hide,
+            PendingOrder::SmokeScreen(_) => OrderMarker::SmokeScreen,
+            PendingOrder::BoundTo(_) => OrderMarker::MoveFastTo,
             PendingOrder::EngageOrFire(_) => {
                 let cursor_point = self.gui_state.current_cursor_world_point();
                 if self
@@ -1,4 +1,4 @@
-use battle_core::state::battle::phase::Phase;
+use battle_core::{game::Side, state::battle::phase::Phase};
 use ggegui::egui::{Align, Align2, Layout, Vec2, Window};
 use ggez::{Context, GameResult};
 
@@ -33,6 +33,14 @@ impl Engine {
                         "Battle is end : {} winning by {} victory.",
                         winner, reason
                     ));
+                    ui.separator();
+                    for side in [Side::A, Side::B] {
+                        ui.label(format!(
+                            "Side {} fratricides : {}",
+                            side,
+                            self.battle_state.stats().fratricides(&side)
+                        ));
+                    }
                     ui.with_layout(Layout::right_to_left(Align::TOP), |ui| {
                         if ui.button("Quit").clicked() {
                             messages.push(EngineMessage::Exit)
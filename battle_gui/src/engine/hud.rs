@@ -1,5 +1,5 @@
 use battle_core::{
-    game::Side,
+    game::{target::TargetPolicy, Side},
     state::battle::{
         message::BattleStateMessage,
         phase::{EndReason, Phase, Victorious},
@@ -23,6 +23,8 @@ impl Engine {
                 ))]
             }
             HudEvent::SelectSoldier(soldier_index) => self.select_soldier(&soldier_index),
+            HudEvent::NextSquadFormation(squad_id) => self.next_squad_formation(&squad_id),
+            HudEvent::NextSquadTargetPolicy(squad_id) => self.next_squad_target_policy(&squad_id),
         }
     }
 
@@ -47,6 +49,25 @@ impl Engine {
         ))]
     }
 
+    pub fn next_squad_formation(&self, squad_id: &SquadUuid) -> Vec<EngineMessage> {
+        let formation = self.battle_state.squad_formation(squad_id).next();
+        vec![EngineMessage::BattleState(
+            BattleStateMessage::SetSquadFormation(*squad_id, formation),
+        )]
+    }
+
+    /// Toggle squad target policy, going back to role defaults after the last one
+    pub fn next_squad_target_policy(&self, squad_id: &SquadUuid) -> Vec<EngineMessage> {
+        let policy = match self.battle_state.squad_target_policy(squad_id) {
+            None => Some(TargetPolicy::default()),
+            Some(TargetPolicy::OfficersAndCrews) => None,
+            Some(policy) => Some(policy.next()),
+        };
+        vec![EngineMessage::BattleState(
+            BattleStateMessage::SetSquadTargetPolicy(*squad_id, policy),
+        )]
+    }
+
     pub fn select_soldier(&self, soldier_index: &SoldierIndex) -> Vec<EngineMessage> {
         vec![EngineMessage::GuiState(GuiStateMessage::SetSelectedSquads(
             Some(*soldier_index),
@@ -2,6 +2,8 @@ use std::fmt::Display;
 
 use battle_core::{
     audio::Sound,
+    order::PendingOrder,
+    state::battle::message::BattleStateMessage,
     types::{Offset, WindowPoint},
 };
 use ggez::{event::MouseButton, input::keyboard::KeyInput, winit::event::VirtualKeyCode, Context};
@@ -149,6 +151,37 @@ impl Engine {
             Some(VirtualKeyCode::T) => {
                 messages.push(EngineMessage::SwitchDecorDisplay);
             }
+            Some(VirtualKeyCode::G) => {
+                // Smoke screen order for selected squads
+                let squads = &self.gui_state.selected_squads().1;
+                if !squads.is_empty() {
+                    messages.push(EngineMessage::GuiState(GuiStateMessage::SetPendingOrders(
+                        squads
+                            .iter()
+                            .map(|squad_index| PendingOrder::SmokeScreen(*squad_index))
+                            .collect(),
+                    )));
+                }
+            }
+            Some(VirtualKeyCode::B) => {
+                // Bounding overwatch order for the two selected squads
+                let squads = &self.gui_state.selected_squads().1;
+                if squads.len() == 2 {
+                    messages.push(EngineMessage::GuiState(GuiStateMessage::SetPendingOrders(
+                        squads
+                            .iter()
+                            .map(|squad_index| PendingOrder::BoundTo(*squad_index))
+                            .collect(),
+                    )));
+                } else {
+                    messages.push(EngineMessage::PlaySound(Sound::Bip1));
+                }
+            }
+            Some(VirtualKeyCode::N) => {
+                messages.push(EngineMessage::BattleState(
+                    BattleStateMessage::ClearNoFireAreas(*self.gui_state.side()),
+                ));
+            }
             Some(VirtualKeyCode::F5) => {
                 messages.push(EngineMessage::TryLoadLastSave);
             }
@@ -178,9 +211,9 @@ impl Engine {
     pub fn determine_controlling(&self) -> Control {
         match self.gui_state.debug_physics() {
             DebugPhysics::None => Control::Soldiers,
-            DebugPhysics::MosinNagantM1924GunFire | DebugPhysics::BrandtMle2731Shelling => {
-                Control::Physics
-            }
+            DebugPhysics::MosinNagantM1924GunFire
+            | DebugPhysics::BrandtMle2731Shelling
+            | DebugPhysics::BrandtMle2731SmokeShelling => Control::Physics,
         }
     }
 
@@ -23,11 +23,8 @@ impl Engine {
             .clear_map_interiors_batch(&self.gui_state.zoom);
 
         for interior in self.battle_state.map().interiors() {
-            // World coordinates
-            let start_x = interior.x();
-            let start_y = interior.y();
-            let end_x = start_x + interior.width();
-            let end_y = start_y + interior.height();
+            // Interior is revealed room by room (if it has rooms)
+            let mut revealed: Vec<Option<usize>> = vec![];
 
             for soldier in self.battle_state.soldiers() {
                 let can_see_interior = soldier.can_see_interior();
@@ -37,28 +34,45 @@ impl Engine {
                     continue;
                 }
 
-                let soldier_position = soldier.world_point();
-                if soldier_position.x >= start_x
-                    && soldier_position.x <= end_x
-                    && soldier_position.y >= start_y
-                    && soldier_position.y <= end_y
-                {
-                    self.graphics.interiors_mut().push(
-                        &self.gui_state.zoom,
-                        DrawParam::new()
-                            .src(Rect::new(
-                                interior.relative_x(),
-                                interior.relative_y(),
-                                interior.relative_width(),
-                                interior.relative_height(),
-                            ))
-                            .dest(
-                                WorldPoint::new(start_x, start_y).to_vec2()
-                                    * self.gui_state.zoom.factor(),
-                            ),
-                    );
+                if !interior.contains(&soldier.world_point()) {
                     continue;
                 }
+
+                let room = interior.room(&soldier.world_point());
+                if (!interior.rooms().is_empty() && room.is_none()) || revealed.contains(&room) {
+                    continue;
+                }
+                revealed.push(room);
+
+                // World coordinates
+                let (start_x, start_y, src) = match room.map(|i| &interior.rooms()[i]) {
+                    Some(room) => (
+                        room.x(),
+                        room.y(),
+                        Rect::new(
+                            room.relative_x(),
+                            room.relative_y(),
+                            room.relative_width(),
+                            room.relative_height(),
+                        ),
+                    ),
+                    None => (
+                        interior.x(),
+                        interior.y(),
+                        Rect::new(
+                            interior.relative_x(),
+                            interior.relative_y(),
+                            interior.relative_width(),
+                            interior.relative_height(),
+                        ),
+                    ),
+                };
+                self.graphics.interiors_mut().push(
+                    &self.gui_state.zoom,
+                    DrawParam::new().src(src).dest(
+                        WorldPoint::new(start_x, start_y).to_vec2() * self.gui_state.zoom.factor(),
+                    ),
+                );
             }
         }
     }
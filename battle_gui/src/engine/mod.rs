@@ -19,6 +19,7 @@ use ggez::{Context, GameResult};
 use crate::audio::player::Player;
 use crate::graphics::Graphics;
 use crate::saves::reader::BattleSavesListBuilder;
+use crate::server::Connection;
 use crate::ui::hud::builder::HudBuilder;
 use crate::ui::hud::painter::HudPainter;
 use crate::ui::hud::{Hud, HUD_HEIGHT};
@@ -66,6 +67,7 @@ pub struct Engine {
     egui_backend: Gui,
     ///
     hud: Hud,
+    connection: Connection,
     a_control: MapControl,
     b_control: MapControl,
 }
@@ -83,6 +85,7 @@ impl Engine {
         battle_state: BattleState,
         sync_required: Arc<AtomicBool>,
         stop_required: Arc<AtomicBool>,
+        connection: Connection,
         a_control: MapControl,
         b_control: MapControl,
     ) -> GameResult<Engine> {
@@ -108,6 +111,7 @@ impl Engine {
             debug_gui: DebugGuiState::new()?,
             egui_backend: Gui::default(),
             hud,
+            connection,
             a_control,
             b_control,
         };
@@ -159,13 +163,29 @@ impl EventHandler<ggez::GameError> for Engine {
         self.generate_explosion_sprites()?;
         self.graphics
             .draw_map(&mut canvas, dest, &self.gui_state.zoom)?;
+        self.graphics
+            .draw_terrain_changes(ctx, &mut canvas, scale)?;
         self.draw_debug_terrain(ctx, &mut canvas, scale)?;
         self.graphics
             .draw_units(&mut canvas, dest, &self.gui_state.zoom)?;
         self.graphics
             .draw_decor(&mut canvas, decor, dest, &self.gui_state.zoom)?;
+        self.graphics.draw_clouds(
+            ctx,
+            &mut canvas,
+            self.battle_state.clouds(),
+            *self.battle_state.frame_i(),
+            scale,
+        )?;
         self.graphics.draw_flags(&mut canvas, dest)?;
         self.draw_flags_names(&mut canvas, dest)?;
+        self.graphics.draw_environment(
+            ctx,
+            &mut canvas,
+            self.battle_state.map().environment(),
+            *self.battle_state.frame_i(),
+            graphics::DrawParam::new(),
+        )?;
 
         // Draw ui
         let mut mesh_builder = MeshBuilder::new();
@@ -260,6 +280,7 @@ impl EventHandler<ggez::GameError> for Engine {
 
     fn quit_event(&mut self, _ctx: &mut Context) -> Result<bool, ggez::GameError> {
         self.stop_required.store(true, Ordering::Relaxed);
+        self.connection.close();
         Ok(false)
     }
 }
@@ -1,7 +1,7 @@
 use std::sync::atomic::Ordering;
 
 use battle_core::{
-    message::OutputMessage,
+    message::{lockstep::LockstepMessage, OutputMessage},
     state::{
         battle::{message::SideEffect, BattleState},
         client::ClientStateMessage,
@@ -10,12 +10,15 @@ use battle_core::{
 };
 use ggez::{Context, GameResult};
 
+use crate::graphics::message::GraphicsMessage;
+
 use super::Engine;
 
 impl Engine {
     pub fn sync(&mut self, ctx: &mut Context) -> GameResult {
         puffin::profile_scope!("sync");
         let mut side_effects = vec![];
+        let mut refresh_terrain = false;
         let frame_i = self.gui_state.frame_i();
 
         while let Ok(messages) = self.input.try_recv() {
@@ -36,6 +39,7 @@ impl Engine {
 
                         self.sync_required.swap(false, Ordering::Relaxed);
                         self.battle_state = battle_state;
+                        refresh_terrain = true;
                     }
                     OutputMessage::BattleState(battle_state_message) => {
                         if !self.sync_required.load(Ordering::Relaxed) {
@@ -55,7 +59,8 @@ impl Engine {
                                 self.player.play(sound, ctx)?
                             }
                             ClientStateMessage::PlayBattleSound(sound) => {
-                                self.player.play(sound, ctx)?
+                                let audibility = self.battle_state.map().environment().audibility();
+                                self.player.play_with_volume(sound, audibility, ctx)?
                             }
                             ClientStateMessage::BattleStarted => {
                                 self.graphics.battle_started(ctx, self.battle_state.map())?;
@@ -65,10 +70,29 @@ impl Engine {
                     OutputMessage::ChangeConfig(change_config) => {
                         self.server_config.react(change_config);
                     }
+                    // Lobby is negotiated before engine start
+                    OutputMessage::Lobby(_) | OutputMessage::BattleSetup(_) => {}
+                    OutputMessage::Lockstep(LockstepMessage::Desync(frame_i)) => {
+                        println!("WARNING :: Lockstep :: battle desync since frame {frame_i}")
+                    }
+                    // Other lockstep messages are consumed by local runner
+                    OutputMessage::Lockstep(_) => {}
                 }
             }
         }
 
+        if refresh_terrain
+            || side_effects
+                .iter()
+                .any(|side_effect| matches!(side_effect, SideEffect::RefreshTerrain))
+        {
+            self.graphics.react(
+                GraphicsMessage::RefreshTerrain,
+                self.battle_state.map(),
+                &self.server_config,
+                ctx,
+            )?;
+        }
         self.side_effects(side_effects);
 
         Ok(())
@@ -1,7 +1,7 @@
 use battle_core::{
     behavior::Behavior,
     entity::soldier::Soldier,
-    game::cover::CoverFinder,
+    game::{bounding::BoundingOverwatch, cover::CoverFinder},
     order::{marker::OrderMarker, Order, PendingOrder},
     state::battle::message::{BattleStateMessage, SoldierMessage, VehicleMessage},
     types::*,
@@ -59,11 +59,86 @@ impl Engine {
         None
     }
 
+    /// Orders spreading pending orders squads along the line from `start` to `end`
+    pub fn create_frontage_orders(
+        &self,
+        start: WorldPoint,
+        end: WorldPoint,
+    ) -> Vec<(SquadUuid, Option<Order>)> {
+        let pending_orders = self.gui_state.pending_order();
+        let squads: Vec<SquadUuid> = pending_orders
+            .iter()
+            .map(|pending_order| *pending_order.squad_index())
+            .collect();
+
+        self.frontage_points(&squads, start, end)
+            .into_iter()
+            .map(|(squad_id, point)| {
+                let world_paths = self.create_path_finding_to(squad_id, &point);
+                let pending_order = pending_orders
+                    .iter()
+                    .find(|pending_order| pending_order.squad_index() == &squad_id);
+                let order = match (pending_order, world_paths) {
+                    (Some(PendingOrder::MoveTo(_, _, _)), Some(paths)) => {
+                        Some(Order::MoveTo(paths, None))
+                    }
+                    (Some(PendingOrder::MoveFastTo(_, _, _)), Some(paths)) => {
+                        Some(Order::MoveFastTo(paths, None))
+                    }
+                    (Some(PendingOrder::SneakTo(_, _, _)), Some(paths)) => {
+                        Some(Order::SneakTo(paths, None))
+                    }
+                    _ => None,
+                };
+                (squad_id, order)
+            })
+            .collect()
+    }
+
+    /// Bounding overwatch of the two squads of pending orders to the cursor
+    pub fn create_bounding_overwatch(&self) -> Option<BoundingOverwatch> {
+        let squads: Vec<SquadUuid> = self
+            .gui_state
+            .pending_order()
+            .iter()
+            .filter(|pending_order| pending_order.is_bound())
+            .map(|pending_order| *pending_order.squad_index())
+            .collect();
+
+        match squads[..] {
+            [first, second] => Some(BoundingOverwatch::new(
+                second,
+                first,
+                self.gui_state.current_cursor_world_point(),
+            )),
+            _ => None,
+        }
+    }
+
     pub fn create_defend_order(&self, squad_id: SquadUuid) -> Option<Order> {
         let angle = self.angle_from_cursor_and_squad(squad_id);
         Some(Order::Defend(angle))
     }
 
+    /// Defend or hide order looking at the kill zone drawn from `start` to `end`
+    pub fn create_ambush_order(
+        &self,
+        pending_order: &PendingOrder,
+        start: &WorldPoint,
+        end: &WorldPoint,
+    ) -> Option<Order> {
+        let squad = self.battle_state.squad(*pending_order.squad_index());
+        let leader_point = self.battle_state.soldier(squad.leader()).world_point();
+        let center = (start.to_vec2() + end.to_vec2()) / 2.;
+        let angle = Angle::from_points(&center, &leader_point.to_vec2());
+
+        match pending_order {
+            PendingOrder::Defend(_) => Some(Order::Defend(angle)),
+            PendingOrder::Hide(_) => Some(Order::Hide(angle)),
+            _ => None,
+        }
+    }
+
     pub fn create_hide_order(&self, squad_id: SquadUuid) -> Option<Order> {
         let angle = self.angle_from_cursor_and_squad(squad_id);
         Some(Order::Hide(angle))
@@ -96,6 +171,12 @@ impl Engine {
         None
     }
 
+    pub fn create_smoke_screen_order(&self, _squad_id: &SquadUuid) -> Option<Order> {
+        Some(Order::SmokeScreen(
+            self.gui_state.current_cursor_world_point(),
+        ))
+    }
+
     pub fn create_pending_order_from_order_marker(
         &self,
         order_marker: &OrderMarker,
@@ -117,14 +198,40 @@ impl Engine {
             OrderMarker::Hide => PendingOrder::Hide(*squad_index),
             OrderMarker::EngageSquad => PendingOrder::EngageOrFire(*squad_index),
             OrderMarker::SuppressFire => PendingOrder::EngageOrFire(*squad_index),
+            OrderMarker::SmokeScreen => PendingOrder::SmokeScreen(*squad_index),
         }
     }
 
     pub fn define_order(&self, squad_leader: &SoldierIndex, order: &Order) -> Vec<EngineMessage> {
-        let mut messages = vec![EngineMessage::BattleState(BattleStateMessage::Soldier(
+        let mut messages = vec![];
+
+        // Player order replaces bounding overwatch, fire sector and ambush
+        let squad_uuid = self.battle_state.soldier(*squad_leader).squad_uuid();
+        if self.battle_state.squad_fire_sector(&squad_uuid).is_some() {
+            messages.push(EngineMessage::BattleState(
+                BattleStateMessage::SetSquadFireSector(squad_uuid, None),
+            ));
+        }
+        if self.battle_state.squad_ambush(&squad_uuid).is_some() {
+            messages.push(EngineMessage::BattleState(
+                BattleStateMessage::SetSquadAmbush(squad_uuid, None),
+            ));
+        }
+        if self
+            .battle_state
+            .bounding_overwatches()
+            .iter()
+            .any(|bounding_overwatch| bounding_overwatch.contains(&squad_uuid))
+        {
+            messages.push(EngineMessage::BattleState(
+                BattleStateMessage::RemoveBoundingOverwatch(squad_uuid),
+            ));
+        }
+
+        messages.push(EngineMessage::BattleState(BattleStateMessage::Soldier(
             *squad_leader,
             SoldierMessage::SetOrder(order.clone()),
-        ))];
+        )));
 
         if self.battle_state.phase().is_placement() {
             // When in placement, solve order immediately
@@ -160,6 +267,7 @@ impl Engine {
             }
             Order::EngageSquad(_)
             | Order::SuppressFire(_)
+            | Order::SmokeScreen(_)
             | Order::Idle
             | Order::MoveTo(_, _)
             | Order::MoveFastTo(_, _)
@@ -260,6 +368,7 @@ impl Engine {
             }
             Order::EngageSquad(_)
             | Order::SuppressFire(_)
+            | Order::SmokeScreen(_)
             | Order::Idle
             | Order::MoveTo(_, _)
             | Order::MoveFastTo(_, _)
@@ -62,8 +62,9 @@ impl Engine {
                 // TODO : manage failures in user display
                 EngineMessage::LoadFromSave(save_path) => {
                     if let Some(copy) = self.load_from_save(&save_path) {
-                        if let Err(error) =
-                            self.output.send(vec![InputMessage::SetBattleState(copy)])
+                        if let Err(error) = self
+                            .output
+                            .send(vec![InputMessage::SetBattleState(Box::new(copy))])
                         {
                             eprintln!(
                                 "Error when try to send battle state copy to server : {}",
@@ -78,8 +79,9 @@ impl Engine {
                     saves.sort();
                     if let Some(save_path) = saves.first() {
                         if let Some(copy) = self.load_from_save(save_path) {
-                            if let Err(error) =
-                                self.output.send(vec![InputMessage::SetBattleState(copy)])
+                            if let Err(error) = self
+                                .output
+                                .send(vec![InputMessage::SetBattleState(Box::new(copy))])
                             {
                                 eprintln!(
                                     "Error when try to send battle state copy to server : {}",
@@ -113,6 +115,10 @@ impl Engine {
                 }
                 // Server side effect
                 SideEffect::SoldierFinishHisBehavior(_, _) => {}
+                // Graphics are refreshed at sync
+                SideEffect::RefreshTerrain => {}
+                // Environment is drawn from battle state
+                SideEffect::RefreshVisibilities => {}
             }
         }
     }
@@ -8,14 +8,20 @@ use glam::Vec2;
 use battle_core::{
     audio::Sound,
     config::{
-        DEFAULT_SELECTED_SQUARE_SIDE, DEFAULT_SELECTED_SQUARE_SIDE_HALF,
+        AMBUSH_TRIGGER_DISTANCE_METERS, DEFAULT_SELECTED_SQUARE_SIDE,
+        DEFAULT_SELECTED_SQUARE_SIDE_HALF, FIRE_SECTOR_ARC_SEGMENTS,
         PENDING_ORDER_PATH_FINDING_DRAW_FRAMES,
     },
     entity::soldier::Soldier,
-    game::{cover::CoverFinder, health::SoldierHealthBuilder},
+    game::{
+        ambush::AmbushTrigger,
+        cover::CoverFinder,
+        health::SoldierHealthBuilder,
+        sector::{FireSector, NoFireArea},
+    },
     graphics::vehicle::VehicleGraphicInfos,
     order::{Order, PendingOrder},
-    physics::path::Direction,
+    physics::{path::Direction, utils::DISTANCE_TO_METERS_COEFFICIENT},
     state::battle::message::{BattleStateMessage, SoldierMessage, VehicleMessage},
     types::*,
     utils::DebugPoint,
@@ -77,6 +83,97 @@ impl Engine {
                     )?;
                 }
             }
+            self.generate_ambush_meshes(squad_uuid, mesh_builder)?;
+            self.generate_fire_sector_meshes(squad_uuid, mesh_builder)?;
+        }
+
+        Ok(())
+    }
+
+    fn generate_fire_sector_meshes(
+        &self,
+        squad_uuid: &SquadUuid,
+        mesh_builder: &mut MeshBuilder,
+    ) -> GameResult {
+        if let Some(sector) = self.battle_state.squad_fire_sector(squad_uuid) {
+            let squad = self.battle_state.squad(*squad_uuid);
+            let from = self
+                .gui_state
+                .window_point_from_world_point(
+                    self.battle_state.soldier(squad.leader()).world_point(),
+                )
+                .to_vec2();
+            let radius = ((sector.range().millimeters() as f32 / DISTANCE_TO_METERS_COEFFICIENT)
+                / 1000.)
+                * self.gui_state.zoom.factor();
+            // Angles are north oriented
+            let start = sector.angle().0 - sector.width() / 2.;
+            let mut points = vec![from];
+            for i in 0..=FIRE_SECTOR_ARC_SEGMENTS {
+                let angle = start + sector.width() * i as f32 / FIRE_SECTOR_ARC_SEGMENTS as f32;
+                points.push(from + Vec2::new(angle.sin(), -angle.cos()) * radius);
+            }
+            points.push(from);
+            mesh_builder.line(&points, 1.0, Color::YELLOW)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn generate_no_fire_areas_meshes(&self, mesh_builder: &mut MeshBuilder) -> GameResult {
+        for area in self.battle_state.no_fire_areas() {
+            if area.side() != self.gui_state.side() {
+                continue;
+            }
+
+            let from = self
+                .gui_state
+                .window_point_from_world_point(*area.top_left());
+            let to = self
+                .gui_state
+                .window_point_from_world_point(*area.bottom_right());
+            mesh_builder.rectangle(
+                DrawMode::stroke(1.0),
+                Rect::new(from.x, from.y, to.x - from.x, to.y - from.y),
+                Color::MAGENTA,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn generate_ambush_meshes(
+        &self,
+        squad_uuid: &SquadUuid,
+        mesh_builder: &mut MeshBuilder,
+    ) -> GameResult {
+        match self.battle_state.squad_ambush(squad_uuid) {
+            Some(AmbushTrigger::KillZone(top_left, bottom_right)) => {
+                let from = self.gui_state.window_point_from_world_point(*top_left);
+                let to = self.gui_state.window_point_from_world_point(*bottom_right);
+                mesh_builder.rectangle(
+                    DrawMode::stroke(1.0),
+                    Rect::new(from.x, from.y, to.x - from.x, to.y - from.y),
+                    Color::RED,
+                )?;
+            }
+            Some(AmbushTrigger::Distance(distance)) => {
+                let squad = self.battle_state.squad(*squad_uuid);
+                let point = self.gui_state.window_point_from_world_point(
+                    self.battle_state.soldier(squad.leader()).world_point(),
+                );
+                let radius = ((distance.millimeters() as f32 / DISTANCE_TO_METERS_COEFFICIENT)
+                    / 1000.)
+                    * self.gui_state.zoom.factor();
+                mesh_builder.circle(
+                    DrawMode::stroke(1.0),
+                    point.to_vec2(),
+                    radius,
+                    1.0,
+                    Color::RED,
+                )?;
+            }
+            None => {}
         }
 
         Ok(())
@@ -95,6 +192,20 @@ impl Engine {
                     GREEN,
                 )?;
             }
+        // Ambush kill zone
+        } else if self
+            .gui_state
+            .pending_order()
+            .iter()
+            .any(|p| p.can_ambush())
+        {
+            if let Some((start, end)) = self.gui_state.current_cursor_vector_window_points() {
+                mesh_builder.rectangle(
+                    DrawMode::stroke(1.0),
+                    Rect::new(start.x, start.y, end.x - start.x, end.y - start.y),
+                    Color::RED,
+                )?;
+            }
         }
 
         Ok(())
@@ -258,6 +369,12 @@ impl Engine {
                 //
                 self.create_engage_order(squad_index)
             }
+            PendingOrder::SmokeScreen(squad_index) => {
+                //
+                self.create_smoke_screen_order(squad_index)
+            }
+            // Given to both squads at once (see `create_bounding_overwatch`)
+            PendingOrder::BoundTo(_) => None,
         }
     }
 
@@ -401,12 +518,29 @@ impl Engine {
             messages.extend(self.digest_scene_select_by_click(ctx, point));
         }
 
+        // Bounding overwatch click
+        if let Some(bounding_overwatch) = self.create_bounding_overwatch() {
+            messages.extend(vec![
+                EngineMessage::PlaySound(Sound::Clac1),
+                EngineMessage::BattleState(BattleStateMessage::AddBoundingOverwatch(
+                    bounding_overwatch,
+                )),
+                EngineMessage::GuiState(GuiStateMessage::SetPendingOrders(vec![])),
+            ]);
+        }
+
         // This is a pending order click
-        for pending_order in self.gui_state.pending_order() {
+        let mut moving_squads = vec![];
+        for pending_order in self
+            .gui_state
+            .pending_order()
+            .iter()
+            .filter(|pending_order| !pending_order.is_bound())
+        {
             let is_appending = ctx.keyboard.is_key_pressed(VirtualKeyCode::LShift)
                 || ctx.keyboard.is_key_pressed(VirtualKeyCode::RShift);
 
-            if is_appending {
+            if is_appending && pending_order.expect_path_finding() {
                 messages.extend(vec![EngineMessage::GuiState(
                     GuiStateMessage::AddCachePointToPendingOrder(
                         self.gui_state.current_cursor_world_point(),
@@ -419,13 +553,27 @@ impl Engine {
                         .battle_state
                         .squad(*pending_order.squad_index())
                         .leader();
+                    if pending_order.expect_path_finding() {
+                        moving_squads.push(*pending_order.squad_index());
+                    }
                     messages.extend(
                         [
                             vec![EngineMessage::PlaySound(Sound::Clac1)],
                             self.define_order(&squad_leader, &order),
                         ]
                         .concat(),
-                    )
+                    );
+                    // Shift click on defend or hide : ambush triggered by distance
+                    if is_appending && pending_order.can_ambush() {
+                        messages.push(EngineMessage::BattleState(
+                            BattleStateMessage::SetSquadAmbush(
+                                *pending_order.squad_index(),
+                                Some(AmbushTrigger::Distance(Distance::from_meters(
+                                    AMBUSH_TRIGGER_DISTANCE_METERS,
+                                ))),
+                            ),
+                        ));
+                    }
                 } else {
                     messages.push(EngineMessage::PlaySound(Sound::Bip1))
                 }
@@ -437,6 +585,13 @@ impl Engine {
             }
         }
 
+        // Squads moved together wait each other at phase lines
+        if !moving_squads.is_empty() {
+            messages.push(EngineMessage::BattleState(
+                BattleStateMessage::SetSquadsGroup(moving_squads),
+            ));
+        }
+
         // In all cases, clean some things
         messages.extend(vec![EngineMessage::GuiState(
             GuiStateMessage::SetDisplayPaths(vec![]),
@@ -456,7 +611,7 @@ impl Engine {
 
     fn cursor_vector_finished_controlling_soldier(
         &mut self,
-        _ctx: &Context,
+        ctx: &Context,
         start: WindowPoint,
         end: WindowPoint,
     ) -> Vec<EngineMessage> {
@@ -476,9 +631,67 @@ impl Engine {
             }
         }
 
-        if !self.gui_state.pending_order().is_empty() {
+        let is_alt = ctx.keyboard.is_key_pressed(VirtualKeyCode::LAlt)
+            || ctx.keyboard.is_key_pressed(VirtualKeyCode::RAlt);
+        let pending_orders = self.gui_state.pending_order();
+        // Several squads move orders : spread squads along the drawn line
+        if pending_orders.len() > 1 && pending_orders.iter().all(|p| p.expect_path_finding()) {
+            let world_start = self.gui_state.world_point_from_window_point(start);
+            let world_end = self.gui_state.world_point_from_window_point(end);
+            let mut moving_squads = vec![];
+            for (squad_id, order) in self.create_frontage_orders(world_start, world_end) {
+                if let Some(order_) = order {
+                    let squad_leader = self.battle_state.squad(squad_id).leader();
+                    moving_squads.push(squad_id);
+                    messages.extend(
+                        [
+                            vec![EngineMessage::PlaySound(Sound::Clac1)],
+                            self.define_order(&squad_leader, &order_),
+                        ]
+                        .concat(),
+                    )
+                } else {
+                    messages.push(EngineMessage::PlaySound(Sound::Bip1))
+                }
+            }
+            messages.extend(vec![
+                EngineMessage::BattleState(BattleStateMessage::SetSquadsGroup(moving_squads)),
+                EngineMessage::GuiState(GuiStateMessage::SetPendingOrders(vec![])),
+                EngineMessage::GuiState(GuiStateMessage::SetDisplayPaths(vec![])),
+            ]);
+        } else if !pending_orders.is_empty() {
+            let world_start = self.gui_state.world_point_from_window_point(start);
+            let world_end = self.gui_state.world_point_from_window_point(end);
+            let is_shift = ctx.keyboard.is_key_pressed(VirtualKeyCode::LShift)
+                || ctx.keyboard.is_key_pressed(VirtualKeyCode::RShift);
+            let kill_zone = if is_shift {
+                AmbushTrigger::drawn_kill_zone(world_start, world_end)
+            } else {
+                None
+            };
             for pending_order in self.gui_state.pending_order() {
-                if let Some(order_) = self.order_from_pending_order(pending_order) {
+                // Defend with drawn fire sector (alt), defend or hide with drawn kill zone
+                // (shift, ambush)
+                let ambush = kill_zone.clone().filter(|_| pending_order.can_ambush());
+                let sector = match pending_order {
+                    PendingOrder::Defend(squad_index) if is_alt => {
+                        let squad = self.battle_state.squad(*squad_index);
+                        Some(FireSector::from_points(
+                            &self.battle_state.soldier(squad.leader()).world_point(),
+                            &world_start,
+                            &world_end,
+                        ))
+                    }
+                    _ => None,
+                };
+                let order = if let Some(sector) = &sector {
+                    Some(Order::Defend(*sector.angle()))
+                } else if ambush.is_some() {
+                    self.create_ambush_order(pending_order, &world_start, &world_end)
+                } else {
+                    self.order_from_pending_order(pending_order)
+                };
+                if let Some(order_) = order {
                     let squad_leader = self
                         .battle_state
                         .squad(*pending_order.squad_index())
@@ -489,7 +702,22 @@ impl Engine {
                             self.define_order(&squad_leader, &order_),
                         ]
                         .concat(),
-                    )
+                    );
+                    if sector.is_some() {
+                        messages.push(EngineMessage::BattleState(
+                            BattleStateMessage::SetSquadFireSector(
+                                *pending_order.squad_index(),
+                                sector,
+                            ),
+                        ));
+                    } else if ambush.is_some() {
+                        messages.push(EngineMessage::BattleState(
+                            BattleStateMessage::SetSquadAmbush(
+                                *pending_order.squad_index(),
+                                ambush,
+                            ),
+                        ));
+                    }
                 } else {
                     messages.push(EngineMessage::PlaySound(Sound::Bip1))
                 }
@@ -498,6 +726,17 @@ impl Engine {
                     EngineMessage::GuiState(GuiStateMessage::SetDisplayPaths(vec![])),
                 ]);
             }
+        } else if is_alt {
+            // No fire area drawing
+            let world_start = self.gui_state.world_point_from_window_point(start);
+            let world_end = self.gui_state.world_point_from_window_point(end);
+            messages.push(EngineMessage::BattleState(
+                BattleStateMessage::AddNoFireArea(NoFireArea::new(
+                    *self.gui_state.side(),
+                    world_start,
+                    world_end,
+                )),
+            ));
         } else {
             let world_start = self.gui_state.world_point_from_window_point(start);
             let world_end = self.gui_state.world_point_from_window_point(end);
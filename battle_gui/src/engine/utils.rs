@@ -1,11 +1,15 @@
 use std::{cmp, collections::HashSet};
 
 use ggez::graphics::Rect;
+use glam::Vec2;
 
 use battle_core::{
     behavior::BehaviorMode,
     entity::{soldier::Soldier, vehicle::OnBoardPlace},
-    game::Side,
+    game::{
+        squad::{frontage_points, group_offset},
+        Side,
+    },
     order::{marker::OrderMarker, Order},
     physics::path::{find_path, Direction, PathMode},
     types::*,
@@ -108,16 +112,20 @@ impl Engine {
             .map()
             .grid_point_from_world_point(&soldier_world_point);
         let cursor_world_point = self.gui_state.current_cursor_world_point();
-        let cursor_grid_point = self
-            .battle_state
-            .map()
-            .grid_point_from_world_point(&cursor_world_point);
 
         // Prevent compute same thing each frames when path not found
         if &Some(cursor_world_point) == self.gui_state.last_computed_path_point() {
             return None;
         }
 
+        // Squads ordered together keep their relative positions
+        let group_offset = self.group_offset(squad_id);
+        let cursor_world_point = cursor_world_point.apply(group_offset);
+        let cursor_grid_point = self
+            .battle_state
+            .map()
+            .grid_point_from_world_point(&cursor_world_point);
+
         // Determine different path "part" to find:
         // Editing existing case
         let bounds = if let Some(order_marker_index_) = order_marker_index {
@@ -164,7 +172,7 @@ impl Engine {
                 let grid_cached_point = self
                     .battle_state
                     .map()
-                    .grid_point_from_world_point(cached_point);
+                    .grid_point_from_world_point(&(*cached_point).apply(group_offset));
                 bounds_.push((last, grid_cached_point));
                 last = grid_cached_point;
             }
@@ -204,6 +212,73 @@ impl Engine {
         None
     }
 
+    /// Squad leader offset from the center of pending orders squads leaders
+    pub fn group_offset(&self, squad_id: SquadUuid) -> Vec2 {
+        let pending_orders = self.gui_state.pending_order();
+        if pending_orders.len() < 2 {
+            return Vec2::ZERO;
+        }
+
+        let leader_point = |squad_id: &SquadUuid| {
+            let squad = self.battle_state.squad(*squad_id);
+            self.battle_state.soldier(squad.leader()).world_point()
+        };
+        let leaders: Vec<WorldPoint> = pending_orders
+            .iter()
+            .map(|pending_order| leader_point(pending_order.squad_index()))
+            .collect();
+
+        group_offset(&leader_point(&squad_id), &leaders)
+    }
+
+    /// Spread squads along the line from `start` to `end`. Squads are sorted by their
+    /// position along the line to prevent their paths crossing.
+    pub fn frontage_points(
+        &self,
+        squads: &[SquadUuid],
+        start: WorldPoint,
+        end: WorldPoint,
+    ) -> Vec<(SquadUuid, WorldPoint)> {
+        let leaders: Vec<(SquadUuid, WorldPoint)> = squads
+            .iter()
+            .map(|squad_id| {
+                let squad = self.battle_state.squad(*squad_id);
+                (
+                    *squad_id,
+                    self.battle_state.soldier(squad.leader()).world_point(),
+                )
+            })
+            .collect();
+
+        frontage_points(&leaders, start, end)
+    }
+
+    /// Path finding from squad leader to given point
+    pub fn create_path_finding_to(
+        &self,
+        squad_id: SquadUuid,
+        world_point: &WorldPoint,
+    ) -> Option<WorldPaths> {
+        let map = self.battle_state.map();
+        let squad = self.battle_state.squad(squad_id);
+        let start = map
+            .grid_point_from_world_point(&self.battle_state.soldier(squad.leader()).world_point());
+        let end = map.grid_point_from_world_point(world_point);
+        let (path_mode, start_direction) =
+            self.battle_state.squad_path_mode_and_direction(squad_id);
+
+        find_path(map, &start, &end, true, &path_mode, &start_direction)
+            .filter(|grid_points| !grid_points.is_empty())
+            .map(|grid_points| {
+                WorldPaths::new(vec![WorldPath::new(
+                    grid_points
+                        .iter()
+                        .map(|p| map.world_point_from_grid_point(*p))
+                        .collect(),
+                )])
+            })
+    }
+
     pub fn current_squad_world_paths(&self, squad_id: SquadUuid) -> Option<&WorldPaths> {
         let squad = self.battle_state.squad(squad_id);
         match self.battle_state.squad_behavior_mode(&squad_id) {
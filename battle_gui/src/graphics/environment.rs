@@ -0,0 +1,91 @@
+use battle_core::game::environment::{Daylight, Environment, Weather};
+use ggez::{
+    graphics::{Color, DrawMode, MeshBuilder, Rect},
+    GameResult,
+};
+use glam::Vec2;
+
+const RAIN_DROPS: u32 = 300;
+const SNOW_FLAKES: u32 = 200;
+const FOG_BANKS: u32 = 12;
+
+/// Spread particle on window with a stable pseudo random position
+fn particle_origin(i: u32, width: f32, height: f32) -> Vec2 {
+    let x = (i.wrapping_mul(7919) % 1000) as f32 / 1000. * width;
+    let y = (i.wrapping_mul(104_729) % 1000) as f32 / 1000. * height;
+    Vec2::new(x, y)
+}
+
+/// Weather and daylight, drawn over the whole window
+pub fn create_environment_mesh_builder(
+    environment: &Environment,
+    frame_i: u64,
+    width: f32,
+    height: f32,
+) -> GameResult<Option<MeshBuilder>> {
+    if environment == &Environment::default() {
+        return Ok(None);
+    }
+
+    let mut mesh_builder = MeshBuilder::new();
+    let window = Rect::new(0., 0., width, height);
+    let frame = frame_i as f32;
+
+    match environment.weather() {
+        Weather::Clear => {}
+        Weather::Fog => {
+            mesh_builder.rectangle(DrawMode::fill(), window, Color::new(0.8, 0.8, 0.8, 0.3))?;
+            for i in 0..FOG_BANKS {
+                let origin = particle_origin(i, width, height);
+                let x = (origin.x + frame * 0.1 * (1 + i % 3) as f32) % (width + 400.) - 200.;
+                mesh_builder.circle(
+                    DrawMode::fill(),
+                    Vec2::new(x, origin.y),
+                    150. + (i % 4) as f32 * 50.,
+                    2.0,
+                    Color::new(0.85, 0.85, 0.85, 0.15),
+                )?;
+            }
+        }
+        Weather::Rain => {
+            mesh_builder.rectangle(DrawMode::fill(), window, Color::new(0.2, 0.25, 0.3, 0.15))?;
+            for i in 0..RAIN_DROPS {
+                let origin = particle_origin(i, width, height);
+                let y = (origin.y + frame * 12.) % height;
+                let x = (origin.x + frame * 2.) % width;
+                mesh_builder.line(
+                    &[Vec2::new(x, y), Vec2::new(x + 2., y + 12.)],
+                    1.0,
+                    Color::new(0.7, 0.75, 0.85, 0.5),
+                )?;
+            }
+        }
+        Weather::Snow => {
+            mesh_builder.rectangle(DrawMode::fill(), window, Color::new(0.9, 0.9, 1.0, 0.15))?;
+            for i in 0..SNOW_FLAKES {
+                let origin = particle_origin(i, width, height);
+                let y = (origin.y + frame * (0.5 + (i % 3) as f32 * 0.3)) % height;
+                let x = origin.x + (frame * 0.02 + i as f32).sin() * 10.;
+                mesh_builder.circle(
+                    DrawMode::fill(),
+                    Vec2::new(x, y),
+                    1.5 + (i % 2) as f32,
+                    0.5,
+                    Color::new(1.0, 1.0, 1.0, 0.8),
+                )?;
+            }
+        }
+    }
+
+    match environment.daylight() {
+        Daylight::Day => {}
+        Daylight::Dusk => {
+            mesh_builder.rectangle(DrawMode::fill(), window, Color::new(0.4, 0.2, 0.2, 0.25))?;
+        }
+        Daylight::Night => {
+            mesh_builder.rectangle(DrawMode::fill(), window, Color::new(0.0, 0.02, 0.1, 0.55))?;
+        }
+    }
+
+    Ok(Some(mesh_builder))
+}
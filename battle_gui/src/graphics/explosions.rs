@@ -1,9 +1,17 @@
 use super::AssetsType;
 
+use battle_core::{
+    map::{terrain::TileType, Map},
+    physics::{
+        event::cloud::{Cloud, CloudType},
+        utils::DISTANCE_TO_METERS_COEFFICIENT,
+    },
+};
 use ggez::{
-    graphics::{DrawParam, Image, InstanceArray},
+    graphics::{Color, DrawMode, DrawParam, Image, InstanceArray, MeshBuilder},
     Context, GameError, GameResult,
 };
+use glam::Vec2;
 
 use crate::utils::qualified::ToQualified;
 
@@ -77,3 +85,76 @@ impl<'a> ExplosionsBuilder<'a> {
         Ok(batch)
     }
 }
+
+/// Craters left by explosions are drawn over the map background
+pub fn create_craters_mesh_builder(map: &Map) -> GameResult<Option<MeshBuilder>> {
+    let craters: Vec<_> = map
+        .tile_changes()
+        .into_iter()
+        .filter(|(_, type_)| type_ == &TileType::Crater)
+        .collect();
+    if craters.is_empty() {
+        return Ok(None);
+    }
+
+    let mut mesh_builder = MeshBuilder::new();
+    for (grid_point, _) in craters {
+        let center = map.world_point_from_grid_point(grid_point);
+        let radius = map.tile_width() as f32 * 0.8;
+        mesh_builder.circle(
+            DrawMode::fill(),
+            center.to_vec2(),
+            radius,
+            0.5,
+            Color::new(0.25, 0.2, 0.15, 0.9),
+        )?;
+        mesh_builder.circle(
+            DrawMode::stroke(1.0),
+            center.to_vec2(),
+            radius,
+            0.5,
+            Color::new(0.4, 0.32, 0.22, 0.9),
+        )?;
+    }
+
+    Ok(Some(mesh_builder))
+}
+
+/// Smoke and fire areas, drawn as particles slowly moving inside clouds
+pub fn create_clouds_mesh_builder(
+    clouds: &[Cloud],
+    frame_i: u64,
+) -> GameResult<Option<MeshBuilder>> {
+    if clouds.is_empty() {
+        return Ok(None);
+    }
+
+    let mut mesh_builder = MeshBuilder::new();
+    for cloud in clouds {
+        let radius = cloud.rayon().millimeters() as f32 / 1000. / DISTANCE_TO_METERS_COEFFICIENT;
+        let particles = (radius / 2.).clamp(6., 80.) as usize;
+        let particle_radius = radius * 0.3;
+        let center = cloud.point().to_vec2();
+
+        for i in 0..particles {
+            // Particles spread with golden angle and swirl with time
+            let swirl = frame_i as f32 * 0.002 * ((i % 3) + 1) as f32;
+            let angle = i as f32 * 2.399_963 + swirl;
+            let distance = radius * ((i as f32 + 0.5) / particles as f32).sqrt();
+            let position = center + Vec2::new(angle.cos(), angle.sin()) * distance;
+            let color = match cloud.type_() {
+                CloudType::Smoke => {
+                    let grey = 0.65 + (i % 4) as f32 * 0.05;
+                    Color::new(grey, grey, grey, 0.25 * cloud.density())
+                }
+                CloudType::Fire => {
+                    let flicker = ((frame_i as f32 * 0.2 + i as f32).sin() + 1.) / 2.;
+                    Color::new(0.9, 0.3 + flicker * 0.3, 0.1, 0.3 * cloud.density())
+                }
+            };
+            mesh_builder.circle(DrawMode::fill(), position, particle_radius, 0.5, color)?;
+        }
+    }
+
+    Ok(Some(mesh_builder))
+}
@@ -1,6 +1,10 @@
 use std::path::{Path, PathBuf};
 
-use battle_core::{config::ServerConfig, map::Map, types::WorldPoint};
+use battle_core::{
+    config::ServerConfig,
+    map::{terrain::TileType, Map},
+    types::{GridPoint, WorldPoint},
+};
 use ggez::{
     graphics::{Color, DrawMode, DrawParam, Image, InstanceArray, MeshBuilder, Rect},
     Context, GameError, GameResult,
@@ -154,7 +158,11 @@ pub fn create_debug_terrain_opacity_mesh_builder(
     for tile in map.terrain_tiles() {
         let dest_x = tile.x as f32 * tile.tile_width as f32;
         let dest_y = tile.y as f32 * tile.tile_height as f32;
-        let color_modifier = config.terrain_tile_opacity(&tile.type_);
+        let grid_point = GridPoint::new(tile.x as i32, tile.y as i32);
+        let color_modifier = match map.opening(&grid_point) {
+            Some(opening) => opening.opacity(),
+            None => config.terrain_tile_opacity(&tile.type_),
+        };
         debug_terrain_opacity_mesh.rectangle(
             DrawMode::fill(),
             Rect::new(
@@ -173,3 +181,36 @@ pub fn create_debug_terrain_opacity_mesh_builder(
     }
     Ok(debug_terrain_opacity_mesh)
 }
+
+/// Changed tiles (by example destroyed walls) are drawn over the map background
+pub fn create_terrain_changes_mesh_builder(map: &Map) -> GameResult<Option<MeshBuilder>> {
+    let changes = map.tile_changes();
+    if changes.is_empty() {
+        return Ok(None);
+    }
+
+    let mut mesh_builder = MeshBuilder::new();
+    for (grid_point, type_) in changes {
+        // Craters are drawn as explosions decals
+        if type_ == TileType::Crater {
+            continue;
+        }
+        let color = match type_ {
+            TileType::MiddleRock => Color::new(0.45, 0.43, 0.40, 1.0),
+            TileType::MiddleWoodLogs => Color::new(0.35, 0.25, 0.15, 1.0),
+            _ => Color::new(0.45, 0.35, 0.22, 1.0),
+        };
+        mesh_builder.rectangle(
+            DrawMode::fill(),
+            Rect::new(
+                (grid_point.x * map.tile_width() as i32) as f32,
+                (grid_point.y * map.tile_height() as i32) as f32,
+                map.tile_width() as f32,
+                map.tile_height() as f32,
+            ),
+            color,
+        )?;
+    }
+
+    Ok(Some(mesh_builder))
+}
@@ -6,6 +6,7 @@ pub enum GraphicsMessage {
     PushExplosionAnimation(WorldPoint, ExplosiveType),
     RemoveExplosionAnimation(WorldPoint),
     RecomputeDebugTerrainOpacity,
+    RefreshTerrain,
     ReloadSoldiersAsset,
     ReloadVehiclesAsset,
     ReloadExplosionsAsset,
@@ -3,13 +3,14 @@ use std::{collections::HashMap, fs, path::PathBuf};
 use battle_core::{
     config::ServerConfig,
     entity::{soldier::Soldier, vehicle::Vehicle},
-    game::control::MapControl,
+    game::{control::MapControl, environment::Environment},
     graphics::vehicle::VehicleGraphicInfos,
     map::Map,
+    physics::event::cloud::Cloud,
     types::{Scale, SoldierIndex, SquadUuid, VehicleIndex, WindowPoint, WorldPoint},
 };
 use ggez::{
-    graphics::{self, Canvas, DrawParam, Image, InstanceArray, Mesh, MeshBuilder, Rect},
+    graphics::{self, Canvas, Color, DrawParam, Image, InstanceArray, Mesh, MeshBuilder, Rect},
     Context, GameError, GameResult,
 };
 use glam::Vec2;
@@ -48,6 +49,7 @@ pub mod animation;
 pub mod background;
 pub mod batch;
 pub mod decors;
+pub mod environment;
 pub mod explosions;
 pub mod flag;
 pub mod interiors;
@@ -117,6 +119,10 @@ pub struct Graphics {
     debug_terrain_batch: InstanceArray,
     //
     debug_terrain_opacity_mesh_builder: MeshBuilder,
+    // Tiles changed during battle
+    terrain_changes_mesh_builder: Option<MeshBuilder>,
+    // Craters left by explosions
+    craters_mesh_builder: Option<MeshBuilder>,
 }
 
 impl Graphics {
@@ -159,6 +165,8 @@ impl Graphics {
         let debug_terrain_batch = map::create_debug_terrain_batch(ctx, map)?;
         let debug_terrain_opacity_mesh_builder =
             map::create_debug_terrain_opacity_mesh_builder(map, config)?;
+        let terrain_changes_mesh_builder = map::create_terrain_changes_mesh_builder(map)?;
+        let craters_mesh_builder = explosions::create_craters_mesh_builder(map)?;
 
         Ok(Graphics {
             soldiers,
@@ -185,6 +193,8 @@ impl Graphics {
             explosion_sequences: vec![],
             debug_terrain_batch,
             debug_terrain_opacity_mesh_builder,
+            terrain_changes_mesh_builder,
+            craters_mesh_builder,
         })
     }
 
@@ -269,6 +279,12 @@ impl Graphics {
     ) -> Vec<graphics::DrawParam> {
         let vehicle_sprite_infos = VehicleGraphicInfos::from_type(vehicle.type_());
         let mut sprites = vec![];
+        // Knocked out vehicle is burnt
+        let color = if vehicle.knocked_out() {
+            Color::new(0.35, 0.35, 0.35, 1.0)
+        } else {
+            Color::WHITE
+        };
 
         let vehicle_sprite_offset: (f32, f32) = (
             VEHICLE_TILE_WIDTH * zoom.factor() * 0.5,
@@ -297,7 +313,8 @@ impl Graphics {
             .offset(Vec2::from(vehicle_sprite_offset))
             .src(Rect::from(body_sprite.relative_rect().to_array()))
             .rotation(vehicle.chassis_orientation().0)
-            .dest(vehicle.world_point().to_vec2() * zoom.factor());
+            .dest(vehicle.world_point().to_vec2() * zoom.factor())
+            .color(color);
         sprites.push(body_draw);
 
         // Main turret
@@ -322,7 +339,8 @@ impl Graphics {
                 )
                 .src(Rect::from(turret_sprite.relative_rect().to_array()))
                 .dest(vehicle.world_point().to_vec2() * zoom.factor())
-                .rotation(vehicle.chassis_orientation().0);
+                .rotation(vehicle.chassis_orientation().0)
+                .color(color);
             sprites.push(turret_draw);
         }
 
@@ -507,6 +525,12 @@ impl Graphics {
                 self.debug_terrain_opacity_mesh_builder =
                     map::create_debug_terrain_opacity_mesh_builder(map, config)?;
             }
+            GraphicsMessage::RefreshTerrain => {
+                self.debug_terrain_opacity_mesh_builder =
+                    map::create_debug_terrain_opacity_mesh_builder(map, config)?;
+                self.terrain_changes_mesh_builder = map::create_terrain_changes_mesh_builder(map)?;
+                self.craters_mesh_builder = explosions::create_craters_mesh_builder(map)?;
+            }
             GraphicsMessage::ReloadSoldiersAsset => {
                 self.soldiers = SoldiersBuilder::new(ctx).build()?;
             }
@@ -540,12 +564,63 @@ impl Graphics {
                 self.debug_terrain_batch = map::create_debug_terrain_batch(ctx, map)?;
                 self.debug_terrain_opacity_mesh_builder =
                     map::create_debug_terrain_opacity_mesh_builder(map, config)?;
+                self.terrain_changes_mesh_builder = map::create_terrain_changes_mesh_builder(map)?;
+                self.craters_mesh_builder = explosions::create_craters_mesh_builder(map)?;
             }
         }
 
         GameResult::Ok(())
     }
 
+    pub fn draw_terrain_changes(
+        &mut self,
+        ctx: &mut Context,
+        canvas: &mut Canvas,
+        draw_param: graphics::DrawParam,
+    ) -> GameResult<()> {
+        if let Some(mesh_builder) = &self.terrain_changes_mesh_builder {
+            canvas.draw(&Mesh::from_data(ctx, mesh_builder.build()), draw_param);
+        }
+        if let Some(mesh_builder) = &self.craters_mesh_builder {
+            canvas.draw(&Mesh::from_data(ctx, mesh_builder.build()), draw_param);
+        }
+
+        GameResult::Ok(())
+    }
+
+    pub fn draw_clouds(
+        &mut self,
+        ctx: &mut Context,
+        canvas: &mut Canvas,
+        clouds: &[Cloud],
+        frame_i: u64,
+        draw_param: graphics::DrawParam,
+    ) -> GameResult<()> {
+        if let Some(mesh_builder) = explosions::create_clouds_mesh_builder(clouds, frame_i)? {
+            canvas.draw(&Mesh::from_data(ctx, mesh_builder.build()), draw_param);
+        }
+
+        GameResult::Ok(())
+    }
+
+    pub fn draw_environment(
+        &mut self,
+        ctx: &mut Context,
+        canvas: &mut Canvas,
+        environment: &Environment,
+        frame_i: u64,
+        draw_param: graphics::DrawParam,
+    ) -> GameResult<()> {
+        let (width, height) = ctx.gfx.drawable_size();
+        if let Some(mesh_builder) =
+            environment::create_environment_mesh_builder(environment, frame_i, width, height)?
+        {
+            canvas.draw(&Mesh::from_data(ctx, mesh_builder.build()), draw_param);
+        }
+
+        GameResult::Ok(())
+    }
+
     pub fn draw_debug_terrain(
         &mut self,
         ctx: &mut Context,
@@ -113,6 +113,8 @@ impl Graphics {
             Behavior::Unconscious => SoldierAnimationType::LyingDown,
             Behavior::SuppressFire(_) => SoldierAnimationType::LyingDown,
             Behavior::EngageSoldier(_) => SoldierAnimationType::LyingDown,
+            Behavior::EngageVehicle(_) => SoldierAnimationType::LyingDown,
+            Behavior::ThrowSmoke(_) => SoldierAnimationType::Idle,
         };
         Box::new(animation_type)
     }
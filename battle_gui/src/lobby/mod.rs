@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+use battle_core::{
+    game::Side,
+    message::{InputMessage, OutputMessage},
+    state::lobby::{BattleSetup, LobbyMessage, LobbyState},
+};
+use crossbeam_channel::{Receiver, RecvTimeoutError, SendError, Sender};
+use thiserror::Error;
+
+// If nothing received from server since this duration, ask again for the lobby state
+const LOBBY_SYNC_DURATION_MS: u64 = 1000;
+
+/// Join the server lobby, send given proposals and wait battle setup agreed by all sides.
+/// Proposals made by other clients are accepted as they come.
+pub fn negotiate(
+    side: &Side,
+    proposals: Vec<LobbyMessage>,
+    input_sender: &Sender<Vec<InputMessage>>,
+    output_receiver: &Receiver<Vec<OutputMessage>>,
+) -> Result<BattleSetup, LobbyError> {
+    let mut messages = vec![InputMessage::Lobby(LobbyMessage::Join(*side))];
+    messages.extend(proposals.into_iter().map(InputMessage::Lobby));
+    messages.push(InputMessage::Lobby(LobbyMessage::SetReady(*side, true)));
+    input_sender.send(messages)?;
+
+    let mut last_summary = String::new();
+    loop {
+        let messages =
+            match output_receiver.recv_timeout(Duration::from_millis(LOBBY_SYNC_DURATION_MS)) {
+                Ok(messages) => messages,
+                Err(RecvTimeoutError::Timeout) => {
+                    input_sender.send(vec![InputMessage::RequireCompleteSync])?;
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => return Err(LobbyError::ServerLost),
+            };
+
+        for message in messages {
+            match message {
+                OutputMessage::Lobby(state) => {
+                    let summary = summary(&state);
+                    if summary != last_summary {
+                        println!("Lobby : {}", summary);
+                        last_summary = summary;
+                    }
+
+                    if let Some(error) = state.error() {
+                        return Err(LobbyError::Refused(error.clone()));
+                    }
+
+                    if !state.connected(side) {
+                        input_sender.send(vec![InputMessage::Lobby(LobbyMessage::Join(*side))])?;
+                    } else if !state.ready(side)
+                        && state.map_name().is_some()
+                        && state.deployment().is_some()
+                    {
+                        input_sender.send(vec![InputMessage::Lobby(LobbyMessage::SetReady(
+                            *side, true,
+                        ))])?;
+                    }
+                }
+                OutputMessage::BattleSetup(setup) => return Ok(setup),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn summary(state: &LobbyState) -> String {
+    let side_summary = |side: &Side| {
+        if state.ai(side) {
+            "ai"
+        } else if state.ready(side) {
+            "ready"
+        } else if state.connected(side) {
+            "connected"
+        } else {
+            "waiting"
+        }
+    };
+
+    format!(
+        "map {}, deployment {}, mode {}, side A {}, side B {}",
+        state.map_name().as_deref().unwrap_or("not proposed"),
+        if state.deployment().is_some() {
+            "proposed"
+        } else {
+            "not proposed"
+        },
+        if state.lockstep() {
+            "lockstep"
+        } else {
+            "server"
+        },
+        side_summary(&Side::A),
+        side_summary(&Side::B),
+    )
+}
+
+#[derive(Error, Debug)]
+pub enum LobbyError {
+    #[error("Error during lobby message send : {0}")]
+    SendInput(SendError<Vec<InputMessage>>),
+    #[error("Server connection lost")]
+    ServerLost,
+    #[error("Server refused battle setup : {0}")]
+    Refused(String),
+}
+
+impl From<SendError<Vec<InputMessage>>> for LobbyError {
+    fn from(error: SendError<Vec<InputMessage>>) -> Self {
+        Self::SendInput(error)
+    }
+}
@@ -9,30 +9,37 @@ use battle_core::config::DEFAULT_SERVER_REP_ADDRESS;
 use battle_core::deployment::DeploymentReader;
 use battle_core::deployment::DeploymentReaderError;
 use battle_core::game::control::MapControl;
+use battle_core::game::environment::EnvironmentChange;
 use battle_core::game::Side;
 use battle_core::map::reader::MapReader;
 use battle_core::map::reader::MapReaderError;
 use battle_core::message::InputMessage;
 use battle_core::network::client::Client;
 use battle_core::network::error::NetworkError;
+use battle_core::network::transport::tcp::TcpTransport;
+use battle_core::network::transport::zmq::ZmqTransport;
+use battle_core::network::transport::Transport;
 use battle_core::state::battle::builder::BattleStateBuilder;
 use battle_core::state::battle::builder::BattleStateBuilderError;
 use battle_core::state::battle::message::BattleStateMessage;
+use battle_core::state::lobby::LobbyMessage;
 use crossbeam_channel::unbounded;
 use crossbeam_channel::SendError;
 use ggez::conf::WindowMode;
 use ggez::event;
 use ggez::GameError;
+use lobby::LobbyError;
 use oc_core::resources::Resources;
 use oc_core::resources::ResourcesError;
 use oc_core::spawn::SpawnZoneName;
-use server::EmbeddedServer;
+use server::{lockstep::LockstepPeer, Connection, EmbeddedServer};
 use thiserror::Error;
 
 mod audio;
 mod debug;
 mod engine;
 mod graphics;
+mod lobby;
 mod physics;
 mod saves;
 mod server;
@@ -45,11 +52,26 @@ use structopt::StructOpt;
 #[derive(StructOpt, Debug)]
 #[structopt(name = "basic")]
 pub struct Opt {
+    /// Map proposed in server lobby
     #[structopt()]
-    map_name: String,
+    map_name: Option<String>,
 
+    /// Deployment proposed in server lobby
     #[structopt(parse(from_os_str))]
-    deployment: PathBuf,
+    deployment: Option<PathBuf>,
+
+    /// Propose in server lobby to run the battle on each client, server only relay orders
+    #[structopt(long = "--lockstep")]
+    lockstep: bool,
+
+    /// Propose in server lobby to let AI play this side (like `--ai b` for single player)
+    #[structopt(long = "--ai")]
+    ai: Vec<Side>,
+
+    /// Propose in server lobby weather and daylight, like `fog,dusk` or `300:rain,night` to
+    /// change it after 300 seconds (can be repeated)
+    #[structopt(long = "--environment")]
+    environment: Vec<EnvironmentChange>,
 
     #[structopt(long = "--embedded-server")]
     embedded_server: bool,
@@ -60,6 +82,10 @@ pub struct Opt {
     #[structopt(long = "--server-bind-address", default_value = DEFAULT_SERVER_PUB_ADDRESS)]
     server_pub_address: String,
 
+    /// Connect to server with plain tcp transport instead of zmq (like 127.0.0.1:4257)
+    #[structopt(long = "--server-tcp-address")]
+    server_tcp_address: Option<String>,
+
     #[structopt(long = "side")]
     side: Side,
 
@@ -69,16 +95,13 @@ pub struct Opt {
     #[structopt(long = "--profile-address", default_value = "0.0.0.0:8585")]
     profile_address: String,
 
-    #[structopt(long = "side-a-control")]
-    a_control: Vec<SpawnZoneName>,
-
-    #[structopt(long = "side-b-control")]
-    b_control: Vec<SpawnZoneName>,
+    /// Spawn zones proposed in server lobby for the played side
+    #[structopt(long = "control")]
+    control: Vec<SpawnZoneName>,
 }
 
 fn main() -> Result<(), GuiError> {
     let opt = Opt::from_args();
-    let map_name: &String = &opt.map_name;
     let sync_required = Arc::new(AtomicBool::new(true));
     let stop_required = Arc::new(AtomicBool::new(false));
     let resources = Resources::new()?.ensure()?;
@@ -93,58 +116,98 @@ fn main() -> Result<(), GuiError> {
         None
     };
 
-    let (input_sender, output_receiver) = if opt.embedded_server {
-        let (input_sender, input_receiver) = unbounded();
-        let (output_sender, output_receiver) = unbounded();
+    let (input_sender, input_receiver) = unbounded();
+    let (output_sender, output_receiver) = unbounded();
+    let (transport, embedded_server): (Box<dyn Transport>, Option<EmbeddedServer>) =
+        if opt.embedded_server {
+            let mut server = EmbeddedServer::new(&resources.lib(), stop_required.clone())
+                .server_rep_address(&opt.server_rep_address)
+                .server_pub_address(&opt.server_pub_address);
+            server.start()?;
+            (Box::new(server.transport()), Some(server))
+        } else if let Some(server_tcp_address) = &opt.server_tcp_address {
+            (Box::new(TcpTransport::new(server_tcp_address)), None)
+        } else {
+            (
+                Box::new(ZmqTransport::new(
+                    &opt.server_rep_address,
+                    &opt.server_pub_address,
+                )),
+                None,
+            )
+        };
+
+    let mut client = Client::new(
+        transport,
+        input_sender.clone(),
+        input_receiver,
+        output_sender,
+        output_receiver.clone(),
+        sync_required.clone(),
+        stop_required.clone(),
+    );
+    client.connect()?;
+    let mut connection = Connection::new(client, embedded_server);
 
-        EmbeddedServer::new(
+    let mut proposals = vec![];
+    if let Some(map_name) = &opt.map_name {
+        proposals.push(LobbyMessage::SetMapName(map_name.clone()));
+    }
+    if let Some(deployment) = &opt.deployment {
+        let deployment = DeploymentReader::from_file(deployment)?;
+        proposals.push(LobbyMessage::SetDeployment(deployment));
+    }
+    if !opt.control.is_empty() {
+        proposals.push(LobbyMessage::SetControl(
+            opt.side,
+            MapControl::new(opt.control.clone()),
+        ));
+    }
+    if opt.lockstep {
+        proposals.push(LobbyMessage::SetLockstep(true));
+    }
+    for side in &opt.ai {
+        proposals.push(LobbyMessage::SetAi(*side, true));
+    }
+    if !opt.environment.is_empty() {
+        proposals.push(LobbyMessage::SetEnvironments(opt.environment.clone()));
+    }
+    println!("Join lobby");
+    let setup = lobby::negotiate(&opt.side, proposals, &input_sender, &output_receiver)?;
+    let map_name = setup.map_name();
+    let a_control = setup.a_control().clone();
+    let b_control = setup.b_control().clone();
+
+    // In lockstep mode, gui exchange with a local runner instead of the server
+    let (input_sender, output_receiver, sync_required) = if setup.lockstep() {
+        let lockstep_peer = LockstepPeer::start(
+            &setup,
+            &opt.side,
             &resources.lib(),
-            input_receiver,
-            output_sender,
+            input_sender,
+            output_receiver,
             stop_required.clone(),
-        )
-        .map_name(map_name)
-        .server_rep_address(&opt.server_rep_address)
-        .server_pub_address(&opt.server_pub_address)
-        .start()?;
-
-        (input_sender, output_receiver)
+        )?;
+        let channels = (
+            lockstep_peer.input_sender(),
+            lockstep_peer.output_receiver(),
+            Arc::new(AtomicBool::new(true)),
+        );
+        connection = connection.lockstep_peer(lockstep_peer);
+        channels
     } else {
-        let (input_sender, input_receiver) = unbounded();
-        let (output_sender, output_receiver) = unbounded();
-
-        Client::new(
-            opt.server_rep_address.clone(),
-            opt.server_pub_address.clone(),
-            input_sender.clone(),
-            input_receiver,
-            output_sender,
-            output_receiver.clone(),
-            sync_required.clone(),
-        )
-        .connect()?;
-
-        (input_sender, output_receiver)
+        (input_sender, output_receiver, sync_required)
     };
 
-    let deployment = DeploymentReader::from_file(&opt.deployment)?;
-    let a_control = MapControl::new(opt.a_control.clone());
-    let b_control = MapControl::new(opt.b_control.clone());
-
     let ready_message = if opt.side == Side::A {
         InputMessage::BattleState(BattleStateMessage::SetAConnected(true))
     } else {
         InputMessage::BattleState(BattleStateMessage::SetBConnected(true))
     };
 
-    // These messages will initialize the battle state
+    // Battle state has been initialized by server from lobby setup
     // Then, the RequireCompleteSync permit client to be same state than server
-    input_sender.send(vec![
-        InputMessage::LoadDeployment(deployment),
-        InputMessage::LoadControl((a_control.clone(), b_control.clone())),
-        InputMessage::RequireCompleteSync,
-        ready_message,
-    ])?;
+    input_sender.send(vec![InputMessage::RequireCompleteSync, ready_message])?;
 
     let mut context_builder = ggez::ContextBuilder::new("Open Combat", "Bastien Sevajol")
         .window_mode(
@@ -175,11 +238,11 @@ fn main() -> Result<(), GuiError> {
         battle_state,
         sync_required,
         stop_required.clone(),
+        connection,
         a_control,
         b_control,
     )?;
 
-    // FIXME BS NOW : Closing GUI don't close thread correctly and keep process running
     println!("Start Gui");
     event::run(context, event_loop, engine)
 }
@@ -200,6 +263,8 @@ enum GuiError {
     Network(NetworkError),
     #[error("Embedded server error : {0}")]
     EmbeddedServer(EmbeddedServerError),
+    #[error("Lobby error : {0}")]
+    Lobby(LobbyError),
     #[error("Battle state builder error : {0}")]
     BattleStateBuilderError(BattleStateBuilderError),
 }
@@ -234,6 +299,12 @@ impl From<EmbeddedServerError> for GuiError {
     }
 }
 
+impl From<LobbyError> for GuiError {
+    fn from(error: LobbyError) -> Self {
+        Self::Lobby(error)
+    }
+}
+
 impl From<ResourcesError> for GuiError {
     fn from(error: ResourcesError) -> Self {
         Self::Resources(error)
@@ -0,0 +1,156 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use battle_core::config::{ServerConfig, NETWORK_POLL_TIMEOUT_MS};
+use battle_core::game::Side;
+use battle_core::message::{InputMessage, OutputMessage};
+use battle_core::state::battle::builder::BattleStateBuilderError;
+use battle_core::state::lobby::BattleSetup;
+use battle_server::ai::Ai;
+use battle_server::lobby::build_battle_state;
+use battle_server::runner::lockstep::Lockstep;
+use battle_server::runner::Runner;
+use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender};
+
+/// Battle run in gui process in lockstep mode. Gui exchange messages with this local runner
+/// like with a server, and the runner exchange lockstep messages with the server relay.
+pub struct LockstepPeer {
+    input_sender: Sender<Vec<InputMessage>>,
+    output_receiver: Receiver<Vec<OutputMessage>>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl LockstepPeer {
+    pub fn start(
+        setup: &BattleSetup,
+        side: &Side,
+        resources: &Path,
+        network_input_sender: Sender<Vec<InputMessage>>,
+        network_output_receiver: Receiver<Vec<OutputMessage>>,
+        stop_required: Arc<AtomicBool>,
+    ) -> Result<Self, BattleStateBuilderError> {
+        let battle_state = build_battle_state(setup, resources)?;
+        let (input_sender, input_receiver) = unbounded();
+        let (output_sender, output_receiver) = unbounded();
+
+        let runner = Runner::new(
+            ServerConfig::default(),
+            input_receiver,
+            output_sender.clone(),
+            stop_required.clone(),
+            battle_state,
+        )
+        .seed(setup.seed())
+        .environments(setup.environments().clone())
+        .lockstep(Lockstep::new(
+            *side,
+            setup.sides().clone(),
+            network_input_sender,
+        ));
+        // AI are deterministic, so each peer run them
+        let runner = setup
+            .ais()
+            .iter()
+            .fold(runner, |runner, side| runner.ai(Ai::new(*side)));
+
+        let handles = vec![
+            Self::start_runner(runner),
+            Self::start_bridge(
+                network_output_receiver,
+                input_sender.clone(),
+                output_sender,
+                stop_required,
+            ),
+        ];
+
+        Ok(Self {
+            input_sender,
+            output_receiver,
+            handles,
+        })
+    }
+
+    /// Channel to send gui messages to the local runner
+    pub fn input_sender(&self) -> Sender<Vec<InputMessage>> {
+        self.input_sender.clone()
+    }
+
+    /// Channel to receive local runner messages
+    pub fn output_receiver(&self) -> Receiver<Vec<OutputMessage>> {
+        self.output_receiver.clone()
+    }
+
+    fn start_runner(mut runner: Runner) -> JoinHandle<()> {
+        thread::Builder::new()
+            .name("lockstep_runner".to_string())
+            .spawn(move || {
+                println!("Start lockstep runner");
+                match runner.run() {
+                    Ok(_) => println!("Lockstep runner finished to run"),
+                    Err(error) => println!("ERROR : Lockstep runner fail to run : {}", error),
+                }
+            })
+            .unwrap()
+    }
+
+    /// Give lockstep messages received from server relay to the local runner. Other server
+    /// messages are given to the gui.
+    fn start_bridge(
+        network_output_receiver: Receiver<Vec<OutputMessage>>,
+        runner_input_sender: Sender<Vec<InputMessage>>,
+        gui_output_sender: Sender<Vec<OutputMessage>>,
+        stop_required: Arc<AtomicBool>,
+    ) -> JoinHandle<()> {
+        thread::Builder::new()
+            .name("lockstep_bridge".to_string())
+            .spawn(move || {
+                let timeout = Duration::from_millis(NETWORK_POLL_TIMEOUT_MS as u64);
+                loop {
+                    let messages = match network_output_receiver.recv_timeout(timeout) {
+                        Ok(messages) => messages,
+                        Err(RecvTimeoutError::Timeout) => {
+                            if stop_required.load(Ordering::Relaxed) {
+                                break;
+                            }
+                            continue;
+                        }
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    };
+
+                    let mut inputs = vec![];
+                    let mut outputs = vec![];
+                    for message in messages {
+                        match message {
+                            OutputMessage::Lockstep(message) => {
+                                inputs.push(InputMessage::Lockstep(message))
+                            }
+                            message => outputs.push(message),
+                        }
+                    }
+
+                    if (!inputs.is_empty() && runner_input_sender.send(inputs).is_err())
+                        || (!outputs.is_empty() && gui_output_sender.send(outputs).is_err())
+                    {
+                        println!("Channel was closed when try to bridge lockstep messages");
+                        break;
+                    }
+                }
+
+                println!("Lockstep bridge finished")
+            })
+            .unwrap()
+    }
+
+    /// Wait runner and bridge threads end. Threads end when stop is required.
+    pub fn join(&mut self) {
+        for handle in self.handles.drain(..) {
+            let name = handle.thread().name().unwrap_or("?").to_string();
+            if handle.join().is_err() {
+                println!("Lockstep thread {} panicked", name);
+            }
+        }
+    }
+}
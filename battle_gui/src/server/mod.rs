@@ -2,39 +2,30 @@ use std::fmt::Display;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
-use std::thread;
+use std::thread::{self, JoinHandle};
 
 use battle_core::config::{ServerConfig, DEFAULT_SERVER_PUB_ADDRESS, DEFAULT_SERVER_REP_ADDRESS};
 use battle_core::message::{InputMessage, OutputMessage};
+use battle_core::network::client::Client;
 use battle_core::network::error::NetworkError;
 use battle_core::network::server::Server;
-use battle_core::state::battle::builder::{BattleStateBuilder, BattleStateBuilderError};
-use battle_server::runner::Runner;
+use battle_core::network::transport::channel::ChannelTransport;
+use battle_core::network::transport::zmq::ZmqTransport;
+use battle_server::lobby::Lobby;
 use crossbeam_channel::{unbounded, Receiver, Sender};
 
-type ServerChannel = (Sender<Vec<OutputMessage>>, Receiver<Vec<InputMessage>>);
-type RunnerChannel = (Sender<Vec<InputMessage>>, Receiver<Vec<OutputMessage>>);
+use self::lockstep::LockstepPeer;
+
+pub mod lockstep;
 
 #[derive(Debug)]
 pub enum EmbeddedServerError {
-    MissingMapName,
-    StateBuilderError(BattleStateBuilderError),
     Network(NetworkError),
 }
 
-impl From<BattleStateBuilderError> for EmbeddedServerError {
-    fn from(error: BattleStateBuilderError) -> Self {
-        Self::StateBuilderError(error)
-    }
-}
-
 impl Display for EmbeddedServerError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            EmbeddedServerError::MissingMapName => f.write_str("Missing map name"),
-            EmbeddedServerError::StateBuilderError(error) => {
-                f.write_str(&format!("State builder error : {}", error))
-            }
             EmbeddedServerError::Network(error) => {
                 f.write_str(&format!("Network serve error : {}", error))
             }
@@ -42,39 +33,31 @@ impl Display for EmbeddedServerError {
     }
 }
 
+/// Lobby and server running in gui process. Gui connect to it with in process transport,
+/// other clients can connect to it with zmq transport.
 pub struct EmbeddedServer {
     resources: PathBuf,
-    map_name: Option<String>,
     server_rep_address: String,
     server_pub_address: String,
-    gui_input_receiver: Receiver<Vec<InputMessage>>,
-    gui_output_sender: Sender<Vec<OutputMessage>>,
     stop_required: Arc<AtomicBool>,
+    transport: ChannelTransport,
+    server: Option<Server>,
+    handles: Vec<JoinHandle<()>>,
 }
 
 impl EmbeddedServer {
-    pub fn new(
-        resources: &Path,
-        gui_input_receiver: Receiver<Vec<InputMessage>>,
-        gui_output_sender: Sender<Vec<OutputMessage>>,
-        stop_required: Arc<AtomicBool>,
-    ) -> Self {
+    pub fn new(resources: &Path, stop_required: Arc<AtomicBool>) -> Self {
         Self {
             resources: resources.to_path_buf(),
-            map_name: None,
             server_rep_address: DEFAULT_SERVER_REP_ADDRESS.to_string(),
             server_pub_address: DEFAULT_SERVER_PUB_ADDRESS.to_string(),
-            gui_input_receiver,
-            gui_output_sender,
             stop_required,
+            transport: ChannelTransport::new(),
+            server: None,
+            handles: vec![],
         }
     }
 
-    pub fn map_name(mut self, map_name: &str) -> Self {
-        self.map_name = Some(map_name.to_string());
-        self
-    }
-
     pub fn server_rep_address(mut self, address: &str) -> Self {
         self.server_rep_address = address.to_string();
         self
@@ -85,127 +68,125 @@ impl EmbeddedServer {
         self
     }
 
-    fn start_runner(&self) -> Result<RunnerChannel, EmbeddedServerError> {
-        let (runner_input_sender, runner_input_receiver) = unbounded();
-        let (runner_output_sender, runner_output_receiver) = unbounded();
+    /// In process transport to give to the gui client
+    pub fn transport(&self) -> ChannelTransport {
+        self.transport.clone()
+    }
 
-        let map_name = self
-            .map_name
-            .as_ref()
-            .ok_or(EmbeddedServerError::MissingMapName)?;
+    fn start_runner(
+        &mut self,
+        runner_input_receiver: Receiver<Vec<InputMessage>>,
+        runner_output_sender: Sender<Vec<OutputMessage>>,
+    ) {
         let config = ServerConfig::default();
-        let state = BattleStateBuilder::new(map_name, self.resources.clone()).build()?;
-
+        let resources = self.resources.clone();
         let stop_required_ = self.stop_required.clone();
-        thread::Builder::new()
+        let handle = thread::Builder::new()
             .name("runner".to_string())
-            .spawn(|| {
-                println!("Start runner");
-                match Runner::new(
+            .spawn(move || {
+                println!("Start lobby");
+                match Lobby::new(
                     config,
+                    &resources,
                     runner_input_receiver,
                     runner_output_sender,
                     stop_required_,
-                    state,
                 )
                 .run()
                 {
                     Ok(_) => {
-                        println!("Runner finished to run")
+                        println!("Lobby finished to run")
                     }
                     Err(error) => {
-                        println!("ERROR : Runner fail to run : {}", error)
+                        println!("ERROR : Lobby fail to run : {}", error)
                     }
                 };
             })
             .unwrap();
-
-        Ok((runner_input_sender, runner_output_receiver))
+        self.handles.push(handle);
     }
 
-    fn start_server(&self) -> Result<ServerChannel, EmbeddedServerError> {
-        let server_rep_address = self.server_rep_address.clone();
-        let server_pub_address = self.server_pub_address.clone();
-        let (server_input_sender, server_input_receiver) = unbounded();
-        let (server_output_sender, server_output_receiver) = unbounded();
-
+    fn start_server(
+        &mut self,
+        server_output_receiver: Receiver<Vec<OutputMessage>>,
+        server_input_sender: Sender<Vec<InputMessage>>,
+    ) -> Result<(), EmbeddedServerError> {
         println!("Start server");
-        if let Err(error) = Server::new(
-            server_rep_address,
-            server_pub_address,
+        let mut server = Server::new(
+            vec![
+                Box::new(self.transport.clone()),
+                Box::new(ZmqTransport::new(
+                    &self.server_rep_address,
+                    &self.server_pub_address,
+                )),
+            ],
             server_output_receiver,
             server_input_sender,
             self.stop_required.clone(),
-        )
-        .serve()
-        {
+        );
+        if let Err(error) = server.serve() {
             return Err(EmbeddedServerError::Network(error));
         };
+        self.server = Some(server);
 
-        Ok((server_output_sender, server_input_receiver))
+        Ok(())
     }
 
-    pub fn start(&self) -> Result<(), EmbeddedServerError> {
-        let (runner_input_sender, runner_output_receiver) = self.start_runner()?;
-        let (server_output_sender, server_input_receiver) = self.start_server()?;
+    pub fn start(&mut self) -> Result<(), EmbeddedServerError> {
+        let (input_sender, input_receiver) = unbounded();
+        let (output_sender, output_receiver) = unbounded();
 
-        let gui_input_receiver_ = self.gui_input_receiver.clone();
-        let runner_input_sender_ = runner_input_sender.clone();
-        thread::Builder::new()
-            .name("emb_gui_inputs_bridge".to_string())
-            .spawn(move || {
-                while let Ok(messages) = gui_input_receiver_.recv() {
-                    if let Err(error) = runner_input_sender_.send(messages) {
-                        println!(
-                            "Error during transmit gui input messages to runner : {}",
-                            error
-                        )
-                    }
-                }
+        self.start_server(output_receiver, input_sender)?;
+        self.start_runner(input_receiver, output_sender);
 
-                println!("Gui input bridge finished");
-            })
-            .expect("Thread must be builded correctly");
+        Ok(())
+    }
 
-        let gui_output_sender_ = self.gui_output_sender.clone();
-        thread::Builder::new()
-            .name("emb_runner_outputs_bridge".to_string())
-            .spawn(move || {
-                while let Ok(messages) = runner_output_receiver.recv() {
-                    if let Err(error) = gui_output_sender_.send(messages.clone()) {
-                        println!(
-                            "Error during transmit runner output messages to gui : {}",
-                            error
-                        )
-                    };
-                    if let Err(error) = server_output_sender.send(messages) {
-                        println!(
-                            "Error during transmit runner output messages to server : {}",
-                            error
-                        )
-                    };
-                }
-
-                println!("Runner output bridge finished");
-            })
-            .expect("Thread must be builded correctly");
+    /// Wait runner and server threads end. Threads end when stop is required : runner stop
+    /// first, then pending output messages are sent by server before its sockets are closed.
+    pub fn join(&mut self) {
+        for handle in self.handles.drain(..) {
+            let name = handle.thread().name().unwrap_or("?").to_string();
+            if handle.join().is_err() {
+                println!("Embedded server thread {} panicked", name);
+            }
+        }
 
-        thread::Builder::new()
-            .name("emb_server_inputs_bridge".to_string())
-            .spawn(move || {
-                while let Ok(messages) = server_input_receiver.recv() {
-                    if let Err(error) = runner_input_sender.send(messages) {
-                        println!(
-                            "Error during transmit server input messages to runner : {}",
-                            error
-                        )
-                    }
-                }
+        if let Some(mut server) = self.server.take() {
+            server.join();
+        }
+    }
+}
 
-                println!("Server input bridge finished");
-            })
-            .expect("Thread must be builded correctly");
+/// Network part used by the gui. Must be closed after stop required to wait its threads end.
+pub struct Connection {
+    client: Client,
+    embedded_server: Option<EmbeddedServer>,
+    lockstep_peer: Option<LockstepPeer>,
+}
 
-        Ok(())
+impl Connection {
+    pub fn new(client: Client, embedded_server: Option<EmbeddedServer>) -> Self {
+        Self {
+            client,
+            embedded_server,
+            lockstep_peer: None,
+        }
+    }
+
+    pub fn lockstep_peer(mut self, lockstep_peer: LockstepPeer) -> Self {
+        self.lockstep_peer = Some(lockstep_peer);
+        self
+    }
+
+    pub fn close(&mut self) {
+        // Local runner first, to let its last messages be sent by client
+        if let Some(lockstep_peer) = &mut self.lockstep_peer {
+            lockstep_peer.join();
+        }
+        self.client.join();
+        if let Some(embedded_server) = &mut self.embedded_server {
+            embedded_server.join();
+        }
     }
 }
@@ -122,7 +122,7 @@ impl IntoSprite for CurrentAction {
                 ACTION_REL_WIDTH,
                 ACTION_REL_HEIGHT,
             ],
-            CurrentAction::Rotating => [
+            CurrentAction::Rotating | CurrentAction::ThrowingSmoke => [
                 ACTION_EMPTY_START_REL_X,
                 ACTION_EMPTY_START_REL_Y,
                 ACTION_REL_WIDTH,
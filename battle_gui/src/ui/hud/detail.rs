@@ -1,5 +1,5 @@
 use battle_core::{
-    game::squad::SquadStatusResume,
+    game::{role::SoldierRole, squad::SquadStatusResume},
     types::{SoldierIndex, WindowPoint},
 };
 use ggez::{
@@ -148,6 +148,36 @@ impl Component<HudEvent> for SquadDetail {
                 ),
                 Color::new(0.5, squad.health().0, 0., 1.),
             )?;
+            // Click on it to change squad formation
+            canvas.draw(
+                Text::new(TextFragment::new(squad.formation().display()).color(Color::WHITE))
+                    .set_layout(TextLayout::center()),
+                DrawParam::default().dest(
+                    health_point
+                        .apply(Vec2::new(
+                            (self.width(ctx) - (SQUAD_TYPE_WIDTH + MARGIN * 2.)) / 2.,
+                            SQUAD_TYPE_HEIGHT / 4.,
+                        ))
+                        .to_vec2(),
+                ),
+            );
+            // Click on it to change squad target policy
+            let target_policy = squad
+                .target_policy()
+                .map(|policy| policy.display())
+                .unwrap_or("by role");
+            canvas.draw(
+                Text::new(TextFragment::new(target_policy).color(Color::WHITE))
+                    .set_layout(TextLayout::center()),
+                DrawParam::default().dest(
+                    health_point
+                        .apply(Vec2::new(
+                            (self.width(ctx) - (SQUAD_TYPE_WIDTH + MARGIN * 2.)) / 2.,
+                            SQUAD_TYPE_HEIGHT * 3. / 4.,
+                        ))
+                        .to_vec2(),
+                ),
+            );
 
             let soldiers_status_start_point = self
                 .point
@@ -159,11 +189,13 @@ impl Component<HudEvent> for SquadDetail {
                     (self.width(ctx) - MARGIN - SOLDIER_WIDTH) / 2.,
                     SOLDIER_HEIGHT / 2.,
                 ));
+                let text = match soldier_status.role() {
+                    SoldierRole::Rifleman => soldier_status.current().display().to_string(),
+                    role => format!("{} {}", role.display(), soldier_status.current().display()),
+                };
                 canvas.draw(
-                    Text::new(
-                        TextFragment::new(soldier_status.current().display()).color(Color::WHITE),
-                    )
-                    .set_layout(TextLayout::center()),
+                    Text::new(TextFragment::new(text).color(Color::WHITE))
+                        .set_layout(TextLayout::center()),
                     DrawParam::default().dest(text_center_dest.to_vec2()),
                 );
 
@@ -207,6 +239,18 @@ impl Component<HudEvent> for SquadDetail {
         let mouse_position = ctx.mouse.position();
 
         if let Some(squad) = &self.squad {
+            let health_point = self.point.apply(Vec2::new(SQUAD_TYPE_WIDTH + MARGIN, 0.));
+            if mouse_position.x >= health_point.x
+                && mouse_position.x <= self.point.x + SQUAD_DETAIL_WIDTH
+                && mouse_position.y >= health_point.y
+                && mouse_position.y <= health_point.y + SQUAD_TYPE_HEIGHT
+            {
+                if mouse_position.y <= health_point.y + SQUAD_TYPE_HEIGHT / 2. {
+                    return Some(HudEvent::NextSquadFormation(*squad.squad_id()));
+                }
+                return Some(HudEvent::NextSquadTargetPolicy(*squad.squad_id()));
+            }
+
             let soldiers_status_start_point = self
                 .point
                 .apply(Vec2::new(SOLDIER_WIDTH, SQUAD_TYPE_HEIGHT + MARGIN));
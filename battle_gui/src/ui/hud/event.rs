@@ -7,4 +7,6 @@ pub enum HudEvent {
     SelectSquad(SquadUuid),
     SelectSoldier(SoldierIndex),
     CenterMapOn(WorldPoint),
+    NextSquadFormation(SquadUuid),
+    NextSquadTargetPolicy(SquadUuid),
 }
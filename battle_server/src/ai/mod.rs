@@ -0,0 +1,479 @@
+use battle_core::{
+    config::{
+        ServerConfig, AI_ENGAGE_DISTANCE_METERS, AI_OBJECTIVE_DISTANCE_METERS, AI_RETREAT_HEALTH,
+        AI_RETREAT_MORALE,
+    },
+    entity::soldier::Soldier,
+    game::{
+        cover::CoverFinder,
+        flag::FlagOwnership,
+        squad::{SquadStatusResume, SquadStatusesResume},
+        Side,
+    },
+    order::Order,
+    physics::{
+        path::{find_path, Direction},
+        utils::distance_between_points,
+    },
+    state::battle::{
+        message::{BattleStateMessage, SoldierMessage},
+        phase::Phase,
+        BattleState,
+    },
+    types::{Angle, Distance, GridPoint, SquadUuid, WorldPath, WorldPaths, WorldPoint},
+};
+
+/// Computer player of one side. It gives orders to its squads leaders like a human player :
+/// attack not owned flags (or defend owned ones), engage visible opponents and retreat to
+/// cover when squad is damaged or side morale is low.
+pub struct Ai {
+    side: Side,
+}
+
+impl Ai {
+    pub fn new(side: Side) -> Self {
+        Self { side }
+    }
+
+    pub fn side(&self) -> &Side {
+        &self.side
+    }
+
+    /// Messages to apply on battle state to give AI orders
+    pub fn orders(
+        &self,
+        battle_state: &BattleState,
+        config: &ServerConfig,
+    ) -> Vec<BattleStateMessage> {
+        match battle_state.phase() {
+            Phase::Placement => self.placement(battle_state),
+            Phase::Battle => self.battle(battle_state, config),
+            Phase::End(_, _) => vec![],
+        }
+    }
+
+    /// AI keeps its deployment and is immediately ready
+    fn placement(&self, battle_state: &BattleState) -> Vec<BattleStateMessage> {
+        let (connected, set_connected, set_ready) = match self.side {
+            Side::A => (
+                battle_state.a_connected(),
+                BattleStateMessage::SetAConnected(true),
+                BattleStateMessage::SetAReady(true),
+            ),
+            Side::B => (
+                battle_state.b_connected(),
+                BattleStateMessage::SetBConnected(true),
+                BattleStateMessage::SetBReady(true),
+            ),
+            Side::All => return vec![],
+        };
+
+        let mut messages = vec![];
+        if !connected {
+            messages.push(set_connected);
+        }
+        if !battle_state.ready(&self.side) {
+            messages.push(set_ready);
+        }
+        messages
+    }
+
+    fn battle(&self, battle_state: &BattleState, config: &ServerConfig) -> Vec<BattleStateMessage> {
+        let opponents: Vec<&Soldier> = battle_state
+            .soldiers()
+            .iter()
+            .filter(|soldier| soldier.side() == &self.side.opposite())
+            .filter(|soldier| soldier.can_be_designed_as_target())
+            .filter(|soldier| battle_state.soldier_is_visible_by_side(soldier, &self.side))
+            .collect();
+
+        // Squads are stored in hash map, sort them to always produce orders in same order
+        let mut squads = SquadStatusesResume::from_battle_state(&self.side, battle_state)
+            .squads()
+            .to_vec();
+        squads.sort_by_key(|squad| squad.squad_id().0);
+
+        squads
+            .iter()
+            .filter_map(|squad| {
+                let leader = battle_state.squad(*squad.squad_id()).leader();
+                self.squad_order(battle_state, config, squad, &opponents)
+                    .map(|order| {
+                        BattleStateMessage::Soldier(leader, SoldierMessage::SetOrder(order))
+                    })
+            })
+            .collect()
+    }
+
+    /// New order for the squad, or None if its current order is still relevant
+    fn squad_order(
+        &self,
+        battle_state: &BattleState,
+        config: &ServerConfig,
+        squad: &SquadStatusResume,
+        opponents: &[&Soldier],
+    ) -> Option<Order> {
+        let leader = battle_state.soldier(battle_state.squad(*squad.squad_id()).leader());
+        if !leader.can_be_leader() {
+            return None;
+        }
+
+        let leader_point = leader.world_point();
+        let nearest_opponent = opponents.iter().min_by_key(|opponent| {
+            distance_between_points(&leader_point, &opponent.world_point()).millimeters()
+        });
+
+        if squad.health().0 < AI_RETREAT_HEALTH || self.morale(battle_state) < AI_RETREAT_MORALE {
+            return nearest_opponent.and_then(|opponent| {
+                self.retreat_order(battle_state, config, squad.squad_id(), leader, opponent)
+            });
+        }
+
+        if let Some(opponent) = nearest_opponent {
+            if distance_between_points(&leader_point, &opponent.world_point())
+                <= Distance::from_meters(AI_ENGAGE_DISTANCE_METERS)
+            {
+                return match leader.order() {
+                    Order::EngageSquad(_) | Order::SuppressFire(_) => None,
+                    _ => Some(Order::EngageSquad(opponent.squad_uuid())),
+                };
+            }
+        }
+
+        let objective = self.objective(battle_state, &leader_point)?;
+        if distance_between_points(&leader_point, &objective)
+            > Distance::from_meters(AI_OBJECTIVE_DISTANCE_METERS)
+        {
+            if move_destination(leader.order())
+                .map(|destination| {
+                    distance_between_points(&destination, &objective)
+                        <= Distance::from_meters(AI_OBJECTIVE_DISTANCE_METERS)
+                })
+                .unwrap_or(false)
+            {
+                return None;
+            }
+
+            let paths = self.paths(battle_state, squad.squad_id(), leader, &objective)?;
+            let then = Some(Box::new(Order::Defend(Angle::from_points(
+                &objective.to_vec2(),
+                &leader_point.to_vec2(),
+            ))));
+            // Be discreet when opponents are around
+            return Some(if nearest_opponent.is_some() {
+                Order::SneakTo(paths, then)
+            } else {
+                Order::MoveTo(paths, then)
+            });
+        }
+
+        match leader.order() {
+            Order::Idle => Some(Order::Defend(leader.get_looking_direction())),
+            _ => None,
+        }
+    }
+
+    /// Move to a covered place from opponent, then hide
+    fn retreat_order(
+        &self,
+        battle_state: &BattleState,
+        config: &ServerConfig,
+        squad_id: &SquadUuid,
+        leader: &Soldier,
+        opponent: &Soldier,
+    ) -> Option<Order> {
+        if matches!(leader.order(), Order::Hide(_))
+            || matches!(leader.order().then(), Some(Order::Hide(_)))
+        {
+            return None;
+        }
+
+        let angle = Angle::from_points(
+            &opponent.world_point().to_vec2(),
+            &leader.world_point().to_vec2(),
+        );
+        let hide = Order::Hide(angle);
+        let cover_point = CoverFinder::new(battle_state, config)
+            .find_better_cover_point_from_point(leader, &opponent.world_point(), false)
+            .map(|grid_point| battle_state.map().world_point_from_grid_point(grid_point));
+
+        match cover_point.and_then(|point| self.paths(battle_state, squad_id, leader, &point)) {
+            Some(paths) => Some(Order::MoveFastTo(paths, Some(Box::new(hide)))),
+            None => Some(hide),
+        }
+    }
+
+    /// Nearest flag not owned by AI side (to attack), or if all are owned, nearest flag (to
+    /// defend)
+    fn objective(&self, battle_state: &BattleState, from: &WorldPoint) -> Option<WorldPoint> {
+        let owned = match self.side {
+            Side::A => FlagOwnership::A,
+            Side::B => FlagOwnership::B,
+            Side::All => return None,
+        };
+        let flags = battle_state.map().flags();
+        let nearest = |points: Vec<WorldPoint>| {
+            points
+                .into_iter()
+                .min_by_key(|point| distance_between_points(from, point).millimeters())
+        };
+
+        let to_attack = flags
+            .iter()
+            .filter(|flag| {
+                battle_state
+                    .flags()
+                    .ownerships()
+                    .iter()
+                    .any(|(name, ownership)| name == flag.name() && ownership != &owned)
+            })
+            .map(|flag| flag.position())
+            .collect();
+
+        nearest(to_attack).or_else(|| nearest(flags.iter().map(|flag| flag.position()).collect()))
+    }
+
+    fn paths(
+        &self,
+        battle_state: &BattleState,
+        squad_id: &SquadUuid,
+        leader: &Soldier,
+        to: &WorldPoint,
+    ) -> Option<WorldPaths> {
+        let map = battle_state.map();
+        let (path_mode, start_direction) = battle_state.squad_path_mode_and_direction(*squad_id);
+        let start_direction =
+            start_direction.or(Some(Direction::from_angle(&leader.get_looking_direction())));
+        let from: GridPoint = map.grid_point_from_world_point(&leader.world_point());
+        let to: GridPoint = map.grid_point_from_world_point(to);
+
+        let points = find_path(map, &from, &to, true, &path_mode, &start_direction)?
+            .iter()
+            .map(|grid_point| map.world_point_from_grid_point(*grid_point))
+            .collect();
+        Some(WorldPaths::new(vec![WorldPath::new(points)]))
+    }
+
+    fn morale(&self, battle_state: &BattleState) -> f32 {
+        match self.side {
+            Side::A => battle_state.a_morale().0,
+            Side::B => battle_state.b_morale().0,
+            Side::All => 1.0,
+        }
+    }
+}
+
+/// Last point of the order move, if it is a move
+fn move_destination(order: &Order) -> Option<WorldPoint> {
+    match order {
+        Order::MoveTo(paths, _) | Order::MoveFastTo(paths, _) | Order::SneakTo(paths, _) => {
+            paths.paths.last().and_then(|path| path.last_point())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use battle_core::{
+        game::flag::{Flag, FlagName, FlagsOwnership},
+        physics::visibility::Visibility,
+        types::SoldierIndex,
+    };
+    use oc_core::morale::Morale;
+    use rstest::*;
+
+    use crate::runner::testing::map_with_flags;
+
+    use super::*;
+
+    /// Side A squad (leader first) of given points. Map has an underbrush band on rows 8 to 29
+    /// and flags "Near" at (80, 80) and "Far" at (150, 150).
+    fn battle_state(
+        points: Vec<WorldPoint>,
+        opponent: Option<WorldPoint>,
+        near: FlagOwnership,
+        far: FlagOwnership,
+    ) -> BattleState {
+        let map = map_with_flags(
+            |_, y| {
+                if (8..30).contains(&y) {
+                    "Underbrush"
+                } else {
+                    "ShortGrass"
+                }
+            },
+            vec![
+                Flag::new(FlagName("Near".to_string()), 75., 75., 10., 10.),
+                Flag::new(FlagName("Far".to_string()), 145., 145., 10., 10.),
+            ],
+        );
+        let mut soldiers: Vec<Soldier> = points
+            .into_iter()
+            .enumerate()
+            .map(|(i, point)| {
+                Soldier::new(SoldierIndex(i), point, SquadUuid(0), Side::A, None, vec![])
+            })
+            .collect();
+        if let Some(point) = opponent {
+            soldiers.push(Soldier::new(
+                SoldierIndex(soldiers.len()),
+                point,
+                SquadUuid(1),
+                Side::B,
+                None,
+                vec![],
+            ));
+        }
+        let mut battle_state = BattleState::new(
+            0,
+            map,
+            soldiers,
+            vec![],
+            HashMap::new(),
+            Phase::Battle,
+            FlagsOwnership::new(vec![
+                (FlagName("Near".to_string()), near),
+                (FlagName("Far".to_string()), far),
+            ]),
+        );
+        battle_state.resolve();
+        battle_state
+    }
+
+    /// Leader of squad sees the opponent
+    fn see(battle_state: &mut BattleState, opponent: SoldierIndex) {
+        let from = battle_state.soldier(SoldierIndex(0)).world_point();
+        let to = battle_state.soldier(opponent).world_point();
+        let visibility = Visibility {
+            from,
+            from_soldier: Some(SoldierIndex(0)),
+            to,
+            to_soldier: Some(opponent),
+            path_final_opacity: 0.,
+            to_scene_item_opacity: 0.,
+            opacity_segments: vec![],
+            visible: true,
+            distance: distance_between_points(&from, &to),
+            break_point: None,
+        };
+        battle_state.react(
+            &BattleStateMessage::SetVisibilities(HashMap::from([(
+                (SoldierIndex(0), opponent),
+                visibility,
+            )])),
+            0,
+        );
+    }
+
+    fn leader_order(battle_state: &BattleState) -> Option<Order> {
+        let ai = Ai::new(Side::A);
+        let squad = SquadStatusResume::from_squad(battle_state, &SquadUuid(0));
+        let opponents: Vec<&Soldier> = battle_state
+            .soldiers()
+            .iter()
+            .filter(|soldier| soldier.side() == &Side::B)
+            .filter(|soldier| battle_state.soldier_is_visible_by_side(soldier, &Side::A))
+            .collect();
+        ai.squad_order(battle_state, &ServerConfig::default(), &squad, &opponents)
+    }
+
+    #[rstest]
+    #[case(FlagOwnership::A, FlagOwnership::B, WorldPoint::new(150., 150.))]
+    #[case(FlagOwnership::A, FlagOwnership::Nobody, WorldPoint::new(150., 150.))]
+    #[case(FlagOwnership::Both, FlagOwnership::A, WorldPoint::new(80., 80.))]
+    #[case(FlagOwnership::A, FlagOwnership::A, WorldPoint::new(80., 80.))]
+    fn move_to_attack_or_defend_flag(
+        #[case] near: FlagOwnership,
+        #[case] far: FlagOwnership,
+        #[case] expected: WorldPoint,
+    ) {
+        // Given
+        let battle_state = battle_state(vec![WorldPoint::new(20., 20.)], None, near, far);
+        let map = battle_state.map();
+
+        // When
+        let order = leader_order(&battle_state);
+
+        // Then
+        let order = order.expect("Squad far from objective must be ordered");
+        assert!(matches!(order, Order::MoveTo(_, Some(_))));
+        assert!(matches!(order.then(), Some(Order::Defend(_))));
+        let destination = move_destination(&order).expect("Order is a move");
+        assert_eq!(
+            map.grid_point_from_world_point(&destination),
+            map.grid_point_from_world_point(&expected)
+        );
+    }
+
+    #[rstest]
+    fn defend_owned_flag_when_on_it() {
+        // Given
+        let battle_state = battle_state(
+            vec![WorldPoint::new(80., 80.)],
+            None,
+            FlagOwnership::A,
+            FlagOwnership::A,
+        );
+
+        // When
+        let order = leader_order(&battle_state);
+
+        // Then
+        assert!(matches!(order, Some(Order::Defend(_))));
+    }
+
+    #[rstest]
+    #[case(0, 1.0, false)]
+    #[case(1, 1.0, false)]
+    #[case(2, 1.0, true)]
+    #[case(0, 0.2, true)]
+    fn retreat_to_cover_when_damaged_or_demoralized(
+        #[case] deads: usize,
+        #[case] morale: f32,
+        #[case] expected: bool,
+    ) {
+        // Given
+        let points = vec![WorldPoint::new(100., 20.); 3];
+        let mut battle_state = battle_state(
+            points,
+            Some(WorldPoint::new(100., 180.)),
+            FlagOwnership::A,
+            FlagOwnership::B,
+        );
+        for i in 0..deads {
+            battle_state.react(
+                &BattleStateMessage::Soldier(SoldierIndex(2 - i), SoldierMessage::SetAlive(false)),
+                0,
+            );
+        }
+        battle_state.react(&BattleStateMessage::SetAMorale(Morale(morale)), 0);
+        see(&mut battle_state, SoldierIndex(3));
+        let config = ServerConfig::default();
+        let cover = CoverFinder::new(&battle_state, &config)
+            .find_better_cover_point_from_point(
+                battle_state.soldier(SoldierIndex(0)),
+                &WorldPoint::new(100., 180.),
+                false,
+            )
+            .expect("Underbrush band must offer cover");
+
+        // When
+        let order = leader_order(&battle_state).expect("Leader must be ordered");
+
+        // Then
+        if expected {
+            assert!(matches!(order, Order::MoveFastTo(_, Some(_))));
+            assert!(matches!(order.then(), Some(Order::Hide(_))));
+            let destination = move_destination(&order).expect("Order is a move");
+            assert_eq!(
+                battle_state.map().grid_point_from_world_point(&destination),
+                cover
+            );
+        } else {
+            assert!(matches!(order, Order::EngageSquad(SquadUuid(1))));
+        }
+    }
+}
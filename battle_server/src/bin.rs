@@ -1,22 +1,49 @@
 use crossbeam_channel::unbounded;
 use env_logger::Env;
+use signal_hook::consts::{SIGINT, SIGTERM};
+use std::io;
 use std::path::PathBuf;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use battle_core::config::ServerConfig;
+use battle_core::config::{ChangeConfigMessage, ServerConfig};
+use battle_core::deployment::{DeploymentReader, DeploymentReaderError};
+use battle_core::game::environment::EnvironmentChange;
+use battle_core::game::Side;
 use battle_core::network::error::NetworkError;
 use battle_core::network::server::Server;
-use battle_core::state::battle::builder::{BattleStateBuilder, BattleStateBuilderError};
+use battle_core::network::transport::tcp::TcpTransport;
+use battle_core::network::transport::zmq::ZmqTransport;
+use battle_core::network::transport::Transport;
+use battle_core::state::lobby::{LobbyMessage, LobbyState};
+use battle_core::types::Distance;
 use structopt::StructOpt;
 
-use battle_server::runner::{Runner, RunnerError};
+use battle_server::lobby::{Lobby, LobbyError};
 
 #[derive(StructOpt, Debug)]
 #[structopt(name = "basic")]
 pub struct Opt {
-    #[structopt()]
-    map_name: String,
+    /// Map proposed to clients in lobby
+    #[structopt(long = "map")]
+    map_name: Option<String>,
+
+    /// Deployment proposed to clients in lobby
+    #[structopt(long = "deployment", parse(from_os_str))]
+    deployment: Option<PathBuf>,
+
+    /// Propose to clients to run the battle themselves, server only relay their orders
+    #[structopt(long = "lockstep")]
+    lockstep: bool,
+
+    /// Propose to clients to let AI play this side (can be repeated)
+    #[structopt(long = "ai")]
+    ai: Vec<Side>,
+
+    /// Propose to clients weather and daylight, like `fog,dusk` or `300:rain,night` to
+    /// change it after 300 seconds (can be repeated)
+    #[structopt(long = "environment")]
+    environment: Vec<EnvironmentChange>,
 
     #[structopt(long = "rep-address")]
     rep_address: String,
@@ -24,6 +51,18 @@ pub struct Opt {
     #[structopt(long = "bind-address")]
     pub_address: String,
 
+    /// Bullets and explosions don't affect soldiers of the shooter side
+    #[structopt(long = "no-friendly-fire")]
+    no_friendly_fire: bool,
+
+    /// Soldiers can't see each other beyond this distance (in meters)
+    #[structopt(long = "visibility-maximum-distance")]
+    visibility_maximum_distance: Option<i64>,
+
+    /// Also accept clients with plain tcp transport on this address (like 0.0.0.0:4257)
+    #[structopt(long = "tcp-address")]
+    tcp_address: Option<String>,
+
     #[structopt(long = "profile")]
     profile: bool,
 
@@ -35,9 +74,12 @@ fn main() -> Result<(), Error> {
     env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
 
     let stop_required = Arc::new(AtomicBool::new(false));
+    // Interrupted server stops like a finished one (clients are informed of aborted battle)
+    for signal in [SIGINT, SIGTERM] {
+        signal_hook::flag::register(signal, stop_required.clone())?;
+    }
     let opt = Opt::from_args();
     let resources = PathBuf::from("./resources");
-    let map_name = &opt.map_name;
 
     let _puffin_server = if opt.profile {
         let puffin_server = puffin_http::Server::new(&opt.profile_address).unwrap();
@@ -49,52 +91,95 @@ fn main() -> Result<(), Error> {
 
     let (server_input_sender, server_input_receiver) = unbounded();
     let (server_output_sender, server_output_receiver) = unbounded();
+    let mut transports: Vec<Box<dyn Transport>> = vec![Box::new(ZmqTransport::new(
+        &opt.rep_address,
+        &opt.pub_address,
+    ))];
+    if let Some(tcp_address) = &opt.tcp_address {
+        transports.push(Box::new(TcpTransport::new(tcp_address)));
+    }
     let stop_required_ = stop_required.clone();
-    let server = Server::new(
-        opt.rep_address.clone(),
-        opt.pub_address.clone(),
+    let mut server = Server::new(
+        transports,
         server_output_receiver,
         server_input_sender,
         stop_required_,
     );
     server.serve()?;
 
+    let mut lobby_state = LobbyState::default();
+    if let Some(map_name) = &opt.map_name {
+        lobby_state.react(&LobbyMessage::SetMapName(map_name.clone()));
+    }
+    if let Some(deployment) = &opt.deployment {
+        let deployment = DeploymentReader::from_file(deployment)?;
+        lobby_state.react(&LobbyMessage::SetDeployment(deployment));
+    }
+    if opt.lockstep {
+        lobby_state.react(&LobbyMessage::SetLockstep(true));
+    }
+    for side in &opt.ai {
+        lobby_state.react(&LobbyMessage::SetAi(*side, true));
+    }
+    if !opt.environment.is_empty() {
+        lobby_state.react(&LobbyMessage::SetEnvironments(opt.environment.clone()));
+    }
+
     let stop_required_ = stop_required.clone();
-    let config = ServerConfig::default();
-    let battle_state = BattleStateBuilder::new(map_name, resources.clone()).build()?;
-    let mut runner = Runner::new(
+    let mut config = ServerConfig::default();
+    if opt.no_friendly_fire {
+        config.react(&ChangeConfigMessage::FriendlyFire(false));
+    }
+    if let Some(meters) = opt.visibility_maximum_distance {
+        config.react(&ChangeConfigMessage::VisibilityMaximumDistance(Some(
+            Distance::from_meters(meters),
+        )));
+    }
+    let mut lobby = Lobby::new(
         config,
+        &resources,
         server_input_receiver,
         server_output_sender,
         stop_required_,
-        battle_state,
-    );
+    )
+    .state(lobby_state);
+
+    let result = lobby.run();
 
-    runner.run()?;
+    stop_required.store(true, Ordering::Relaxed);
+    server.join();
+    result?;
     Ok(())
 }
 
 #[derive(Debug)]
 enum Error {
-    LoadBattle(BattleStateBuilderError),
+    LoadDeployment(DeploymentReaderError),
     Network(NetworkError),
-    Run(RunnerError),
+    Run(LobbyError),
+    Signal(io::Error),
 }
 
-impl From<RunnerError> for Error {
-    fn from(error: RunnerError) -> Self {
+impl From<LobbyError> for Error {
+    fn from(error: LobbyError) -> Self {
         Self::Run(error)
     }
 }
 
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::Signal(error)
+    }
+}
+
 impl From<NetworkError> for Error {
     fn from(error: NetworkError) -> Self {
         Self::Network(error)
     }
 }
 
-impl From<BattleStateBuilderError> for Error {
-    fn from(error: BattleStateBuilderError) -> Self {
-        Self::LoadBattle(error)
+impl From<DeploymentReaderError> for Error {
+    fn from(error: DeploymentReaderError) -> Self {
+        Self::LoadDeployment(error)
     }
 }
@@ -1 +1,3 @@
+pub mod ai;
+pub mod lobby;
 pub mod runner;
@@ -0,0 +1,257 @@
+use battle_core::{
+    config::ServerConfig,
+    game::Side,
+    message::{lockstep::LockstepMessage, InputMessage, OutputMessage},
+    state::{
+        battle::{
+            builder::{BattleStateBuilder, BattleStateBuilderError},
+            message::BattleStateMessage,
+            BattleState,
+        },
+        lobby::{BattleSetup, LobbyState},
+    },
+};
+use crossbeam_channel::{Receiver, RecvTimeoutError, SendError, Sender};
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{
+    ai::Ai,
+    runner::{Runner, RunnerError},
+};
+
+const LOBBY_POLL_DURATION_MS: u64 = 250;
+// Lockstep peer which sent nothing since this delay is considered as disconnected
+const RELAY_PEER_TIMEOUT_S: u64 = 60;
+
+/// Wait clients agreement on battle setup, then run the battle. When battle is finished,
+/// come back to lobby for next battle.
+pub struct Lobby {
+    config: ServerConfig,
+    resources: PathBuf,
+    input: Receiver<Vec<InputMessage>>,
+    output: Sender<Vec<OutputMessage>>,
+    stop_required: Arc<AtomicBool>,
+    state: LobbyState,
+}
+
+impl Lobby {
+    pub fn new(
+        config: ServerConfig,
+        resources: &Path,
+        input: Receiver<Vec<InputMessage>>,
+        output: Sender<Vec<OutputMessage>>,
+        stop_required: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            config,
+            resources: resources.to_path_buf(),
+            input,
+            output,
+            stop_required,
+            state: LobbyState::default(),
+        }
+    }
+
+    pub fn state(mut self, state: LobbyState) -> Self {
+        self.state = state;
+        self
+    }
+
+    pub fn run(&mut self) -> Result<(), LobbyError> {
+        println!("Lobby open");
+        loop {
+            if self.stop_required.load(Ordering::Relaxed) {
+                println!("Stopping lobby ...");
+                break;
+            }
+
+            let inputs = match self
+                .input
+                .recv_timeout(Duration::from_millis(LOBBY_POLL_DURATION_MS))
+            {
+                Ok(inputs) => inputs,
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => return Err(LobbyError::InputChannelClosed),
+            };
+            self.inputs(inputs)?;
+
+            if let Some(setup) = self.state.agreed() {
+                self.battle(setup)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn inputs(&mut self, inputs: Vec<InputMessage>) -> Result<(), LobbyError> {
+        for input in inputs {
+            match input {
+                InputMessage::Lobby(lobby_message) => {
+                    log::debug!("Lobby message : {:?}", lobby_message);
+                    self.state.react(&lobby_message);
+                }
+                // Lobby state is sent after each inputs
+                InputMessage::RequireCompleteSync => {}
+                _ => log::debug!("Ignore message outside battle : {:?}", input),
+            }
+        }
+
+        self.output
+            .send(vec![OutputMessage::Lobby(self.state.clone())])?;
+        Ok(())
+    }
+
+    fn battle(&mut self, mut setup: BattleSetup) -> Result<(), LobbyError> {
+        setup.set_seed(rand::random());
+        if setup.lockstep() {
+            println!("Start lockstep battle on map {}", setup.map_name());
+            self.output
+                .send(vec![OutputMessage::BattleSetup(setup.clone())])?;
+            self.relay(&setup)?;
+        } else {
+            let battle_state = match build_battle_state(&setup, &self.resources) {
+                Ok(battle_state) => battle_state,
+                Err(error) => {
+                    println!("Unable to build battle state : {}", error);
+                    self.state.set_error(error.to_string());
+                    self.output
+                        .send(vec![OutputMessage::Lobby(self.state.clone())])?;
+                    return Ok(());
+                }
+            };
+
+            println!("Start battle on map {}", setup.map_name());
+            self.output
+                .send(vec![OutputMessage::BattleSetup(setup.clone())])?;
+            let runner = Runner::new(
+                self.config.clone(),
+                self.input.clone(),
+                self.output.clone(),
+                self.stop_required.clone(),
+                battle_state,
+            )
+            .seed(setup.seed())
+            .environments(setup.environments().clone());
+            setup
+                .ais()
+                .iter()
+                .fold(runner, |runner, side| runner.ai(Ai::new(*side)))
+                .run()?;
+        }
+
+        self.state = self.state.after_battle();
+        self.output
+            .send(vec![OutputMessage::Lobby(self.state.clone())])?;
+        Ok(())
+    }
+
+    /// In lockstep mode, clients run the battle : only relay their lockstep messages until
+    /// each of them finished or stopped to send messages (disconnected).
+    fn relay(&mut self, setup: &BattleSetup) -> Result<(), LobbyError> {
+        let mut finished: Vec<Side> = vec![];
+        let mut last_seen: Vec<(Side, Instant)> = setup
+            .sides()
+            .iter()
+            .map(|side| (*side, Instant::now()))
+            .collect();
+        while !setup.sides().iter().all(|side| finished.contains(side)) {
+            if self.stop_required.load(Ordering::Relaxed) {
+                println!("Stopping lockstep relay ...");
+                break;
+            }
+
+            for (side, seen) in &last_seen {
+                if !finished.contains(side)
+                    && seen.elapsed() > Duration::from_secs(RELAY_PEER_TIMEOUT_S)
+                {
+                    println!("Lockstep peer {} timed out, consider it as finished", side);
+                    finished.push(*side);
+                }
+            }
+
+            let inputs = match self
+                .input
+                .recv_timeout(Duration::from_millis(LOBBY_POLL_DURATION_MS))
+            {
+                Ok(inputs) => inputs,
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => return Err(LobbyError::InputChannelClosed),
+            };
+
+            let mut outputs = vec![];
+            for input in inputs {
+                match input {
+                    InputMessage::Lockstep(message) => {
+                        if let Some(sender) = message.side() {
+                            last_seen
+                                .iter_mut()
+                                .filter(|(side, _)| side == sender)
+                                .for_each(|(_, seen)| *seen = Instant::now());
+                        }
+                        if let LockstepMessage::Finished(side) = &message {
+                            finished.push(*side);
+                        }
+                        outputs.push(OutputMessage::Lockstep(message));
+                    }
+                    _ => log::debug!("Ignore message during lockstep battle : {:?}", input),
+                }
+            }
+
+            if !outputs.is_empty() {
+                self.output.send(outputs)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Build the battle state described by the setup
+pub fn build_battle_state(
+    setup: &BattleSetup,
+    resources: &Path,
+) -> Result<BattleState, BattleStateBuilderError> {
+    let mut battle_state =
+        BattleStateBuilder::new(setup.map_name(), resources.to_path_buf()).build()?;
+    battle_state.inject(setup.deployment());
+    battle_state.update_flags_from_control(setup.a_control().clone(), setup.b_control().clone());
+    battle_state.react(&BattleStateMessage::SetEnvironment(setup.environment()), 0);
+    Ok(battle_state)
+}
+
+#[derive(Debug)]
+pub enum LobbyError {
+    InputChannelClosed,
+    Output(SendError<Vec<OutputMessage>>),
+    Run(RunnerError),
+}
+
+impl From<SendError<Vec<OutputMessage>>> for LobbyError {
+    fn from(error: SendError<Vec<OutputMessage>>) -> Self {
+        Self::Output(error)
+    }
+}
+
+impl From<RunnerError> for LobbyError {
+    fn from(error: RunnerError) -> Self {
+        Self::Run(error)
+    }
+}
+
+impl Display for LobbyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LobbyError::InputChannelClosed => f.write_str("Input channel closed"),
+            LobbyError::Output(error) => f.write_str(&format!("Output error : {}", error)),
+            LobbyError::Run(error) => f.write_str(&format!("Run error : {}", error)),
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use super::{message::RunnerMessage, Runner};
+
+impl Runner {
+    pub fn tick_ais(&self) -> Vec<RunnerMessage> {
+        puffin::profile_scope!("tick_ais");
+        if self.battle_state.frame_i() % self.config.ai_update_freq() != 0 {
+            return vec![];
+        }
+
+        self.ais
+            .iter()
+            .flat_map(|ai| ai.orders(&self.battle_state, &self.config))
+            .map(RunnerMessage::BattleState)
+            .collect()
+    }
+}
@@ -0,0 +1,140 @@
+use battle_core::{
+    order::Order,
+    state::battle::message::{BattleStateMessage, SoldierMessage},
+    types::{Angle, SquadUuid, WorldPoint},
+};
+
+use super::{message::RunnerMessage, Runner};
+
+impl Runner {
+    /// Ambushing squads open fire all together when an opponent triggers their ambush. Hiding
+    /// squads switch to defend, facing the opponent, to be able to fire.
+    pub fn tick_ambushes(&self) -> Vec<RunnerMessage> {
+        puffin::profile_scope!("tick_ambushes");
+        if self.battle_state.frame_i() % self.config.ambush_update_freq() != 0 {
+            return vec![];
+        }
+
+        let mut squads: Vec<SquadUuid> = self.battle_state.squads().keys().copied().collect();
+        squads.sort_by_key(|squad_uuid| squad_uuid.0);
+
+        let mut messages = vec![];
+        for squad_uuid in squads {
+            if let Some(opponent_point) = self.ambush_trigger_point(&squad_uuid) {
+                messages.push(RunnerMessage::BattleState(
+                    BattleStateMessage::SetSquadAmbush(squad_uuid, None),
+                ));
+
+                let leader = self
+                    .battle_state
+                    .soldier(self.battle_state.squad(squad_uuid).leader());
+                if let Order::Hide(_) = leader.order() {
+                    let angle = Angle::from_points(
+                        &opponent_point.to_vec2(),
+                        &leader.world_point().to_vec2(),
+                    );
+                    messages.push(RunnerMessage::BattleState(BattleStateMessage::Soldier(
+                        leader.uuid(),
+                        SoldierMessage::SetOrder(Order::Defend(angle)),
+                    )));
+                }
+            }
+        }
+
+        messages
+    }
+
+    /// Point of the opponent who triggers squad ambush, if any
+    fn ambush_trigger_point(&self, squad_uuid: &SquadUuid) -> Option<WorldPoint> {
+        let trigger = self.battle_state.squad_ambush(squad_uuid)?;
+        let squad = self.battle_state.squad(*squad_uuid);
+        let side = self.battle_state.soldier(squad.leader()).side();
+        let members: Vec<WorldPoint> = squad
+            .members()
+            .iter()
+            .map(|soldier_index| self.battle_state.soldier(*soldier_index))
+            .filter(|soldier| soldier.can_be_animated())
+            .map(|soldier| soldier.world_point())
+            .collect();
+
+        self.battle_state
+            .soldiers()
+            .iter()
+            .filter(|soldier| soldier.side() == &side.opposite())
+            .filter(|soldier| soldier.can_be_designed_as_target())
+            .filter(|soldier| self.battle_state.soldier_is_visible_by_side(soldier, side))
+            .map(|soldier| soldier.world_point())
+            .find(|point| trigger.triggered_by(point, &members))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use battle_core::{
+        entity::soldier::Soldier,
+        game::{ambush::AmbushTrigger, Side},
+        types::SoldierIndex,
+    };
+    use rstest::*;
+
+    use crate::runner::{message::RunnerMessage, testing::runner};
+
+    use super::*;
+
+    #[rstest]
+    #[case(WorldPoint::new(150., 50.), true)]
+    #[case(WorldPoint::new(150., 150.), false)]
+    fn hiding_ambush_switch_to_defend_when_triggered(
+        #[case] opponent_point: WorldPoint,
+        #[case] triggered: bool,
+    ) {
+        // Given a hiding squad in ambush with a kill zone at east
+        let mut runner = runner(vec![
+            Soldier::new(
+                SoldierIndex(0),
+                WorldPoint::new(50., 50.),
+                SquadUuid(0),
+                Side::A,
+                None,
+                vec![],
+            ),
+            Soldier::new(
+                SoldierIndex(1),
+                opponent_point,
+                SquadUuid(1),
+                Side::B,
+                None,
+                vec![],
+            ),
+        ]);
+        runner.react(&vec![
+            RunnerMessage::BattleState(BattleStateMessage::Soldier(
+                SoldierIndex(0),
+                SoldierMessage::SetOrder(Order::Hide(Angle(0.))),
+            )),
+            RunnerMessage::BattleState(BattleStateMessage::SetSquadAmbush(
+                SquadUuid(0),
+                Some(AmbushTrigger::kill_zone(
+                    WorldPoint::new(100., 0.),
+                    WorldPoint::new(200., 100.),
+                )),
+            )),
+        ]);
+        let visibilities = runner.update_visibilities();
+        runner.react(&visibilities);
+
+        // When
+        let messages = runner.tick_ambushes();
+        runner.react(&messages);
+
+        // Then
+        let order = runner.battle_state.soldier(SoldierIndex(0)).order();
+        if triggered {
+            assert!(runner.battle_state.squad_ambush(&SquadUuid(0)).is_none());
+            assert!(matches!(order, Order::Defend(_)), "{:?}", order);
+        } else {
+            assert!(runner.battle_state.squad_ambush(&SquadUuid(0)).is_some());
+            assert!(matches!(order, Order::Hide(_)), "{:?}", order);
+        }
+    }
+}
@@ -19,13 +19,15 @@ impl Runner {
         let leader = self.battle_state.soldier(squad.leader());
         let mut orders = vec![];
 
-        // In case of hide and enemy in perimeter, switch to defend
+        // In case of hide and enemy in perimeter, switch to defend (except in ambush)
         if let Behavior::Hide(angle) = behavior {
-            if self.visible_soldier_in_circle(
-                &leader.world_point(),
-                &self.config.hide_maximum_rayon,
-                &leader.side().opposite(),
-            ) {
+            if self.battle_state.squad_ambush(&squad_uuid).is_none()
+                && self.visible_soldier_in_circle(
+                    &leader.world_point(),
+                    &self.config.hide_maximum_rayon,
+                    &leader.side().opposite(),
+                )
+            {
                 return (vec![(leader, Order::Defend(*angle))], vec![]);
             }
         }
@@ -3,12 +3,29 @@ use battle_core::{
     game::cover::CoverFinder,
     order::Order,
     physics::path::{find_path, Direction, PathMode},
-    types::{SoldierIndex, SquadUuid, WorldPath, WorldPaths},
+    types::{SoldierIndex, SquadUuid, VehicleIndex, WorldPath, WorldPaths},
 };
 
-use crate::runner::{fight::choose::ChooseMethod, Runner};
+use crate::runner::Runner;
 
 impl Runner {
+    /// Squad engages the squad of the vehicle crew
+    pub fn propagate_engage_vehicle(
+        &self,
+        squad_uuid: &SquadUuid,
+        engaged_vehicle_index: &VehicleIndex,
+    ) -> Vec<(&Soldier, Order)> {
+        match self
+            .battle_state
+            .vehicle_board()
+            .get(engaged_vehicle_index)
+            .and_then(|board| board.first())
+        {
+            Some((_, crew_index)) => self.propagate_engage_soldier(squad_uuid, crew_index),
+            None => vec![],
+        }
+    }
+
     pub fn propagate_engage_soldier(
         &self,
         squad_uuid: &SquadUuid,
@@ -28,6 +45,7 @@ impl Runner {
             .subordinates()
             .iter()
             .map(|i| self.battle_state.soldier(**i))
+            .filter(|member| !self.sniper_is_relocating(member))
         {
             let member_grid_point = self
                 .battle_state
@@ -37,7 +55,7 @@ impl Runner {
                 .soldier_find_opponent_to_target(
                     member,
                     Some(&engaged_squad_index),
-                    &ChooseMethod::RandomFromNearest,
+                    &self.soldier_choose_method(member),
                 )
                 .is_some()
             {
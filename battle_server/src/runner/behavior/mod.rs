@@ -10,7 +10,7 @@ use battle_core::{
     utils::NewDebugPoint,
 };
 
-use super::{fight::choose::ChooseMethod, message::RunnerMessage, Runner};
+use super::{message::RunnerMessage, Runner};
 
 mod blast;
 mod bullet;
@@ -18,6 +18,7 @@ mod death;
 mod defend;
 mod engage;
 mod moves;
+mod smoke;
 mod suppress;
 
 impl Runner {
@@ -35,6 +36,7 @@ impl Runner {
             Order::Hide(angle) => self.hide_behavior(soldier, angle),
             Order::EngageSquad(squad_index) => self.engage_behavior(soldier, squad_index),
             Order::SuppressFire(point) => self.suppress_fire_behavior(soldier, point),
+            Order::SmokeScreen(point) => self.smoke_screen_behavior(soldier, point),
         };
 
         // In case of squad leader and regularly propagation
@@ -108,6 +110,10 @@ impl Runner {
             Behavior::EngageSoldier(soldier_index) => {
                 self.propagate_engage_soldier(&leader.squad_uuid(), soldier_index)
             }
+            Behavior::EngageVehicle(vehicle_index) => {
+                self.propagate_engage_vehicle(&leader.squad_uuid(), vehicle_index)
+            }
+            Behavior::ThrowSmoke(point) => self.propagate_smoke_screen(leader.squad_uuid(), point),
         };
 
         for (subordinate, order) in orders {
@@ -132,9 +138,15 @@ impl Runner {
     }
 
     pub fn idle_behavior(&self, soldier: &Soldier) -> Behavior {
-        if let Some(opponent) =
-            self.soldier_find_opponent_to_target(soldier, None, &ChooseMethod::RandomFromNearest)
-        {
+        if let Some(vehicle) = self.soldier_find_vehicle_to_target(soldier, None) {
+            return Behavior::EngageVehicle(*vehicle.uuid());
+        }
+
+        if let Some(opponent) = self.soldier_find_opponent_to_target(
+            soldier,
+            None,
+            &self.soldier_choose_method(soldier),
+        ) {
             return Behavior::EngageSoldier(opponent.uuid());
         }
 
@@ -147,9 +159,15 @@ impl Runner {
     }
 
     pub fn move_behavior(&self, soldier: &Soldier, paths: &WorldPaths) -> Behavior {
-        if let Some(opponent) =
-            self.soldier_find_opponent_to_target(soldier, None, &ChooseMethod::RandomFromNearest)
-        {
+        if let Some(vehicle) = self.soldier_find_vehicle_to_target(soldier, None) {
+            return Behavior::EngageVehicle(*vehicle.uuid());
+        }
+
+        if let Some(opponent) = self.soldier_find_opponent_to_target(
+            soldier,
+            None,
+            &self.soldier_choose_method(soldier),
+        ) {
             return Behavior::EngageSoldier(opponent.uuid());
         }
 
@@ -183,10 +201,12 @@ impl Runner {
     pub fn defend_behavior(&self, soldier: &Soldier, angle: &Angle) -> Behavior {
         match self.battle_state.soldier_behavior_mode(soldier) {
             BehaviorMode::Ground => {
-                if let Some(opponent) = self.soldier_find_opponent_to_target(
+                if let Some(vehicle) = self.soldier_find_vehicle_to_target(soldier, None) {
+                    Behavior::EngageVehicle(*vehicle.uuid())
+                } else if let Some(opponent) = self.soldier_find_opponent_to_target(
                     soldier,
                     None,
-                    &ChooseMethod::RandomFromNearest,
+                    &self.soldier_choose_method(soldier),
                 ) {
                     Behavior::EngageSoldier(opponent.uuid())
                 } else {
@@ -216,10 +236,12 @@ impl Runner {
     pub fn hide_behavior(&self, soldier: &Soldier, angle: &Angle) -> Behavior {
         match self.battle_state.soldier_behavior_mode(soldier) {
             BehaviorMode::Ground => {
-                if let Some(opponent) = self.soldier_find_opponent_to_target(
+                if let Some(vehicle) = self.soldier_find_vehicle_to_target(soldier, None) {
+                    Behavior::EngageVehicle(*vehicle.uuid())
+                } else if let Some(opponent) = self.soldier_find_opponent_to_target(
                     soldier,
                     None,
-                    &ChooseMethod::RandomFromNearest,
+                    &self.soldier_choose_method(soldier),
                 ) {
                     Behavior::EngageSoldier(opponent.uuid())
                 } else {
@@ -246,16 +268,24 @@ impl Runner {
     }
 
     pub fn engage_behavior(&self, soldier: &Soldier, squad_index: &SquadUuid) -> Behavior {
+        if let Some(vehicle) = self.soldier_find_vehicle_to_target(soldier, Some(squad_index)) {
+            return Behavior::EngageVehicle(*vehicle.uuid());
+        }
+
         let opponent = soldier
             .behavior()
             .opponent()
             .map(|s| self.battle_state.soldier(*s))
-            .filter(|s| s.can_be_designed_as_target())
+            .filter(|s| {
+                s.can_be_designed_as_target()
+                    && self.soldier_can_target(soldier, s)
+                    && !self.friends_on_line_of_fire(soldier, &s.world_point())
+            })
             .or_else(|| {
                 self.soldier_find_opponent_to_target(
                     soldier,
                     Some(squad_index),
-                    &ChooseMethod::RandomFromNearest,
+                    &self.soldier_choose_method(soldier),
                 )
             });
 
@@ -269,4 +299,8 @@ impl Runner {
     pub fn suppress_fire_behavior(&self, _soldier: &Soldier, point: &WorldPoint) -> Behavior {
         Behavior::SuppressFire(*point)
     }
+
+    pub fn smoke_screen_behavior(&self, _soldier: &Soldier, point: &WorldPoint) -> Behavior {
+        Behavior::ThrowSmoke(*point)
+    }
 }
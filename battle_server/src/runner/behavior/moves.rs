@@ -1,7 +1,7 @@
 use battle_core::{
     behavior::Behavior,
     entity::{soldier::Soldier, vehicle::OnBoardPlace},
-    game::squad::{squad_positions, Formation},
+    game::cover::CoverFinder,
     order::Order,
     physics::path::{find_path, PathMode},
     types::{SquadUuid, WorldPath, WorldPaths},
@@ -19,7 +19,27 @@ impl Runner {
         let squad = self.battle_state.squad(squad_uuid);
         let leader = self.battle_state.soldier(squad.leader());
 
-        for (soldier_index, point) in squad_positions(squad, Formation::Line, leader, None) {
+        // Members keep their current destination while it still fits the formation
+        let keep_points = squad
+            .subordinates()
+            .iter()
+            .filter_map(
+                |soldier_index| match self.battle_state.soldier(**soldier_index).order() {
+                    Order::MoveTo(paths, _)
+                    | Order::MoveFastTo(paths, _)
+                    | Order::SneakTo(paths, _) => {
+                        paths.last_point().map(|point| (**soldier_index, point))
+                    }
+                    _ => None,
+                },
+            )
+            .collect();
+
+        // Members follow leader in squad formation, adapted to close covers
+        for (soldier_index, point) in CoverFinder::new(&self.battle_state, &self.config)
+            .keep_points(keep_points)
+            .find_formation_points(squad, leader)
+        {
             let soldier = self.battle_state.soldier(soldier_index);
             let map = self.battle_state.map();
             if let Some(grid_path) = find_path(
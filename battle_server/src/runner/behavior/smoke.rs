@@ -0,0 +1,31 @@
+use battle_core::{
+    entity::soldier::Soldier,
+    order::Order,
+    types::{SquadUuid, WorldPoint},
+};
+
+use crate::runner::Runner;
+
+impl Runner {
+    pub fn propagate_smoke_screen(
+        &self,
+        squad_uuid: SquadUuid,
+        point: &WorldPoint,
+    ) -> Vec<(&Soldier, Order)> {
+        let mut orders = vec![];
+
+        // Only soldiers still carrying smoke grenades participate to the smoke screen
+        for member in self
+            .battle_state
+            .squad(squad_uuid)
+            .subordinates()
+            .iter()
+            .map(|i| self.battle_state.soldier(**i))
+            .filter(|s| s.smoke_grenades() > 0)
+        {
+            orders.push((member, Order::SmokeScreen(*point)));
+        }
+
+        orders
+    }
+}
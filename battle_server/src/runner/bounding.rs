@@ -0,0 +1,158 @@
+use battle_core::{
+    config::{BOUNDING_OVERWATCH_ARRIVAL_METERS, BOUNDING_OVERWATCH_BOUND_METERS, COVER_DISTANCE},
+    entity::soldier::Soldier,
+    game::bounding::BoundingOverwatch,
+    map::{find_arbitrary_cover_grid_points, terrain::TileType},
+    order::Order,
+    physics::{
+        path::{find_path, Direction},
+        utils::distance_between_points,
+    },
+    state::battle::message::{BattleStateMessage, SoldierMessage},
+    types::{Angle, Distance, SquadUuid, WorldPath, WorldPaths, WorldPoint},
+};
+
+use super::{message::RunnerMessage, Runner};
+
+impl Runner {
+    /// When moving squad finished its bound, it covers the other squad which bounds in turn
+    pub fn tick_bounding_overwatches(&self) -> Vec<RunnerMessage> {
+        puffin::profile_scope!("tick_bounding_overwatches");
+        if !self.battle_state.phase().is_battle()
+            || self.battle_state.frame_i() % self.config.bounding_overwatch_update_freq() != 0
+        {
+            return vec![];
+        }
+
+        self.battle_state
+            .bounding_overwatches()
+            .iter()
+            .flat_map(|bounding_overwatch| self.bounding_overwatch_messages(bounding_overwatch))
+            .map(RunnerMessage::BattleState)
+            .collect()
+    }
+
+    fn bounding_overwatch_messages(
+        &self,
+        bounding_overwatch: &BoundingOverwatch,
+    ) -> Vec<BattleStateMessage> {
+        let moving_squad = *bounding_overwatch.moving();
+        let moving_leader = self.squad_leader(moving_squad);
+        let covering_leader = self.squad_leader(*bounding_overwatch.covering());
+        if !moving_leader.can_be_leader() || !covering_leader.can_be_leader() {
+            return vec![BattleStateMessage::RemoveBoundingOverwatch(moving_squad)];
+        }
+
+        // Bound is in progress
+        if matches!(
+            moving_leader.order(),
+            Order::MoveTo(_, _) | Order::MoveFastTo(_, _) | Order::SneakTo(_, _)
+        ) {
+            return vec![];
+        }
+
+        let destination = bounding_overwatch.destination();
+        let arrival = Distance::from_meters(BOUNDING_OVERWATCH_ARRIVAL_METERS);
+        if distance_between_points(&moving_leader.world_point(), destination) <= arrival
+            && distance_between_points(&covering_leader.world_point(), destination) <= arrival
+        {
+            return vec![BattleStateMessage::RemoveBoundingOverwatch(moving_squad)];
+        }
+
+        let bound_order = match self.bound_order(bounding_overwatch, covering_leader) {
+            Some(order) => order,
+            None => return vec![BattleStateMessage::RemoveBoundingOverwatch(moving_squad)],
+        };
+
+        vec![
+            BattleStateMessage::Soldier(
+                covering_leader.uuid(),
+                SoldierMessage::SetOrder(bound_order),
+            ),
+            BattleStateMessage::Soldier(
+                moving_leader.uuid(),
+                SoldierMessage::SetOrder(self.cover_order(destination, moving_leader)),
+            ),
+            BattleStateMessage::SwapBoundingOverwatch(moving_squad),
+        ]
+    }
+
+    fn squad_leader(&self, squad_uuid: SquadUuid) -> &Soldier {
+        self.battle_state
+            .soldier(self.battle_state.squad(squad_uuid).leader())
+    }
+
+    /// Rush to the most covered place close to the next bound point, then defend
+    fn bound_order(
+        &self,
+        bounding_overwatch: &BoundingOverwatch,
+        leader: &Soldier,
+    ) -> Option<Order> {
+        let map = self.battle_state.map();
+        let destination = bounding_overwatch.destination();
+        let bound_point = bounding_overwatch.next_bound_point(
+            &leader.world_point(),
+            &Distance::from_meters(BOUNDING_OVERWATCH_BOUND_METERS),
+        );
+        let bound_grid_point = map.grid_point_from_world_point(&bound_point);
+        // Last bound ends exactly on destination
+        let to = if &bound_point == destination {
+            bound_grid_point
+        } else {
+            find_arbitrary_cover_grid_points(&self.config, &bound_grid_point, map, COVER_DISTANCE)
+                .into_iter()
+                .rev()
+                .find(|(grid_point, tile)| {
+                    !matches!(tile.type_, TileType::BrickWall | TileType::DeepWater)
+                        && !map.closed_wall(grid_point)
+                })
+                .map(|(grid_point, _)| grid_point)
+                .unwrap_or(bound_grid_point)
+        };
+
+        let (path_mode, start_direction) = self
+            .battle_state
+            .squad_path_mode_and_direction(leader.squad_uuid());
+        let start_direction =
+            start_direction.or(Some(Direction::from_angle(&leader.get_looking_direction())));
+        let from = map.grid_point_from_world_point(&leader.world_point());
+        let points = find_path(map, &from, &to, true, &path_mode, &start_direction)
+            .filter(|grid_points| !grid_points.is_empty())?
+            .iter()
+            .map(|grid_point| map.world_point_from_grid_point(*grid_point))
+            .collect();
+
+        let then = Order::Defend(Angle::from_points(
+            &destination.to_vec2(),
+            &leader.world_point().to_vec2(),
+        ));
+        Some(Order::MoveFastTo(
+            WorldPaths::new(vec![WorldPath::new(points)]),
+            Some(Box::new(then)),
+        ))
+    }
+
+    /// Engage nearest visible opponent, or watch toward destination
+    fn cover_order(&self, destination: &WorldPoint, leader: &Soldier) -> Order {
+        let leader_point = leader.world_point();
+        self.battle_state
+            .soldiers()
+            .iter()
+            .filter(|soldier| soldier.side() == &leader.side().opposite())
+            .filter(|soldier| soldier.can_be_designed_as_target())
+            .filter(|soldier| {
+                self.battle_state
+                    .soldier_is_visible_by_side(soldier, leader.side())
+            })
+            .min_by_key(|soldier| {
+                distance_between_points(&leader_point, &soldier.world_point()).millimeters()
+            })
+            .map(|opponent| Order::EngageSquad(opponent.squad_uuid()))
+            .unwrap_or_else(|| {
+                Order::Defend(Angle::from_points(
+                    &destination.to_vec2(),
+                    &leader_point.to_vec2(),
+                ))
+            })
+    }
+}
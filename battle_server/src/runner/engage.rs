@@ -1,6 +1,6 @@
 use battle_core::{
     state::battle::message::{BattleStateMessage, SoldierMessage},
-    types::SoldierIndex,
+    types::{SoldierIndex, VehicleIndex},
     utils::angle,
 };
 
@@ -20,4 +20,18 @@ impl Runner {
             SoldierMessage::SetOrientation(angle),
         ))]
     }
+
+    pub fn engage_vehicle_update(
+        &self,
+        soldier_index: &SoldierIndex,
+        vehicle_index: &VehicleIndex,
+    ) -> Vec<RunnerMessage> {
+        let soldier = self.battle_state.soldier(*soldier_index);
+        let vehicle = self.battle_state.vehicle(*vehicle_index);
+        let angle = angle(&vehicle.world_point(), &soldier.world_point());
+        vec![RunnerMessage::BattleState(BattleStateMessage::Soldier(
+            *soldier_index,
+            SoldierMessage::SetOrientation(angle),
+        ))]
+    }
 }
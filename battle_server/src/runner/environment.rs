@@ -0,0 +1,20 @@
+use battle_core::{config::TARGET_FPS, state::battle::message::BattleStateMessage};
+
+use super::{message::RunnerMessage, Runner};
+
+impl Runner {
+    /// Scripted weather and daylight changes. Initial environment is set at battle state
+    /// creation.
+    pub fn tick_environment(&self) -> Vec<RunnerMessage> {
+        let frame_i = *self.battle_state.frame_i();
+        self.environments
+            .iter()
+            .filter(|change| change.start() > 0 && change.start() * TARGET_FPS == frame_i)
+            .map(|change| {
+                RunnerMessage::BattleState(BattleStateMessage::SetEnvironment(
+                    *change.environment(),
+                ))
+            })
+            .collect()
+    }
+}
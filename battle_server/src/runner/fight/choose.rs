@@ -1,10 +1,15 @@
-use rand::seq::SliceRandom;
+use rand::{seq::SliceRandom, Rng};
 
 use battle_core::{
-    entity::soldier::Soldier,
-    physics::{utils::distance_between_points, visibility::Visibility},
+    config::{FRIENDLY_LINE_OF_FIRE_MILLIMETERS, TARGET_POLICY_RECENT_SHOOT_FRAMES},
+    entity::{soldier::Soldier, vehicle::Vehicle},
+    game::target::TargetPolicy,
+    physics::{
+        utils::{distance_between_points, nearest_point_on_segment, projection_ratio},
+        visibility::Visibility,
+    },
     state::battle::BattleState,
-    types::{Distance, SoldierIndex, SquadUuid},
+    types::{Distance, SoldierIndex, SquadUuid, WorldPoint},
 };
 
 use crate::runner::Runner;
@@ -13,15 +18,124 @@ pub const NEAR_SOLDIERS_DISTANCE_METERS: i64 = 7;
 
 pub enum ChooseMethod {
     RandomFromNearest,
+    MostThreatening,
+    WeakestCover,
+    VehiclesFirst,
+    LeadersFirst,
+    OfficersAndCrews,
 }
+
+impl From<TargetPolicy> for ChooseMethod {
+    fn from(policy: TargetPolicy) -> Self {
+        match policy {
+            TargetPolicy::Nearest => Self::RandomFromNearest,
+            TargetPolicy::MostThreatening => Self::MostThreatening,
+            TargetPolicy::WeakestCover => Self::WeakestCover,
+            TargetPolicy::VehiclesFirst => Self::VehiclesFirst,
+            TargetPolicy::LeadersFirst => Self::LeadersFirst,
+            TargetPolicy::OfficersAndCrews => Self::OfficersAndCrews,
+        }
+    }
+}
+
 impl ChooseMethod {
     fn choose(
         &self,
         battle_state: &BattleState,
+        soldier: &Soldier,
+        visibles: Vec<&Visibility>,
+        rng: &mut impl Rng,
+    ) -> Option<SoldierIndex> {
+        match self {
+            Self::RandomFromNearest => self.choose_random_from_nearest(battle_state, visibles, rng),
+            Self::MostThreatening
+            | Self::WeakestCover
+            | Self::VehiclesFirst
+            | Self::LeadersFirst
+            | Self::OfficersAndCrews => {
+                self.choose_by_priority(battle_state, soldier, visibles, rng)
+            }
+        }
+    }
+
+    /// Choose the nearest opponent with the highest priority, or fallback on nearest ones if
+    /// none of them is a priority
+    fn choose_by_priority(
+        &self,
+        battle_state: &BattleState,
+        soldier: &Soldier,
         visibles: Vec<&Visibility>,
+        rng: &mut impl Rng,
     ) -> Option<SoldierIndex> {
+        let mut best: Option<(u32, &Visibility)> = None;
+        for visibility in &visibles {
+            let priority = self.priority(battle_state, soldier, visibility);
+            if priority > 0 && best.map(|(best_, _)| priority > best_).unwrap_or(true) {
+                best = Some((priority, visibility));
+            }
+        }
+
+        if let Some((_, visibility)) = best {
+            return visibility.to_soldier;
+        }
+
+        self.choose_random_from_nearest(battle_state, visibles, rng)
+    }
+
+    fn priority(
+        &self,
+        battle_state: &BattleState,
+        soldier: &Soldier,
+        visibility: &Visibility,
+    ) -> u32 {
+        let opponent = battle_state.soldier(
+            visibility
+                .to_soldier
+                .expect("visibles_soldiers_by must returned with to_soldier"),
+        );
         match self {
-            Self::RandomFromNearest => self.choose_random_from_nearest(battle_state, visibles),
+            Self::RandomFromNearest => 0,
+            Self::MostThreatening => {
+                let mut priority = 0;
+                if let Some(target) = opponent.target() {
+                    if *target == soldier.uuid() {
+                        priority += 3;
+                    } else if battle_state.soldier(*target).squad_uuid() == soldier.squad_uuid() {
+                        priority += 2;
+                    }
+                }
+                if opponent
+                    .main_weapon()
+                    .as_ref()
+                    .map(|weapon| weapon.is_machine_gun())
+                    .unwrap_or(false)
+                {
+                    priority += 2;
+                }
+                let last_shoot_frame_i = *opponent.last_shoot_frame_i();
+                if last_shoot_frame_i > 0
+                    && last_shoot_frame_i + TARGET_POLICY_RECENT_SHOOT_FRAMES
+                        >= *battle_state.frame_i()
+                {
+                    priority += 1;
+                }
+                priority
+            }
+            // Less opacity around opponent means less cover
+            Self::WeakestCover => {
+                ((1.0 - visibility.to_scene_item_opacity.clamp(0.0, 1.0)) * 100.0) as u32
+            }
+            Self::VehiclesFirst => battle_state.soldier_vehicle(opponent.uuid()).is_some() as u32,
+            Self::LeadersFirst => {
+                (battle_state.squad(opponent.squad_uuid()).leader() == opponent.uuid()) as u32
+            }
+            Self::OfficersAndCrews => {
+                if battle_state.squad(opponent.squad_uuid()).leader() == opponent.uuid() {
+                    2
+                } else {
+                    battle_state.soldier_vehicle(opponent.uuid()).is_some() as u32
+                }
+            }
         }
     }
 
@@ -29,6 +143,7 @@ impl ChooseMethod {
         &self,
         battle_state: &BattleState,
         visibles: Vec<&Visibility>,
+        rng: &mut impl Rng,
     ) -> Option<SoldierIndex> {
         if let Some(visibility) = visibles.first() {
             let soldier = battle_state.soldier(
@@ -51,9 +166,7 @@ impl ChooseMethod {
                 })
                 .collect();
 
-            return near_soldiers
-                .choose(&mut rand::thread_rng())
-                .map(|s| s.uuid());
+            return near_soldiers.choose(rng).map(|s| s.uuid());
         }
 
         None
@@ -70,15 +183,25 @@ impl Runner {
         squad_index: Option<&SquadUuid>,
         method: &ChooseMethod,
     ) -> Option<&Soldier> {
+        // Ambushing squad holds its fire
+        if self
+            .battle_state
+            .squad_ambush(&soldier.squad_uuid())
+            .is_some()
+        {
+            return None;
+        }
+
         let mut visibles = self
             .battle_state
             .visibilities()
             .visibles_soldiers_by_soldier(soldier);
 
         visibles.retain(|v| {
-            self.battle_state
-                .soldier(v.to_soldier.expect("filtered previously"))
-                .can_be_designed_as_target()
+            let opponent = self
+                .battle_state
+                .soldier(v.to_soldier.expect("filtered previously"));
+            opponent.can_be_designed_as_target() && self.soldier_can_target(soldier, opponent)
         });
 
         if let Some(squad_index) = squad_index {
@@ -90,19 +213,252 @@ impl Runner {
             })
         }
 
-        visibles.sort_by(|a, b| {
-            a.distance
-                .millimeters()
-                .partial_cmp(&b.distance.millimeters())
-                .expect("Must be i64")
-        });
+        // Soldier index discriminate same distances to keep choice deterministic
+        visibles.sort_by_key(|v| (v.distance.millimeters(), v.to_soldier.map(|i| i.0)));
 
         if soldier.behavior().is_hide() {
             visibles.retain(|v| v.distance <= self.config.hide_maximum_rayon)
         }
 
         method
-            .choose(&self.battle_state, visibles)
+            .choose(&self.battle_state, soldier, visibles, &mut *self.rng())
             .map(|i| self.battle_state.soldier(i))
+            .filter(|opponent| !self.friends_on_line_of_fire(soldier, &opponent.world_point()))
+    }
+
+    /// Choose method according to soldier squad or role target policy
+    pub fn soldier_choose_method(&self, soldier: &Soldier) -> ChooseMethod {
+        ChooseMethod::from(self.battle_state.soldier_target_policy(soldier))
+    }
+
+    /// Nearest visible opponent vehicle in range of soldier anti-tank weapon
+    pub fn soldier_find_vehicle_to_target(
+        &self,
+        soldier: &Soldier,
+        squad_index: Option<&SquadUuid>,
+    ) -> Option<&Vehicle> {
+        let weapon = soldier
+            .main_weapon()
+            .as_ref()
+            .filter(|weapon| weapon.is_anti_tank())?;
+
+        // Ambushing squad holds its fire
+        if self
+            .battle_state
+            .squad_ambush(&soldier.squad_uuid())
+            .is_some()
+        {
+            return None;
+        }
+
+        self.battle_state
+            .vehicles()
+            .iter()
+            .filter(|vehicle| !vehicle.knocked_out())
+            .filter(|vehicle| {
+                self.battle_state
+                    .vehicle_board()
+                    .get(vehicle.uuid())
+                    .unwrap_or(&vec![])
+                    .iter()
+                    .map(|(_, crew_index)| self.battle_state.soldier(*crew_index))
+                    .any(|crew| {
+                        crew.side() != soldier.side()
+                            && crew.can_be_designed_as_target()
+                            && squad_index
+                                .map(|squad_index| crew.squad_uuid() == *squad_index)
+                                .unwrap_or(true)
+                    })
+            })
+            .map(|vehicle| {
+                let distance =
+                    distance_between_points(&soldier.world_point(), &vehicle.world_point());
+                (distance, vehicle)
+            })
+            .filter(|(distance, _)| {
+                weapon
+                    .maximum_range()
+                    .map(|maximum_range| distance <= &maximum_range)
+                    .unwrap_or(true)
+                    && (!soldier.behavior().is_hide()
+                        || distance <= &self.config.hide_maximum_rayon)
+            })
+            .filter(|(_, vehicle)| {
+                self.soldier_can_target_point(soldier, &vehicle.world_point())
+                    && self.battle_state.point_is_visible_by_soldier(
+                        &self.config,
+                        soldier,
+                        &vehicle.world_point(),
+                        self.config.visibility_by_last_frame_shoot_distance,
+                    )
+            })
+            .min_by_key(|(distance, vehicle)| (distance.millimeters(), vehicle.uuid().0))
+            .map(|(_, vehicle)| vehicle)
+            .filter(|vehicle| !self.friends_on_line_of_fire(soldier, &vehicle.world_point()))
+    }
+
+    /// Opponent is in soldier squad fire sector (if any) and out of side no fire areas
+    pub fn soldier_can_target(&self, soldier: &Soldier, opponent: &Soldier) -> bool {
+        self.soldier_can_target_point(soldier, &opponent.world_point())
+    }
+
+    /// Point is in soldier squad fire sector (if any) and out of side no fire areas
+    pub fn soldier_can_target_point(&self, soldier: &Soldier, point: &WorldPoint) -> bool {
+        let in_sector = self
+            .battle_state
+            .squad_fire_sector(&soldier.squad_uuid())
+            .map(|sector| sector.contains(&soldier.world_point(), point))
+            .unwrap_or(true);
+        let in_no_fire_area = self
+            .battle_state
+            .no_fire_areas()
+            .iter()
+            .any(|area| area.side() == soldier.side() && area.contains(point));
+
+        in_sector && !in_no_fire_area
+    }
+
+    /// Alive friend (other than soldier) stands near the line of fire from soldier to point.
+    /// Friends behind soldier (or at its point) are not in the way.
+    pub fn friends_on_line_of_fire(&self, soldier: &Soldier, point: &WorldPoint) -> bool {
+        let from = soldier.world_point();
+        self.battle_state.soldiers().iter().any(|friend| {
+            friend.side() == soldier.side()
+                && friend.uuid() != soldier.uuid()
+                && friend.can_be_designed_as_target()
+                && friend.world_point() != from
+                && projection_ratio(&friend.world_point(), &from, point) > 0.
+                && distance_between_points(
+                    &friend.world_point(),
+                    &nearest_point_on_segment(&friend.world_point(), &from, point),
+                )
+                .millimeters()
+                    < FRIENDLY_LINE_OF_FIRE_MILLIMETERS
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use battle_core::{
+        behavior::Behavior,
+        entity::vehicle::{OnBoardPlace, VehicleType},
+        game::Side,
+        types::VehicleIndex,
+    };
+    use rstest::*;
+
+    use crate::runner::testing::{map, runner, runner_on};
+
+    use super::*;
+
+    #[rstest]
+    #[case(WorldPoint::new(100., 51.), true)]
+    #[case(WorldPoint::new(100., 100.), false)]
+    #[case(WorldPoint::new(47., 50.), false)]
+    #[case(WorldPoint::new(50., 52.), false)]
+    #[case(WorldPoint::new(50., 50.), false)]
+    fn friend_on_line_of_fire(#[case] friend_point: WorldPoint, #[case] expected: bool) {
+        // Given
+        let soldier =
+            |i, point| Soldier::new(SoldierIndex(i), point, SquadUuid(0), Side::A, None, vec![]);
+        let runner = runner(vec![
+            soldier(0, WorldPoint::new(50., 50.)),
+            soldier(1, friend_point),
+        ]);
+        let shooter = runner.battle_state.soldier(SoldierIndex(0));
+
+        // When
+        let blocked = runner.friends_on_line_of_fire(shooter, &WorldPoint::new(150., 50.));
+
+        // Then
+        assert_eq!(blocked, expected);
+    }
+
+    /// Opponents (side B, squad 1, its leader is the first one) seen by soldier 0 :
+    /// 1: leader, 2: nearest in good cover, 3: engaging soldier 0, 4: vehicle crew,
+    /// 5: farthest without cover
+    #[rstest]
+    #[case(ChooseMethod::RandomFromNearest, vec![1, 2, 3, 4, 5], 2)]
+    #[case(ChooseMethod::MostThreatening, vec![1, 2, 3, 4, 5], 3)]
+    #[case(ChooseMethod::MostThreatening, vec![1, 2, 4, 5], 2)]
+    #[case(ChooseMethod::WeakestCover, vec![1, 2, 3, 4, 5], 5)]
+    #[case(ChooseMethod::VehiclesFirst, vec![1, 2, 3, 4, 5], 4)]
+    #[case(ChooseMethod::VehiclesFirst, vec![1, 2, 3, 5], 2)]
+    #[case(ChooseMethod::LeadersFirst, vec![1, 2, 3, 4, 5], 1)]
+    #[case(ChooseMethod::OfficersAndCrews, vec![1, 2, 3, 4, 5], 1)]
+    #[case(ChooseMethod::OfficersAndCrews, vec![2, 3, 4, 5], 4)]
+    fn choose_opponent_by_method(
+        #[case] method: ChooseMethod,
+        #[case] visibles: Vec<usize>,
+        #[case] expected: usize,
+    ) {
+        // Given
+        let opponents = [
+            (1, 200., 0.4),
+            (2, 50., 0.8),
+            (3, 100., 0.6),
+            (4, 150., 0.5),
+            (5, 250., 0.),
+        ];
+        let mut soldiers = vec![Soldier::new(
+            SoldierIndex(0),
+            WorldPoint::new(10., 10.),
+            SquadUuid(0),
+            Side::A,
+            None,
+            vec![],
+        )];
+        for (i, x, _) in opponents {
+            soldiers.push(Soldier::new(
+                SoldierIndex(i),
+                WorldPoint::new(x, 10.),
+                SquadUuid(1),
+                Side::B,
+                None,
+                vec![],
+            ))
+        }
+        soldiers[3].set_behavior(Behavior::EngageSoldier(SoldierIndex(0)));
+        let runner = runner_on(
+            map(|_, _| "ShortGrass"),
+            soldiers,
+            vec![Vehicle::new(
+                VehicleIndex(0),
+                VehicleType::T26,
+                WorldPoint::new(150., 10.),
+            )],
+            HashMap::from([(SoldierIndex(4), (VehicleIndex(0), OnBoardPlace::Driver))]),
+        );
+        let soldier = runner.battle_state.soldier(SoldierIndex(0));
+        let visibilities: Vec<Visibility> = opponents
+            .iter()
+            .filter(|(i, _, _)| visibles.contains(i))
+            .map(|(i, x, opacity)| Visibility {
+                from: soldier.world_point(),
+                from_soldier: Some(SoldierIndex(0)),
+                to: WorldPoint::new(*x, 10.),
+                to_soldier: Some(SoldierIndex(*i)),
+                path_final_opacity: 0.,
+                to_scene_item_opacity: *opacity,
+                opacity_segments: vec![],
+                visible: true,
+                distance: distance_between_points(
+                    &soldier.world_point(),
+                    &WorldPoint::new(*x, 10.),
+                ),
+                break_point: None,
+            })
+            .collect();
+        let mut visibles: Vec<&Visibility> = visibilities.iter().collect();
+        visibles.sort_by_key(|v| v.distance.millimeters());
+
+        // When
+        let chosen = method.choose(&runner.battle_state, soldier, visibles, &mut *runner.rng());
+
+        // Then
+        assert_eq!(chosen, Some(SoldierIndex(expected)));
     }
 }
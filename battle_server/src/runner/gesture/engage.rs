@@ -1,4 +1,7 @@
-use battle_core::{entity::soldier::Soldier, types::SoldierIndex};
+use battle_core::{
+    entity::soldier::Soldier,
+    types::{SoldierIndex, VehicleIndex},
+};
 
 use crate::runner::Runner;
 
@@ -22,4 +25,25 @@ impl Runner {
 
         GestureResult::Cant(None)
     }
+
+    pub fn engage_vehicle_gesture(
+        &self,
+        soldier: &Soldier,
+        engaged_vehicle_index: &VehicleIndex,
+    ) -> GestureResult {
+        let vehicle = self.battle_state.vehicle(*engaged_vehicle_index);
+
+        if !vehicle.knocked_out() {
+            let point = vehicle.world_point();
+            if let Some(weapon) = self
+                .soldier_able_to_fire_on_point(soldier, &point)
+                .filter(|(_, weapon)| weapon.is_anti_tank())
+            {
+                let (gesture_context, gesture) = self.engage_point_gesture(soldier, &point, weapon);
+                return GestureResult::Handled(gesture_context, gesture);
+            }
+        }
+
+        GestureResult::Cant(None)
+    }
 }
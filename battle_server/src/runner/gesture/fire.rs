@@ -1,11 +1,11 @@
 use battle_core::{
     behavior::gesture::{Gesture, GestureContext},
+    config::BULLET_MISS_EXTENSION_METERS,
     entity::soldier::{Soldier, WeaponClass},
     game::weapon::Weapon,
-    physics::utils::distance_between_points,
-    types::WorldPoint,
+    physics::{ballistics::ShotDispersion, utils::distance_between_points},
+    types::{Distance, WorldPoint},
 };
-use glam::Vec2;
 use rand::Rng;
 
 use crate::runner::Runner;
@@ -26,7 +26,17 @@ impl Runner {
             return None;
         }
 
+        if self.friends_on_line_of_fire(soldier, point) {
+            return None;
+        }
+
         if let Some((weapon_class, weapon)) = self.soldier_weapon_for_point(soldier, point) {
+            if let Some(maximum_range) = weapon.maximum_range() {
+                if distance_between_points(&soldier.world_point(), point) > maximum_range {
+                    return None;
+                }
+            }
+
             if weapon.can_fire() || weapon.can_reload() {
                 return Some((weapon_class, weapon));
             }
@@ -75,32 +85,34 @@ impl Runner {
         };
 
         let final_point = self.soldier_fire_point(soldier, &weapon.0, point);
-        (GestureContext::Firing(final_point, None), gesture)
+        (GestureContext::Firing(final_point), gesture)
     }
 
-    // FIXME : use realistic range error (angle from target)
+    /// Shot end point, deviated in weapon and shooter dispersion cone, and extended after target
     pub fn soldier_fire_point(
         &self,
         soldier: &Soldier,
-        _weapon_class: &WeaponClass,
+        weapon_class: &WeaponClass,
         target_point: &WorldPoint,
     ) -> WorldPoint {
-        let mut rng = rand::thread_rng();
-        // TODO : change precision according to weapon, stress, distance, etc
-        let range = 2.0
-            * (distance_between_points(&soldier.world_point(), target_point).meters() as f32
-                / 500.);
-
-        if range == 0. {
-            eprintln!(
-                "ERROR : soldier_fire_point on original soldier point ({:?})",
-                target_point
-            );
-            return *target_point;
-        }
+        let weapon = match soldier.weapon(weapon_class) {
+            Some(weapon) => weapon,
+            None => return *target_point,
+        };
+        let distance = distance_between_points(&soldier.world_point(), target_point);
+        let deviation: f32 = self.rng().gen_range(-1.0..=1.0);
+        // Grenades burst where they land
+        let extension = if weapon.ammunition().explosive().is_some() {
+            Distance::from_meters(0)
+        } else {
+            Distance::from_meters(BULLET_MISS_EXTENSION_METERS)
+        };
 
-        let x_change = rng.gen_range(-range..range);
-        let y_change = rng.gen_range(-range..range);
-        target_point.apply(Vec2::new(x_change, y_change))
+        ShotDispersion::from_soldier(soldier, weapon, distance).shot_end(
+            &soldier.world_point(),
+            target_point,
+            deviation,
+            &extension,
+        )
     }
 }
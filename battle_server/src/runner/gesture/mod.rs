@@ -10,7 +10,7 @@ use battle_core::{
         battle::message::{BattleStateMessage, SoldierMessage},
         client::ClientStateMessage,
     },
-    types::{Precision, SoldierIndex, WorldPoint},
+    types::WorldPoint,
 };
 
 use super::{message::RunnerMessage, Runner};
@@ -47,6 +47,10 @@ impl Runner {
                 //
                 self.engage_soldier_gesture(soldier, soldier_index)
             }
+            Behavior::EngageVehicle(vehicle_index) => {
+                //
+                self.engage_vehicle_gesture(soldier, vehicle_index)
+            }
             _ => GestureResult::Handled(GestureContext::Idle, Gesture::Idle),
         };
 
@@ -90,9 +94,9 @@ impl Runner {
                 }
             }
             (_, Gesture::Aiming(_, _)) => {}
-            (GestureContext::Firing(point, target), Gesture::Firing(_, class)) => {
+            (GestureContext::Firing(point), Gesture::Firing(_, class)) => {
                 if let Some(weapon) = soldier.weapon(class) {
-                    return self.firing_gesture_messages(soldier, class, weapon, point, target);
+                    return self.firing_gesture_messages(soldier, class, weapon, point);
                 }
             }
             _ => {}
@@ -129,25 +133,29 @@ impl Runner {
         class: &WeaponClass,
         weapon: &Weapon,
         point: &WorldPoint,
-        target: &Option<(SoldierIndex, Precision)>,
     ) -> Vec<RunnerMessage> {
-        [vec![
-            RunnerMessage::BattleState(BattleStateMessage::Soldier(
-                soldier.uuid(),
-                SoldierMessage::WeaponShot(class.clone()),
-            )),
-            RunnerMessage::BattleState(BattleStateMessage::PushBulletFire(BulletFire::new(
-                soldier.world_point(),
-                *point,
-                target.clone(),
-                weapon.ammunition(),
-                weapon.gun_fire_sound_type(),
-            ))),
-            RunnerMessage::BattleState(BattleStateMessage::Soldier(
-                soldier.uuid(),
-                SoldierMessage::SetLastShootFrameI(*self.battle_state.frame_i()),
-            )),
-        ]]
+        [
+            vec![
+                RunnerMessage::BattleState(BattleStateMessage::Soldier(
+                    soldier.uuid(),
+                    SoldierMessage::WeaponShot(class.clone()),
+                )),
+                RunnerMessage::BattleState(BattleStateMessage::PushBulletFire(
+                    BulletFire::new(
+                        soldier.world_point(),
+                        *point,
+                        weapon.ammunition(),
+                        weapon.gun_fire_sound_type(),
+                    )
+                    .fired_by(*soldier.side()),
+                )),
+                RunnerMessage::BattleState(BattleStateMessage::Soldier(
+                    soldier.uuid(),
+                    SoldierMessage::SetLastShootFrameI(*self.battle_state.frame_i()),
+                )),
+            ],
+            self.sniper_relocation_messages(soldier),
+        ]
         .concat()
     }
 }
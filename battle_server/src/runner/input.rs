@@ -1,6 +1,6 @@
 use battle_core::{
     message::{InputMessage, OutputMessage},
-    state::battle::BattleState,
+    state::battle::{message::BattleStateMessage, BattleState},
 };
 use crossbeam_channel::TryRecvError;
 
@@ -21,6 +21,10 @@ impl Runner {
 
             let mut side_effects = vec![];
             for input in inputs {
+                let input = match self.lockstep_input(input) {
+                    Some(input) => input,
+                    None => continue,
+                };
                 match input {
                     InputMessage::LoadDeployment(deployment) => {
                         self.battle_state.inject(&deployment)
@@ -31,8 +35,12 @@ impl Runner {
                             .update_flags_from_control(a_control, b_control);
                     }
                     InputMessage::RequireCompleteSync => {
-                        self.output
-                            .send(vec![OutputMessage::LoadFromCopy(self.battle_state.copy())])?;
+                        self.output.send(vec![
+                            OutputMessage::LoadFromCopy(Box::new(self.battle_state.copy())),
+                            OutputMessage::BattleState(BattleStateMessage::SetVisibilities(
+                                self.battle_state.visibilities().all().clone(),
+                            )),
+                        ])?;
                     }
                     InputMessage::BattleState(battle_state_message) => {
                         side_effects.extend(
@@ -44,6 +52,8 @@ impl Runner {
                         self.output
                             .send(vec![OutputMessage::ChangeConfig(change_config.clone())])?;
                         self.config.react(&change_config);
+                        // Visibilities depend on config, compute all of them again
+                        self.visibility_stamps().clear();
                     }
                     InputMessage::SetBattleState(copy) => {
                         //
@@ -51,6 +61,12 @@ impl Runner {
                         self.battle_state.resolve();
                         self.output.send(vec![OutputMessage::LoadFromCopy(copy)])?;
                     }
+                    InputMessage::Lobby(lobby_message) => {
+                        log::debug!("Ignore lobby message during battle : {:?}", lobby_message)
+                    }
+                    InputMessage::Lockstep(lockstep_message) => {
+                        log::debug!("Ignore lockstep message : {:?}", lockstep_message)
+                    }
                 };
             }
         }
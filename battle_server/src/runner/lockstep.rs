@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+
+use battle_core::{
+    config::LOCKSTEP_INPUT_DELAY_TURNS,
+    game::Side,
+    message::{lockstep::LockstepMessage, InputMessage, OutputMessage},
+    state::battle::message::BattleStateMessage,
+};
+use crossbeam_channel::Sender;
+
+use super::{message::RunnerMessage, Runner, RunnerError};
+
+/// Lockstep mode of the runner : each peer run the battle. Local orders are sent to peers
+/// (through server relay) instead of being directly applied, and the battle only advance
+/// when orders of all peers are known for the current turn.
+pub struct Lockstep {
+    side: Side,
+    peers: Vec<Side>,
+    network: Sender<Vec<InputMessage>>,
+    start_frame_i: Option<u64>,
+    pending: Vec<BattleStateMessage>,
+    orders: HashMap<u64, Vec<(Side, Vec<BattleStateMessage>)>>,
+    checksums: HashMap<u64, Vec<(Side, u64)>>,
+    desync: Option<u64>,
+}
+
+impl Lockstep {
+    /// Lockstep for local `side` player, with `peers` the sides of all players (including
+    /// local one) and `network` the channel to send messages to server relay.
+    pub fn new(side: Side, peers: Vec<Side>, network: Sender<Vec<InputMessage>>) -> Self {
+        Self {
+            side,
+            peers,
+            network,
+            start_frame_i: None,
+            pending: vec![],
+            orders: HashMap::new(),
+            checksums: HashMap::new(),
+            desync: None,
+        }
+    }
+
+    pub fn order(&mut self, message: BattleStateMessage) {
+        self.pending.push(message);
+    }
+
+    pub fn receive(&mut self, message: LockstepMessage) {
+        match message {
+            LockstepMessage::Orders(side, frame_i, messages) => self
+                .orders
+                .entry(frame_i)
+                .or_default()
+                .push((side, messages)),
+            LockstepMessage::Checksum(side, frame_i, checksum) => self
+                .checksums
+                .entry(frame_i)
+                .or_default()
+                .push((side, checksum)),
+            LockstepMessage::Finished(side) => println!("Lockstep peer {} finished", side),
+            LockstepMessage::Desync(_) => {}
+        }
+    }
+
+    /// True if orders of all peers are known for this frame. First frames are always ready
+    /// because no orders can be scheduled for them.
+    pub fn ready(&mut self, frame_i: u64, turn_frames: u64) -> bool {
+        let start_frame_i = *self.start_frame_i.get_or_insert(frame_i);
+        if frame_i % turn_frames != 0
+            || frame_i < start_frame_i + LOCKSTEP_INPUT_DELAY_TURNS * turn_frames
+        {
+            return true;
+        }
+
+        let orders = self.orders.get(&frame_i);
+        self.peers.iter().all(|peer| {
+            orders
+                .map(|orders| orders.iter().any(|(side, _)| side == peer))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Send pending local orders for a next turn and return all peers orders for this frame,
+    /// in peers order to be applied identically by each peer.
+    pub fn turn(
+        &mut self,
+        frame_i: u64,
+        turn_frames: u64,
+    ) -> Result<Vec<BattleStateMessage>, RunnerError> {
+        if frame_i % turn_frames != 0 {
+            return Ok(vec![]);
+        }
+
+        let pending = std::mem::take(&mut self.pending);
+        self.send(LockstepMessage::Orders(
+            self.side,
+            frame_i + LOCKSTEP_INPUT_DELAY_TURNS * turn_frames,
+            pending,
+        ))?;
+
+        let orders = self.orders.remove(&frame_i).unwrap_or_default();
+        Ok(self
+            .peers
+            .iter()
+            .flat_map(|peer| {
+                orders
+                    .iter()
+                    .filter(move |(side, _)| side == peer)
+                    .flat_map(|(_, messages)| messages.clone())
+            })
+            .collect())
+    }
+
+    pub fn checksum(&mut self, frame_i: u64, checksum: u64) -> Result<(), RunnerError> {
+        self.send(LockstepMessage::Checksum(self.side, frame_i, checksum))
+    }
+
+    /// Return the frame where desync happened if newly detected. Checksums are compared as
+    /// soon as all peers one are received for a frame.
+    pub fn desync(&mut self) -> Option<u64> {
+        let complete: Vec<u64> = self
+            .checksums
+            .iter()
+            .filter(|(_, checksums)| {
+                self.peers
+                    .iter()
+                    .all(|peer| checksums.iter().any(|(side, _)| side == peer))
+            })
+            .map(|(frame_i, _)| *frame_i)
+            .collect();
+
+        for frame_i in complete {
+            let checksums = self.checksums.remove(&frame_i).unwrap_or_default();
+            let different = checksums
+                .windows(2)
+                .any(|checksums| checksums[0].1 != checksums[1].1);
+            if different && self.desync.is_none() {
+                self.desync = Some(frame_i);
+                return Some(frame_i);
+            }
+        }
+
+        None
+    }
+
+    pub fn finished(&self) -> Result<(), RunnerError> {
+        self.send(LockstepMessage::Finished(self.side))
+    }
+
+    fn send(&self, message: LockstepMessage) -> Result<(), RunnerError> {
+        self.network
+            .send(vec![InputMessage::Lockstep(message)])
+            .map_err(RunnerError::Network)
+    }
+}
+
+impl Runner {
+    /// Handle input in lockstep mode. Return the input if it must be handled as usual.
+    pub fn lockstep_input(&mut self, input: InputMessage) -> Option<InputMessage> {
+        let lockstep = match &mut self.lockstep {
+            Some(lockstep) => lockstep,
+            None => return Some(input),
+        };
+
+        match input {
+            InputMessage::BattleState(message) => lockstep.order(message),
+            InputMessage::Lockstep(message) => lockstep.receive(message),
+            InputMessage::RequireCompleteSync | InputMessage::Lobby(_) => return Some(input),
+            InputMessage::LoadDeployment(_)
+            | InputMessage::LoadControl(_)
+            | InputMessage::SetBattleState(_)
+            | InputMessage::ChangeConfig(_) => {
+                log::warn!(
+                    "Ignore input which would desync lockstep peers : {:?}",
+                    input
+                )
+            }
+        }
+
+        None
+    }
+
+    /// In lockstep mode, true if current frame can be computed
+    pub fn lockstep_ready(&mut self) -> bool {
+        let frame_i = *self.battle_state.frame_i();
+        self.lockstep
+            .as_mut()
+            .map(|lockstep| lockstep.ready(frame_i, self.config.lockstep_turn_frames()))
+            .unwrap_or(true)
+    }
+
+    /// In lockstep mode, apply peers orders scheduled for current frame
+    pub fn lockstep_turn(&mut self) -> Result<(), RunnerError> {
+        let frame_i = *self.battle_state.frame_i();
+        let orders = match &mut self.lockstep {
+            Some(lockstep) => lockstep.turn(frame_i, self.config.lockstep_turn_frames())?,
+            None => return Ok(()),
+        };
+
+        let messages: Vec<RunnerMessage> =
+            orders.into_iter().map(RunnerMessage::BattleState).collect();
+        self.react(&messages);
+        Ok(())
+    }
+
+    /// In lockstep mode, share battle state checksum with peers and report desync
+    pub fn lockstep_checksum(&mut self) -> Result<(), RunnerError> {
+        let frame_i = *self.battle_state.frame_i();
+        if self.lockstep.is_none() || frame_i % self.config.lockstep_checksum_freq() != 0 {
+            return Ok(());
+        }
+
+        let checksum = self.battle_state.copy().checksum();
+        if let Some(lockstep) = &mut self.lockstep {
+            lockstep.checksum(frame_i, checksum)?;
+            if let Some(desync_frame_i) = lockstep.desync() {
+                println!("WARNING :: Lockstep :: desync detected at frame {desync_frame_i}");
+                self.output
+                    .send(vec![OutputMessage::Lockstep(LockstepMessage::Desync(
+                        desync_frame_i,
+                    ))])?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossbeam_channel::{unbounded, Receiver};
+    use rstest::*;
+
+    use super::*;
+
+    const TURN_FRAMES: u64 = 10;
+    const FIRST_ORDERS_FRAME_I: u64 = LOCKSTEP_INPUT_DELAY_TURNS * TURN_FRAMES;
+
+    fn lockstep() -> (Lockstep, Receiver<Vec<InputMessage>>) {
+        let (network, relay) = unbounded();
+        (
+            Lockstep::new(Side::A, vec![Side::A, Side::B], network),
+            relay,
+        )
+    }
+
+    #[rstest]
+    fn turn_ready_when_all_peers_orders_received() {
+        // Given
+        let (mut lockstep, _relay) = lockstep();
+        assert!(lockstep.ready(0, TURN_FRAMES));
+
+        // When
+        let without_orders = lockstep.ready(FIRST_ORDERS_FRAME_I, TURN_FRAMES);
+        lockstep.receive(LockstepMessage::Orders(
+            Side::A,
+            FIRST_ORDERS_FRAME_I,
+            vec![],
+        ));
+        let with_local_orders = lockstep.ready(FIRST_ORDERS_FRAME_I, TURN_FRAMES);
+        lockstep.receive(LockstepMessage::Orders(
+            Side::B,
+            FIRST_ORDERS_FRAME_I,
+            vec![],
+        ));
+        let with_all_orders = lockstep.ready(FIRST_ORDERS_FRAME_I, TURN_FRAMES);
+
+        // Then
+        assert!(!without_orders);
+        assert!(!with_local_orders);
+        assert!(with_all_orders);
+        assert!(lockstep.ready(FIRST_ORDERS_FRAME_I + 1, TURN_FRAMES));
+    }
+
+    #[rstest]
+    fn turn_applies_orders_in_peers_order() {
+        // Given
+        let (mut lockstep, relay) = lockstep();
+        lockstep.order(BattleStateMessage::SetAReady(false));
+        lockstep.receive(LockstepMessage::Orders(
+            Side::B,
+            FIRST_ORDERS_FRAME_I,
+            vec![BattleStateMessage::SetBReady(true)],
+        ));
+        lockstep.receive(LockstepMessage::Orders(
+            Side::A,
+            FIRST_ORDERS_FRAME_I,
+            vec![BattleStateMessage::SetAReady(true)],
+        ));
+
+        // When
+        let orders = lockstep.turn(FIRST_ORDERS_FRAME_I, TURN_FRAMES).unwrap();
+
+        // Then
+        assert!(matches!(
+            orders.as_slice(),
+            [
+                BattleStateMessage::SetAReady(true),
+                BattleStateMessage::SetBReady(true)
+            ]
+        ));
+        let sent = relay.try_recv().unwrap();
+        assert!(matches!(
+            sent.as_slice(),
+            [InputMessage::Lockstep(LockstepMessage::Orders(
+                Side::A,
+                frame_i,
+                messages,
+            ))] if *frame_i == 2 * FIRST_ORDERS_FRAME_I
+                && matches!(messages.as_slice(), [BattleStateMessage::SetAReady(false)])
+        ));
+    }
+
+    #[rstest]
+    #[case(42, 42, None)]
+    #[case(42, 43, Some(TURN_FRAMES))]
+    fn desync_on_checksum_mismatch(
+        #[case] a_checksum: u64,
+        #[case] b_checksum: u64,
+        #[case] expected: Option<u64>,
+    ) {
+        // Given
+        let (mut lockstep, _relay) = lockstep();
+        lockstep.receive(LockstepMessage::Checksum(Side::A, TURN_FRAMES, a_checksum));
+        assert_eq!(lockstep.desync(), None);
+        lockstep.receive(LockstepMessage::Checksum(Side::B, TURN_FRAMES, b_checksum));
+
+        // When
+        let desync = lockstep.desync();
+
+        // Then
+        assert_eq!(desync, expected);
+        assert_eq!(lockstep.desync(), None);
+    }
+}
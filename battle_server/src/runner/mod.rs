@@ -1,25 +1,34 @@
 use battle_core::{
     config::ServerConfig,
+    game::environment::EnvironmentChange,
     message::{InputMessage, OutputMessage},
     state::battle::BattleState,
+    types::{Distance, SoldierIndex, WorldPoint},
 };
 use crossbeam_channel::{Receiver, SendError, Sender};
+use rand::{rngs::StdRng, SeedableRng};
 use std::{
+    collections::HashMap,
     fmt::Display,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc,
+        Arc, Mutex, MutexGuard,
     },
     thread,
     time::{Duration, Instant},
 };
 
+mod ai;
+mod ambush;
 mod behavior;
+mod bounding;
 mod engage;
+mod environment;
 mod fight;
 mod flag;
 mod gesture;
 mod input;
+pub mod lockstep;
 mod message;
 mod morale;
 mod movement;
@@ -27,7 +36,11 @@ mod output;
 mod phase;
 mod physics;
 mod react;
+mod smoke;
+mod sniper;
 mod soldier;
+#[cfg(test)]
+pub mod testing;
 mod tick;
 mod update;
 mod utils;
@@ -35,7 +48,13 @@ mod vehicle;
 mod victory;
 mod visibility;
 
+use crate::ai::Ai;
+
+use self::{lockstep::Lockstep, tick::CloudStamp, visibility::VisibilityStamp};
+
 const TARGET_CYCLE_DURATION_US: u64 = 16666;
+// Keep running some frames after battle end to let clients receive last messages
+const END_PHASE_DURATION_FRAMES: u64 = 600;
 
 pub struct Runner {
     config: ServerConfig,
@@ -44,6 +63,15 @@ pub struct Runner {
     stop_required: Arc<AtomicBool>,
     last: Instant,
     battle_state: BattleState,
+    end_frame_i: Option<u64>,
+    // Mutex because runner is shared between threads during visibilities computing
+    rng: Mutex<StdRng>,
+    lockstep: Option<Lockstep>,
+    ais: Vec<Ai>,
+    visibility_stamps: Mutex<HashMap<SoldierIndex, VisibilityStamp>>,
+    visibility_dirty_areas: Mutex<Vec<(WorldPoint, Distance)>>,
+    clouds_stamp: Vec<CloudStamp>,
+    environments: Vec<EnvironmentChange>,
 }
 
 impl Runner {
@@ -61,9 +89,48 @@ impl Runner {
             stop_required,
             last: Instant::now(),
             battle_state: state,
+            end_frame_i: None,
+            rng: Mutex::new(StdRng::from_entropy()),
+            lockstep: None,
+            ais: vec![],
+            visibility_stamps: Mutex::new(HashMap::new()),
+            visibility_dirty_areas: Mutex::new(vec![]),
+            clouds_stamp: vec![],
+            environments: vec![],
         }
     }
 
+    /// Seed the simulation random generator : same seed and same inputs give same battle
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = Mutex::new(StdRng::seed_from_u64(seed));
+        self
+    }
+
+    pub fn lockstep(mut self, lockstep: Lockstep) -> Self {
+        self.lockstep = Some(lockstep);
+        self
+    }
+
+    /// Weather and daylight changes to apply during battle
+    pub fn environments(mut self, environments: Vec<EnvironmentChange>) -> Self {
+        self.environments = environments;
+        self
+    }
+
+    /// Let an AI play a side
+    pub fn ai(mut self, ai: Ai) -> Self {
+        self.ais.push(ai);
+        self
+    }
+
+    /// Random generator to use for all simulation decisions
+    pub fn rng(&self) -> MutexGuard<'_, StdRng> {
+        self.rng
+            .lock()
+            .expect("Runner rng mutex must not be poisoned")
+    }
+
+    /// Run the battle until stop is required or battle ended since `END_PHASE_DURATION_FRAMES`
     pub fn run(&mut self) -> Result<(), RunnerError> {
         loop {
             if self.stop_required.load(Ordering::Relaxed) {
@@ -71,6 +138,11 @@ impl Runner {
                 break;
             }
 
+            if self.battle_finished() {
+                println!("Battle finished, stopping runner ...");
+                break;
+            }
+
             let frame_i = self.battle_state.frame_i();
             puffin::profile_scope!("run", format!("frame {frame_i}"));
             puffin::GlobalProfiler::lock().new_frame();
@@ -80,9 +152,23 @@ impl Runner {
             self.tick()?;
         }
 
+        if let Some(lockstep) = &self.lockstep {
+            lockstep.finished()?;
+        }
+
         Ok(())
     }
 
+    fn battle_finished(&mut self) -> bool {
+        let frame_i = *self.battle_state.frame_i();
+        if !self.battle_state.phase().is_end() {
+            return false;
+        }
+
+        let end_frame_i = *self.end_frame_i.get_or_insert(frame_i);
+        frame_i - end_frame_i >= END_PHASE_DURATION_FRAMES
+    }
+
     fn sleep_duration(&self) -> Duration {
         let elapsed = self.last.elapsed().as_micros() as u64;
         if elapsed > TARGET_CYCLE_DURATION_US {
@@ -97,6 +183,7 @@ impl Runner {
 pub enum RunnerError {
     InputChannelClosed,
     Output(SendError<Vec<OutputMessage>>),
+    Network(SendError<Vec<InputMessage>>),
 }
 
 impl From<SendError<Vec<OutputMessage>>> for RunnerError {
@@ -110,6 +197,7 @@ impl Display for RunnerError {
         match self {
             RunnerError::InputChannelClosed => f.write_str("Input channel closed"),
             RunnerError::Output(error) => f.write_str(&format!("Output error : {}", error)),
+            RunnerError::Network(error) => f.write_str(&format!("Network error : {}", error)),
         }
     }
 }
@@ -22,6 +22,13 @@ impl Runner {
             .config
             .behavior_velocity(soldier.behavior())
             .expect("Entity behavior must have velocity when move code called");
+        // Mud or snow slow movement
+        let map = self.battle_state.map();
+        let velocity =
+            match map.terrain_tile(&map.grid_point_from_world_point(&soldier.world_point())) {
+                Some(tile) => velocity / map.environment().ground_factor(&tile.type_),
+                None => velocity,
+            };
         let vector = (point.to_vec2() - soldier.world_point().to_vec2()).normalize() * velocity;
 
         // Point reached
@@ -52,7 +59,8 @@ impl Runner {
                         SoldierMessage::SetOrder(order),
                     )),
                 ]);
-            } else {
+            // Squad leader waits other squads of its group at phase line
+            } else if !self.waits_at_phase_line(soldier_index, path) {
                 messages.push(RunnerMessage::BattleState(BattleStateMessage::Soldier(
                     soldier_index,
                     SoldierMessage::ReachBehaviorStep,
@@ -70,4 +78,12 @@ impl Runner {
 
         messages
     }
+
+    fn waits_at_phase_line(&self, soldier_index: SoldierIndex, path: &WorldPaths) -> bool {
+        let squad_uuid = self.battle_state.soldier(soldier_index).squad_uuid();
+        self.battle_state.squad(squad_uuid).leader() == soldier_index
+            && self
+                .battle_state
+                .squad_waits_at_phase_line(&squad_uuid, path)
+    }
 }
@@ -0,0 +1,244 @@
+use battle_core::{
+    audio::Sound,
+    behavior::{Behavior, Body},
+    config::{
+        VEHICLE_BAILOUT_MARGIN_METERS, VEHICLE_KNOCK_OUT_CREW_KILL_CHANCE,
+        VEHICLE_PENETRATION_KNOCK_OUT_CHANCE,
+    },
+    entity::vehicle::Vehicle,
+    game::{explosive::ExplosiveType, Side},
+    order::Order,
+    physics::{
+        event::{bullet::BulletFire, explosion::Explosion},
+        utils::{
+            distance_between_points, nearest_point_on_segment, DISTANCE_TO_METERS_COEFFICIENT,
+        },
+    },
+    state::{
+        battle::message::{BattleStateMessage, SoldierMessage, VehicleMessage},
+        client::ClientStateMessage,
+    },
+    types::WorldPoint,
+};
+use glam::Vec2;
+use rand::Rng;
+
+use crate::runner::{message::RunnerMessage, Runner};
+
+impl Runner {
+    /// Nearest vehicle from shooter crossed by bullet path
+    pub fn vehicle_on_bullet_path(&self, bullet_fire: &BulletFire) -> Option<&Vehicle> {
+        self.battle_state
+            .vehicles()
+            .iter()
+            .filter(|vehicle| {
+                let side = self.battle_state.vehicle_side(*vehicle.uuid());
+                // Without friendly fire, bullets of crew side pass by
+                self.config.friendly_fire || side.is_none() || side != bullet_fire.side()
+            })
+            .filter(|vehicle| {
+                let nearest = nearest_point_on_segment(
+                    &vehicle.world_point(),
+                    bullet_fire.from(),
+                    bullet_fire.to(),
+                );
+                // Shooter on board doesn't hit its own vehicle
+                vehicle.chassis_shape().contains(&nearest)
+                    && !vehicle.chassis_shape().contains(bullet_fire.from())
+            })
+            .min_by_key(|vehicle| {
+                (
+                    distance_between_points(bullet_fire.from(), &vehicle.world_point())
+                        .millimeters(),
+                    vehicle.uuid().0,
+                )
+            })
+    }
+
+    /// Bullet hits vehicle facing exposed to the shooter and maybe pierces its armor
+    pub fn vehicle_hit_effects(
+        &self,
+        vehicle: &Vehicle,
+        bullet_fire: &BulletFire,
+    ) -> Vec<RunnerMessage> {
+        puffin::profile_scope!("vehicle_hit_effects", vehicle.uuid().to_string());
+        let mut messages = vec![RunnerMessage::ClientsState(
+            ClientStateMessage::PlayBattleSound(Sound::BulletMetalImpact1),
+        )];
+
+        if vehicle.knocked_out() {
+            return messages;
+        }
+
+        let facing = vehicle.facing_from(bullet_fire.from());
+        let distance = distance_between_points(bullet_fire.from(), &vehicle.world_point());
+        let penetration = bullet_fire.ammunition().armor_penetration(&distance);
+        if penetration >= vehicle.type_().armor(&facing)
+            && self.rng().gen::<f32>() <= VEHICLE_PENETRATION_KNOCK_OUT_CHANCE
+        {
+            messages.extend(self.vehicle_knock_out_effects(vehicle, bullet_fire.side()));
+        }
+
+        messages
+    }
+
+    /// Vehicle ammunition explodes, crew dies or bails out out of the blast
+    fn vehicle_knock_out_effects(
+        &self,
+        vehicle: &Vehicle,
+        side: Option<&Side>,
+    ) -> Vec<RunnerMessage> {
+        puffin::profile_scope!("vehicle_knock_out_effects", vehicle.uuid().to_string());
+        let mut explosion = Explosion::new(vehicle.world_point(), ExplosiveType::AmmunitionRack);
+        if let Some(side) = side {
+            explosion = explosion.fired_by(*side);
+        }
+        let mut messages = vec![
+            RunnerMessage::BattleState(BattleStateMessage::Vehicle(
+                *vehicle.uuid(),
+                VehicleMessage::KnockOut,
+            )),
+            RunnerMessage::BattleState(BattleStateMessage::PushExplosion(explosion)),
+        ];
+
+        // Board is built from a hash map, sort crew to always spread it in same order
+        let mut crew = self
+            .battle_state
+            .vehicle_board()
+            .get(vehicle.uuid())
+            .cloned()
+            .unwrap_or_default();
+        crew.sort_by_key(|(_, crew_index)| crew_index.0);
+        for (i, (_, crew_index)) in crew.iter().enumerate() {
+            let soldier = self.battle_state.soldier(*crew_index);
+            if !soldier.alive() {
+                continue;
+            }
+
+            let point = bailout_point(vehicle, i, crew.len());
+
+            messages.extend(vec![
+                RunnerMessage::BattleState(BattleStateMessage::Soldier(
+                    *crew_index,
+                    SoldierMessage::SetWorldPosition(point),
+                )),
+                RunnerMessage::BattleState(BattleStateMessage::Soldier(
+                    *crew_index,
+                    SoldierMessage::SetOrder(Order::Idle),
+                )),
+            ]);
+
+            if self.rng().gen::<f32>() <= VEHICLE_KNOCK_OUT_CREW_KILL_CHANCE {
+                messages.extend(self.casualty_effects(side, soldier));
+                messages.extend(self.soldier_die(*crew_index));
+            } else {
+                messages.push(RunnerMessage::BattleState(BattleStateMessage::Soldier(
+                    *crew_index,
+                    SoldierMessage::SetBehavior(Behavior::Idle(Body::StandUp)),
+                )));
+            }
+        }
+
+        messages
+    }
+}
+
+/// Point out of the ammunition blast where the `i`th of `crew_count` crew members bails out.
+/// Crew members are spread around the vehicle, starting by its sides.
+fn bailout_point(vehicle: &Vehicle, i: usize, crew_count: usize) -> WorldPoint {
+    let bailout_distance = (ExplosiveType::AmmunitionRack
+        .regressive_injured_rayon()
+        .meters()
+        + VEHICLE_BAILOUT_MARGIN_METERS) as f32
+        / DISTANCE_TO_METERS_COEFFICIENT;
+    let around_angle =
+        std::f32::consts::FRAC_PI_2 + i as f32 * std::f32::consts::TAU / crew_count as f32;
+    let direction = Vec2::from_angle(vehicle.chassis_orientation().0 + around_angle);
+    WorldPoint::from_vec2(vehicle.world_point().to_vec2() + direction * bailout_distance)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use battle_core::{
+        entity::{
+            soldier::Soldier,
+            vehicle::{OnBoardPlace, VehicleType},
+        },
+        types::{Distance, SoldierIndex, SquadUuid, VehicleIndex},
+    };
+    use rstest::*;
+
+    use crate::runner::testing::{map, runner_on};
+
+    use super::*;
+
+    #[rstest]
+    fn knocked_out_crew_bails_out_of_blast() {
+        // Given a crewed tank
+        let point = WorldPoint::new(100., 100.);
+        let crew = |i| Soldier::new(SoldierIndex(i), point, SquadUuid(0), Side::A, None, vec![]);
+        let mut runner = runner_on(
+            map(|_, _| "ShortGrass"),
+            vec![crew(0), crew(1)],
+            vec![Vehicle::new(VehicleIndex(0), VehicleType::T26, point)],
+            HashMap::from([
+                (SoldierIndex(0), (VehicleIndex(0), OnBoardPlace::Driver)),
+                (
+                    SoldierIndex(1),
+                    (VehicleIndex(0), OnBoardPlace::MainTurretGunner),
+                ),
+            ]),
+        );
+
+        // When
+        let vehicle = runner.battle_state.vehicle(VehicleIndex(0)).clone();
+        let messages = runner.vehicle_knock_out_effects(&vehicle, Some(&Side::B));
+        runner.react(&messages);
+
+        // Then
+        assert!(runner.battle_state.vehicle(VehicleIndex(0)).knocked_out());
+        let explosion = runner.battle_state.explosions().last().unwrap();
+        assert_eq!(explosion.side(), Some(&Side::B));
+        let blast = ExplosiveType::AmmunitionRack.regressive_injured_rayon();
+        for i in [0, 1] {
+            let soldier = runner.battle_state.soldier(SoldierIndex(i));
+            assert!(runner
+                .battle_state
+                .soldier_vehicle_place(SoldierIndex(i))
+                .is_none());
+            assert!(distance_between_points(&soldier.world_point(), &point) > blast);
+        }
+        assert_ne!(
+            runner.battle_state.soldier(SoldierIndex(0)).world_point(),
+            runner.battle_state.soldier(SoldierIndex(1)).world_point()
+        );
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(2)]
+    #[case(3)]
+    #[case(4)]
+    fn crew_bails_out_around_vehicle(#[case] crew_count: usize) {
+        // Given
+        let point = WorldPoint::new(100., 100.);
+        let vehicle = Vehicle::new(VehicleIndex(0), VehicleType::T26, point);
+        let blast = ExplosiveType::AmmunitionRack.regressive_injured_rayon();
+
+        // When
+        let points: Vec<WorldPoint> = (0..crew_count)
+            .map(|i| bailout_point(&vehicle, i, crew_count))
+            .collect();
+
+        // Then
+        for (i, bailout_point) in points.iter().enumerate() {
+            assert!(distance_between_points(bailout_point, &point) > blast);
+            // Crew members don't stack on same points
+            assert!(points[..i].iter().all(|other| {
+                distance_between_points(other, bailout_point) > Distance::from_meters(5)
+            }));
+        }
+    }
+}
@@ -1,11 +1,15 @@
 use battle_core::{
     audio::Sound,
+    config::{BULLET_HIT_DISTANCE_MILLIMETERS, BULLET_HIT_KILL_CHANCE, COVERAGE_TILE_STEPS},
     entity::soldier::Soldier,
     physics::{
-        coverage::SoldierCovered, event::bullet::BulletFire, utils::distance_between_points,
+        ballistics::{bullet_obstacles, hit_chance, BulletObstacle},
+        coverage::SoldierCovered,
+        event::{bullet::BulletFire, explosion::Explosion},
+        utils::{distance_between_points, nearest_point_on_segment},
     },
-    state::client::ClientStateMessage,
-    types::Distance,
+    state::{battle::message::BattleStateMessage, client::ClientStateMessage},
+    types::{Distance, WorldPoint},
 };
 use rand::seq::SliceRandom;
 use rand::Rng;
@@ -26,53 +30,157 @@ impl Runner {
         messages
     }
 
-    // FIXME : find algorithm kill/injure about bullet + terrain + position
     fn bullet_fire_effects(&self, bullet_fire: &BulletFire) -> Vec<RunnerMessage> {
         puffin::profile_scope!(
             "bullet_fire_effects",
             format!("start={} end={}", bullet_fire.start(), bullet_fire.end())
         );
         let mut messages = vec![];
-        let point = bullet_fire.point();
 
-        for soldier in self.battle_state.soldiers() {
-            if !soldier.can_feel_bullet_fire() {
-                continue;
+        // Bullet stops on vehicle armor
+        let mut path = bullet_fire.clone();
+        let vehicle = self.vehicle_on_bullet_path(bullet_fire);
+        if let Some(vehicle) = vehicle {
+            path = bullet_fire.until(nearest_point_on_segment(
+                &vehicle.world_point(),
+                bullet_fire.from(),
+                bullet_fire.to(),
+            ));
+            messages.extend(self.vehicle_hit_effects(vehicle, bullet_fire));
+        }
+
+        // Grenades burst at impact
+        if let Some(explosive) = bullet_fire.ammunition().explosive() {
+            let mut explosion = Explosion::new(*path.to(), explosive);
+            if let Some(side) = bullet_fire.side() {
+                explosion = explosion.fired_by(*side);
             }
+            messages.push(RunnerMessage::BattleState(
+                BattleStateMessage::PushExplosion(explosion),
+            ));
+            return messages;
+        }
 
-            // Simple for now, but if in vehicle, don't be affected
-            if self
-                .battle_state
-                .soldier_vehicle_place(soldier.uuid())
-                .is_some()
+        // Bullet stops on first soldier it hits (or on its cover), or on first obstacle
+        let mut hit = None;
+        let mut stopped = false;
+        let mut passed = 0.;
+        let obstacles = bullet_obstacles(&self.config, self.battle_state.map(), &path);
+        let cover_margin =
+            (COVERAGE_TILE_STEPS as u32 * self.battle_state.map().tile_width()) as f32;
+        for (soldier, from_shooter, offset) in self.soldiers_on_bullet_path(&path) {
+            // Soldier own cover is resolved by coverage, obstacles before can stop the bullet
+            if let Some(point) =
+                self.obstacle_stopping_bullet(&obstacles, passed, from_shooter - cover_margin)
             {
-                continue;
+                path = bullet_fire.until(point);
+                stopped = true;
+                break;
             }
 
-            let distance = distance_between_points(&soldier.world_point(), point);
-            if distance.meters() < 1
-                && SoldierCovered::new(self.battle_state.map(), bullet_fire, soldier).compute()
+            let until = bullet_fire.until(soldier.world_point());
+            if SoldierCovered::new(self.battle_state.map(), &until, soldier)
+                .compute(&mut *self.rng())
             {
+                path = until;
+                stopped = true;
                 messages.extend(self.covered_bullet_effects(soldier));
-                messages.extend(self.proximity_bullet_effects(soldier, &distance))
-            } else if distance.millimeters() < 500 {
-                let mut rng = rand::thread_rng();
-                let value: u8 = rng.gen();
-                if value < 10 {
+                break;
+            }
+
+            if self.rng().gen::<f32>() < hit_chance(&soldier.behavior().posture(), &offset) {
+                path = until;
+                stopped = true;
+                hit = Some(soldier.uuid());
+                messages.extend(self.casualty_effects(bullet_fire.side(), soldier));
+                if self.rng().gen::<f32>() <= BULLET_HIT_KILL_CHANCE {
                     messages.extend(self.killing_bullet_effects(soldier))
-                } else if value < 50 {
-                    messages.extend(self.injuring_bullet_effects(soldier))
                 } else {
-                    messages.extend(self.proximity_bullet_effects(soldier, &distance))
+                    messages.extend(self.injuring_bullet_effects(soldier))
                 }
-            } else {
-                messages.extend(self.proximity_bullet_effects(soldier, &distance))
+                break;
             }
+
+            passed = from_shooter;
+        }
+
+        // Missing bullet can be stopped by next obstacles
+        if !stopped {
+            if let Some(point) = self.obstacle_stopping_bullet(&obstacles, passed, f32::MAX) {
+                path = bullet_fire.until(point);
+            }
+        }
+
+        for soldier in self.soldiers_feeling_bullet_fire() {
+            if Some(soldier.uuid()) == hit {
+                continue;
+            }
+
+            let point = soldier.world_point();
+            let nearest = nearest_point_on_segment(&point, path.from(), path.to());
+            let distance = distance_between_points(&point, &nearest);
+            messages.extend(self.proximity_bullet_effects(soldier, &distance))
         }
 
         messages
     }
 
+    fn soldiers_feeling_bullet_fire(&self) -> impl Iterator<Item = &Soldier> {
+        self.battle_state.soldiers().iter().filter(|soldier| {
+            // Simple for now, but if in vehicle, don't be affected
+            soldier.can_feel_bullet_fire()
+                && self
+                    .battle_state
+                    .soldier_vehicle_place(soldier.uuid())
+                    .is_none()
+        })
+    }
+
+    /// Soldiers crossed by bullet path, from the nearest of shooter, with their distance (in
+    /// pixels) from shooter and their distance from path
+    fn soldiers_on_bullet_path(&self, bullet_fire: &BulletFire) -> Vec<(&Soldier, f32, Distance)> {
+        let hit_distance = BULLET_HIT_DISTANCE_MILLIMETERS;
+        let mut soldiers: Vec<(&Soldier, f32, Distance)> = self
+            .soldiers_feeling_bullet_fire()
+            .filter(|soldier| !self.spared_by_fire_of(bullet_fire.side(), soldier))
+            .filter_map(|soldier| {
+                let point = soldier.world_point();
+                let nearest =
+                    nearest_point_on_segment(&point, bullet_fire.from(), bullet_fire.to());
+                let offset = distance_between_points(&point, &nearest);
+                let from_shooter = distance_between_points(bullet_fire.from(), &point);
+                // Shooter himself is not on the path
+                (offset.millimeters() < hit_distance && from_shooter.millimeters() > hit_distance)
+                    .then_some((
+                        soldier,
+                        bullet_fire.from().to_vec2().distance(point.to_vec2()),
+                        offset,
+                    ))
+            })
+            .collect();
+        soldiers.sort_by(|(a, a_distance, _), (b, b_distance, _)| {
+            a_distance
+                .total_cmp(b_distance)
+                .then(a.uuid().0.cmp(&b.uuid().0))
+        });
+        soldiers
+    }
+
+    /// First obstacle between `after` and `before` (in pixels from shooter) which stops the
+    /// bullet. Opaque tiles always stop it, cover tiles according to their coverage.
+    fn obstacle_stopping_bullet(
+        &self,
+        obstacles: &[BulletObstacle],
+        after: f32,
+        before: f32,
+    ) -> Option<WorldPoint> {
+        obstacles
+            .iter()
+            .filter(|obstacle| obstacle.distance > after && obstacle.distance <= before)
+            .find(|obstacle| obstacle.stops(&mut *self.rng()))
+            .map(|obstacle| obstacle.point)
+    }
+
     pub fn killing_bullet_effects(&self, soldier: &Soldier) -> Vec<RunnerMessage> {
         puffin::profile_scope!("KillingBullet", soldier.uuid().to_string());
         let mut messages = self.soldier_die(soldier.uuid());
@@ -165,3 +273,161 @@ impl Runner {
         self.soldier_proximity_bullet(soldier.uuid(), distance)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use battle_core::{
+        game::{weapon::Weapon, Side},
+        state::battle::message::SoldierMessage,
+        types::{SoldierIndex, SquadUuid, WorldPoint},
+    };
+    use rstest::*;
+
+    use crate::runner::testing::{map, runner_on};
+
+    use super::*;
+
+    #[rstest]
+    #[case("ShortGrass", true)]
+    #[case("Hedge", true)]
+    #[case("BrickWall", false)]
+    fn soldier_behind_obstacle_hit(#[case] obstacle: &'static str, #[case] can_be_hit: bool) {
+        // Given a soldier some tiles behind an obstacle column
+        let map = map(|x, _| if x == 20 { obstacle } else { "ShortGrass" });
+        let runner = runner_on(
+            map,
+            vec![Soldier::new(
+                SoldierIndex(0),
+                WorldPoint::new(150., 52.),
+                SquadUuid(0),
+                Side::A,
+                None,
+                vec![],
+            )],
+            vec![],
+            Default::default(),
+        );
+        let weapon = Weapon::MosinNagantM1924(true, None);
+        let bullet_fire = BulletFire::new(
+            WorldPoint::new(20., 52.),
+            WorldPoint::new(180., 52.),
+            weapon.ammunition(),
+            weapon.gun_fire_sound_type(),
+        );
+
+        // When
+        let kills = (0..1000)
+            .filter(|_| {
+                runner
+                    .bullet_fire_effects(&bullet_fire)
+                    .iter()
+                    .any(|message| {
+                        matches!(
+                            message,
+                            RunnerMessage::BattleState(BattleStateMessage::Soldier(
+                                SoldierIndex(0),
+                                SoldierMessage::SetAlive(false),
+                            ))
+                        )
+                    })
+            })
+            .count();
+
+        // Then
+        assert_eq!(kills > 0, can_be_hit, "{} kills", kills);
+    }
+
+    #[rstest]
+    #[case(true, vec![0, 1])]
+    #[case(false, vec![1])]
+    fn friends_spared_without_friendly_fire(
+        #[case] friendly_fire: bool,
+        #[case] expected: Vec<usize>,
+    ) {
+        // Given
+        let soldier = |i, x, side| {
+            Soldier::new(
+                SoldierIndex(i),
+                WorldPoint::new(x, 52.),
+                SquadUuid(i),
+                side,
+                None,
+                vec![],
+            )
+        };
+        let mut runner = runner_on(
+            map(|_, _| "ShortGrass"),
+            vec![soldier(0, 100., Side::A), soldier(1, 120., Side::B)],
+            vec![],
+            Default::default(),
+        );
+        runner.config.friendly_fire = friendly_fire;
+        let weapon = Weapon::MosinNagantM1924(true, None);
+        let bullet_fire = BulletFire::new(
+            WorldPoint::new(20., 52.),
+            WorldPoint::new(180., 52.),
+            weapon.ammunition(),
+            weapon.gun_fire_sound_type(),
+        )
+        .fired_by(Side::A);
+
+        // When
+        let on_path: Vec<usize> = runner
+            .soldiers_on_bullet_path(&bullet_fire)
+            .iter()
+            .map(|(soldier, _, _)| soldier.uuid().0)
+            .collect();
+
+        // Then
+        assert_eq!(on_path, expected);
+    }
+
+    #[rstest]
+    #[case(Side::A, true)]
+    #[case(Side::B, false)]
+    fn same_side_hit_is_fratricide(#[case] shooter_side: Side, #[case] fratricide: bool) {
+        // Given
+        let runner = runner_on(
+            map(|_, _| "ShortGrass"),
+            vec![Soldier::new(
+                SoldierIndex(0),
+                WorldPoint::new(100., 52.),
+                SquadUuid(0),
+                Side::A,
+                None,
+                vec![],
+            )],
+            vec![],
+            Default::default(),
+        );
+        let weapon = Weapon::MosinNagantM1924(true, None);
+        let bullet_fire = BulletFire::new(
+            WorldPoint::new(20., 52.),
+            WorldPoint::new(180., 52.),
+            weapon.ammunition(),
+            weapon.gun_fire_sound_type(),
+        )
+        .fired_by(shooter_side);
+
+        // When
+        let messages: Vec<RunnerMessage> = (0..1000)
+            .flat_map(|_| runner.bullet_fire_effects(&bullet_fire))
+            .collect();
+
+        // Then
+        assert!(messages.iter().any(|message| matches!(
+            message,
+            RunnerMessage::BattleState(BattleStateMessage::Soldier(
+                SoldierIndex(0),
+                SoldierMessage::SetAlive(false),
+            ))
+        )));
+        assert_eq!(
+            messages.iter().any(|message| matches!(
+                message,
+                RunnerMessage::BattleState(BattleStateMessage::AddFratricide(Side::A))
+            )),
+            fratricide
+        );
+    }
+}
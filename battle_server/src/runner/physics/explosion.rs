@@ -1,12 +1,19 @@
 use battle_core::audio::Sound;
+use battle_core::config::{
+    ELEVATION_EXPLOSION_HEIGHT, EXPLOSION_SMOKE_DURATION, EXPLOSION_SMOKE_RAYON,
+    TERRAIN_DESTRUCTION_CHANCE, VEHICLE_FIRE_DURATION, VEHICLE_FIRE_RAYON,
+};
 use battle_core::entity::soldier::Soldier;
 use battle_core::entity::vehicle::Vehicle;
 use battle_core::game::explosive::ExplosiveType;
+use battle_core::map::terrain::TileType;
+use battle_core::physics::event::cloud::{Cloud, CloudType};
 use battle_core::physics::event::explosion::Explosion;
-use battle_core::physics::utils::distance_between_points;
+use battle_core::physics::utils::{distance_between_points, DISTANCE_TO_METERS_COEFFICIENT};
+use battle_core::state::battle::message::BattleStateMessage;
 use battle_core::state::client::ClientStateMessage;
 
-use battle_core::types::Distance;
+use battle_core::types::{Distance, GridPoint};
 use rand::seq::SliceRandom;
 use rand::Rng;
 
@@ -53,6 +60,26 @@ impl Runner {
 
             let distance = distance_between_points(&soldier.world_point(), point);
 
+            if self.spared_by_fire_of(explosion.side(), soldier) {
+                if distance.meters() < 100 {
+                    messages.extend(self.proximity_blast_effects(soldier, distance));
+                }
+                continue;
+            }
+
+            // Ground between explosion and soldier absorb the blast
+            if self.battle_state.map().elevation_masks(
+                point,
+                ELEVATION_EXPLOSION_HEIGHT,
+                &soldier.world_point(),
+                soldier.behavior().posture().height(),
+            ) {
+                if distance.meters() < 100 {
+                    messages.extend(self.proximity_blast_effects(soldier, distance));
+                }
+                continue;
+            }
+
             // TODO : Move into dedicated struct ?
             if let (
                 Some(direct_death_rayons),
@@ -70,25 +97,27 @@ impl Runner {
                     .get(explosion.type_()),
             ) {
                 if &distance < direct_death_rayons {
+                    messages.extend(self.casualty_effects(explosion.side(), soldier));
                     messages.extend(self.killing_blast_effects(soldier));
                 } else if &distance <= regressive_death_rayon
                     || &distance <= regressive_injured_rayon
                 {
-                    let mut rng = rand::thread_rng();
                     let percent = 1.0
                         - (distance.millimeters() as f32
                             / regressive_death_rayon.millimeters() as f32);
-                    let roll = rng.gen_range(0.0..1.0);
+                    let roll = self.rng().gen_range(0.0..1.0);
 
                     if roll <= percent {
+                        messages.extend(self.casualty_effects(explosion.side(), soldier));
                         messages.extend(self.killing_blast_effects(soldier));
                     } else {
                         let percent = 1.0
                             - (distance.millimeters() as f32
                                 / regressive_injured_rayon.millimeters() as f32);
-                        let roll = rng.gen_range(0.0..1.0);
+                        let roll = self.rng().gen_range(0.0..1.0);
 
                         if roll <= percent {
+                            messages.extend(self.casualty_effects(explosion.side(), soldier));
                             messages.extend(self.stunning_blast_effects(soldier));
                         } else {
                             messages.extend(self.proximity_blast_effects(soldier, distance));
@@ -101,11 +130,79 @@ impl Runner {
         }
 
         for vehicle in self.battle_state.vehicles() {
-            if vehicle.chassis_shape().contains(point) {
+            // Infantry anti-tank charges effects on vehicle are resolved at impact
+            if vehicle.chassis_shape().contains(point) && !explosive_type.anti_tank_charge() {
                 messages.extend(self.vehicle_shell_impact_effects(vehicle, explosive_type));
             }
         }
 
+        messages.extend(self.explosion_terrain_effects(explosion));
+        messages.push(RunnerMessage::BattleState(BattleStateMessage::PushCloud(
+            Cloud::new(
+                *point,
+                CloudType::Smoke,
+                Distance::from_meters(EXPLOSION_SMOKE_RAYON),
+                EXPLOSION_SMOKE_DURATION,
+            ),
+        )));
+        messages
+    }
+
+    /// Destructible tiles in explosion direct death rayon can be degraded, and ground under
+    /// explosion become a crater
+    fn explosion_terrain_effects(&self, explosion: &Explosion) -> Vec<RunnerMessage> {
+        let rayon = match self
+            .config
+            .explosive_direct_death_rayon
+            .get(explosion.type_())
+        {
+            Some(rayon) => rayon,
+            None => return vec![],
+        };
+        let map = self.battle_state.map();
+        let center = map.grid_point_from_world_point(explosion.point());
+        let tiles_rayon = (rayon.millimeters() as f32
+            / 1000.
+            / DISTANCE_TO_METERS_COEFFICIENT
+            / map.tile_width() as f32)
+            .ceil() as i32;
+
+        let mut messages = vec![];
+        if map
+            .terrain_tile(&center)
+            .map(|tile| tile.type_().craterable())
+            .unwrap_or(false)
+        {
+            messages.push(RunnerMessage::BattleState(BattleStateMessage::SetTileType(
+                center,
+                TileType::Crater,
+            )));
+        }
+
+        for y in (center.y - tiles_rayon)..=(center.y + tiles_rayon) {
+            for x in (center.x - tiles_rayon)..=(center.x + tiles_rayon) {
+                let grid_point = GridPoint::new(x, y);
+                let degraded = match map
+                    .terrain_tile(&grid_point)
+                    .and_then(|tile| tile.type_().degraded())
+                {
+                    Some(degraded) => degraded,
+                    None => continue,
+                };
+                let distance = distance_between_points(
+                    explosion.point(),
+                    &map.world_point_from_grid_point(grid_point),
+                );
+                if &distance <= rayon
+                    && self.rng().gen_range(0.0..1.0) <= TERRAIN_DESTRUCTION_CHANCE
+                {
+                    messages.push(RunnerMessage::BattleState(BattleStateMessage::SetTileType(
+                        grid_point, degraded,
+                    )));
+                }
+            }
+        }
+
         messages
     }
 
@@ -159,12 +256,19 @@ impl Runner {
         puffin::profile_scope!("vehicle_shell_impact_effects", vehicle.uuid().to_string());
         // TODO effects on soldiers (with a real explosive algorithm)
         let pick_from = vec![Sound::MetalHit1];
-        vec![RunnerMessage::ClientsState(
-            ClientStateMessage::PlayBattleSound(
+        vec![
+            RunnerMessage::ClientsState(ClientStateMessage::PlayBattleSound(
                 *pick_from
                     .choose(&mut rand::thread_rng())
                     .expect("Must one be chosen"),
-            ),
-        )]
+            )),
+            // Hit vehicle is burning
+            RunnerMessage::BattleState(BattleStateMessage::PushCloud(Cloud::new(
+                vehicle.world_point(),
+                CloudType::Fire,
+                Distance::from_meters(VEHICLE_FIRE_RAYON),
+                VEHICLE_FIRE_DURATION,
+            ))),
+        ]
     }
 }
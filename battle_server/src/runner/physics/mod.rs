@@ -1,5 +1,10 @@
+use battle_core::{
+    entity::soldier::Soldier, game::Side, state::battle::message::BattleStateMessage,
+};
+
 use super::{message::RunnerMessage, Runner};
 
+mod armor;
 mod bullet;
 mod explosion;
 
@@ -15,4 +20,20 @@ impl Runner {
 
         messages
     }
+
+    /// Soldier is spared by a fire of `side` when friendly fire is disabled
+    fn spared_by_fire_of(&self, side: Option<&Side>, soldier: &Soldier) -> bool {
+        !self.config.friendly_fire && side == Some(soldier.side())
+    }
+
+    /// Count soldier casualty as fratricide if caused by its own side
+    fn casualty_effects(&self, side: Option<&Side>, soldier: &Soldier) -> Vec<RunnerMessage> {
+        if side == Some(soldier.side()) {
+            return vec![RunnerMessage::BattleState(
+                BattleStateMessage::AddFratricide(*soldier.side()),
+            )];
+        }
+
+        vec![]
+    }
 }
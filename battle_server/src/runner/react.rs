@@ -51,6 +51,10 @@ impl Runner {
                 soldier.set_behavior(behavior);
                 soldier.set_order(order);
             }
+            // Visibilities depend on terrain and environment, compute all of them again
+            SideEffect::RefreshTerrain | SideEffect::RefreshVisibilities => {
+                self.visibility_stamps().clear()
+            }
             // Server ignore this side effect because concern Gui only
             SideEffect::RefreshEntityAnimation(_) => {}
         }
@@ -0,0 +1,77 @@
+use battle_core::{
+    config::{
+        SMOKE_GRENADE_DISPERSION, SMOKE_GRENADE_DURATION, SMOKE_GRENADE_MAXIMUM_THROW,
+        SMOKE_GRENADE_RAYON,
+    },
+    order::Order,
+    physics::{
+        event::cloud::{Cloud, CloudType},
+        utils::{distance_between_points, DISTANCE_TO_METERS_COEFFICIENT},
+    },
+    state::battle::message::{BattleStateMessage, SoldierMessage},
+    types::{Distance, SoldierIndex, WorldPoint},
+};
+use rand::Rng;
+
+use super::{message::RunnerMessage, Runner};
+
+impl Runner {
+    /// Throw a smoke grenade (if any) in the direction of the point, then end the order
+    pub fn throw_smoke_update(
+        &self,
+        soldier_index: &SoldierIndex,
+        point: &WorldPoint,
+    ) -> Vec<RunnerMessage> {
+        let soldier = self.battle_state.soldier(*soldier_index);
+        // Grenade already thrown
+        if !matches!(soldier.order(), Order::SmokeScreen(_)) {
+            return vec![];
+        }
+
+        let mut messages = vec![RunnerMessage::BattleState(BattleStateMessage::Soldier(
+            *soldier_index,
+            SoldierMessage::SetOrder(Order::Idle),
+        ))];
+
+        if soldier.smoke_grenades() > 0 {
+            let landing = self.smoke_grenade_landing_point(&soldier.world_point(), point);
+            messages.extend(vec![
+                RunnerMessage::BattleState(BattleStateMessage::Soldier(
+                    *soldier_index,
+                    SoldierMessage::ThrowSmokeGrenade,
+                )),
+                RunnerMessage::BattleState(BattleStateMessage::PushCloud(Cloud::new(
+                    landing,
+                    CloudType::Smoke,
+                    Distance::from_meters(SMOKE_GRENADE_RAYON),
+                    SMOKE_GRENADE_DURATION,
+                ))),
+            ]);
+        }
+
+        messages
+    }
+
+    fn smoke_grenade_landing_point(&self, from: &WorldPoint, to: &WorldPoint) -> WorldPoint {
+        let maximum = Distance::from_meters(SMOKE_GRENADE_MAXIMUM_THROW);
+        let distance = distance_between_points(from, to);
+        let aimed = if distance > maximum {
+            let ratio = maximum.millimeters() as f32 / distance.millimeters() as f32;
+            WorldPoint::new(
+                from.x + (to.x - from.x) * ratio,
+                from.y + (to.y - from.y) * ratio,
+            )
+        } else {
+            *to
+        };
+
+        let mut rng = self.rng();
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        let dispersion =
+            rng.gen_range(0.0..SMOKE_GRENADE_DISPERSION) / DISTANCE_TO_METERS_COEFFICIENT;
+        WorldPoint::new(
+            aimed.x + angle.cos() * dispersion,
+            aimed.y + angle.sin() * dispersion,
+        )
+    }
+}
@@ -0,0 +1,235 @@
+use battle_core::{
+    behavior::Behavior,
+    config::SNIPER_RELOCATION_SHOTS,
+    entity::soldier::Soldier,
+    game::{cover::CoverFinder, role::SoldierRole},
+    order::Order,
+    physics::path::{find_path, Direction, PathMode},
+    state::battle::message::{BattleStateMessage, SoldierMessage},
+    types::{WorldPath, WorldPaths},
+};
+
+use super::{message::RunnerMessage, Runner};
+
+impl Runner {
+    /// Sniper sneaks to another position keeping sight on its target after some shots (or
+    /// once spotted), then resumes its order. Squad leaders stay with their squad.
+    pub fn sniper_relocation_messages(&self, soldier: &Soldier) -> Vec<RunnerMessage> {
+        if soldier.role() != &SoldierRole::Sniper
+            || self.battle_state.squad(soldier.squad_uuid()).leader() == soldier.uuid()
+        {
+            return vec![];
+        }
+
+        // Already relocating
+        if soldier.order().then().is_some() {
+            return vec![];
+        }
+
+        // Current shot is not counted yet
+        let spotted = self
+            .battle_state
+            .soldier_is_visible_by_side(soldier, &soldier.side().opposite());
+        if soldier.position_shots() + 1 < SNIPER_RELOCATION_SHOTS && !spotted {
+            return vec![];
+        }
+
+        let target_point = match soldier.behavior() {
+            Behavior::EngageSoldier(soldier_index) => {
+                self.battle_state.soldier(*soldier_index).world_point()
+            }
+            Behavior::EngageVehicle(vehicle_index) => {
+                self.battle_state.vehicle(*vehicle_index).world_point()
+            }
+            Behavior::SuppressFire(point) => *point,
+            _ => return vec![],
+        };
+
+        let map = self.battle_state.map();
+        let grid_point = map.grid_point_from_world_point(&soldier.world_point());
+        let new_grid_point = match CoverFinder::new(&self.battle_state, &self.config)
+            .exclude_grid_points(vec![grid_point])
+            .find_better_cover_point_from_point(soldier, &target_point, true)
+        {
+            Some(new_grid_point) => new_grid_point,
+            None => return vec![],
+        };
+
+        // Sniper must be able to fire on its target from there
+        let mut relocated = soldier.clone();
+        relocated.set_world_point(map.world_point_from_grid_point(new_grid_point));
+        if !self.battle_state.point_is_visible_by_soldier(
+            &self.config,
+            &relocated,
+            &target_point,
+            self.config.visibility_by_last_frame_shoot_distance,
+        ) {
+            return vec![];
+        }
+
+        let grid_points_path = match find_path(
+            map,
+            &grid_point,
+            &new_grid_point,
+            true,
+            &PathMode::Walk,
+            &Some(Direction::from_angle(&soldier.get_looking_direction())),
+        ) {
+            Some(grid_points_path) => grid_points_path,
+            None => return vec![],
+        };
+        let world_path = WorldPath::new(
+            grid_points_path
+                .iter()
+                .map(|p| map.world_point_from_grid_point(*p))
+                .collect(),
+        );
+
+        vec![RunnerMessage::BattleState(BattleStateMessage::Soldier(
+            soldier.uuid(),
+            SoldierMessage::SetOrder(Order::SneakTo(
+                WorldPaths::new(vec![world_path]),
+                Some(Box::new(soldier.order().clone())),
+            )),
+        ))]
+    }
+
+    /// Sniper sneaks to its new position, squad orders must not interrupt it
+    pub fn sniper_is_relocating(&self, soldier: &Soldier) -> bool {
+        soldier.role() == &SoldierRole::Sniper
+            && matches!(soldier.order(), Order::SneakTo(_, Some(_)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use battle_core::{
+        entity::soldier::WeaponClass,
+        game::Side,
+        types::{SoldierIndex, SquadUuid, WorldPoint},
+    };
+    use rstest::*;
+
+    use crate::runner::testing::runner;
+
+    use super::*;
+
+    #[rstest]
+    // First shot of a sniper not yet spotted
+    #[case(0, false, false, false)]
+    // Last shot before relocation
+    #[case(SNIPER_RELOCATION_SHOTS - 1, false, false, true)]
+    // Spotted sniper relocates at once
+    #[case(0, true, false, true)]
+    // Sniper leading its squad stays with it
+    #[case(SNIPER_RELOCATION_SHOTS - 1, true, true, false)]
+    fn sniper_relocates_after_shots_or_once_spotted(
+        #[case] previous_shots: u32,
+        #[case] spotted: bool,
+        #[case] leader: bool,
+        #[case] expected: bool,
+    ) {
+        // Given a sniper engaging an opponent
+        let rifleman_squad = if leader { SquadUuid(2) } else { SquadUuid(0) };
+        let mut runner = runner(vec![
+            Soldier::new(
+                SoldierIndex(0),
+                WorldPoint::new(30., 30.),
+                rifleman_squad,
+                Side::A,
+                None,
+                vec![],
+            ),
+            Soldier::new(
+                SoldierIndex(1),
+                WorldPoint::new(50., 50.),
+                SquadUuid(0),
+                Side::A,
+                None,
+                vec![],
+            )
+            .with_role(SoldierRole::Sniper),
+            Soldier::new(
+                SoldierIndex(2),
+                WorldPoint::new(150., 50.),
+                SquadUuid(1),
+                Side::B,
+                None,
+                vec![],
+            ),
+        ]);
+        let mut messages = vec![RunnerMessage::BattleState(BattleStateMessage::Soldier(
+            SoldierIndex(1),
+            SoldierMessage::SetBehavior(Behavior::EngageSoldier(SoldierIndex(2))),
+        ))];
+        for _ in 0..previous_shots {
+            messages.push(RunnerMessage::BattleState(BattleStateMessage::Soldier(
+                SoldierIndex(1),
+                SoldierMessage::WeaponShot(WeaponClass::Main),
+            )));
+        }
+        runner.react(&messages);
+        if spotted {
+            let visibilities = runner.update_visibilities();
+            runner.react(&visibilities);
+        }
+
+        // When
+        let sniper = runner.battle_state.soldier(SoldierIndex(1));
+        let messages = runner.sniper_relocation_messages(sniper);
+
+        // Then
+        assert_eq!(!messages.is_empty(), expected);
+        if expected {
+            assert!(matches!(
+                messages[0],
+                RunnerMessage::BattleState(BattleStateMessage::Soldier(
+                    SoldierIndex(1),
+                    SoldierMessage::SetOrder(Order::SneakTo(_, _))
+                ))
+            ));
+        }
+    }
+
+    #[rstest]
+    #[case(false)]
+    #[case(true)]
+    fn relocating_sniper_keeps_sneaking_while_leader_engages(#[case] relocating: bool) {
+        // Given a squad leader and its sniper seeing an opponent
+        let soldier = |i, point, squad, side| {
+            Soldier::new(SoldierIndex(i), point, SquadUuid(squad), side, None, vec![])
+        };
+        let mut runner = runner(vec![
+            soldier(0, WorldPoint::new(30., 30.), 0, Side::A),
+            soldier(1, WorldPoint::new(50., 50.), 0, Side::A).with_role(SoldierRole::Sniper),
+            soldier(2, WorldPoint::new(150., 50.), 1, Side::B),
+        ]);
+        let visibilities = runner.update_visibilities();
+        runner.react(&visibilities);
+        if relocating {
+            let sneak_to = Order::SneakTo(
+                WorldPaths::new(vec![WorldPath::new(vec![WorldPoint::new(60., 50.)])]),
+                Some(Box::new(Order::Idle)),
+            );
+            runner.react(&vec![RunnerMessage::BattleState(
+                BattleStateMessage::Soldier(SoldierIndex(1), SoldierMessage::SetOrder(sneak_to)),
+            )]);
+        }
+
+        // When
+        let leader = runner.battle_state.soldier(SoldierIndex(0));
+        let messages = runner.propagate_behavior(leader, &Behavior::EngageSoldier(SoldierIndex(2)));
+
+        // Then
+        let ordered = messages.iter().any(|message| {
+            matches!(
+                message,
+                RunnerMessage::BattleState(BattleStateMessage::Soldier(
+                    SoldierIndex(1),
+                    SoldierMessage::SetOrder(_)
+                ))
+            )
+        });
+        assert_eq!(ordered, !relocating);
+    }
+}
@@ -0,0 +1,81 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{atomic::AtomicBool, Arc},
+};
+
+use battle_core::{
+    config::ServerConfig,
+    entity::{soldier::Soldier, vehicle::Vehicle},
+    game::flag::{Flag, FlagsOwnership},
+    map::{decor::Decor, terrain::TerrainTile, Map},
+    state::battle::{phase::Phase, BattleState},
+    types::SoldiersOnBoard,
+};
+
+use super::Runner;
+
+/// 40x40 tiles (5 pixels each) map with tile type id given by its position
+pub fn map(tile: impl Fn(u32, u32) -> &'static str) -> Map {
+    map_with_flags(tile, vec![])
+}
+
+/// Same as [map] with given flags
+pub fn map_with_flags(tile: impl Fn(u32, u32) -> &'static str, flags: Vec<Flag>) -> Map {
+    let terrain_tiles = (0..40 * 40)
+        .map(|i| {
+            let (x, y) = (i % 40, i / 40);
+            TerrainTile::from_str_id(tile(x, y), 5, 5, 0.1, 0.1, x, y, 0, 0).unwrap()
+        })
+        .collect();
+    Map::new(
+        "TestMap".to_string(),
+        PathBuf::from("."),
+        PathBuf::from("."),
+        PathBuf::from("."),
+        vec![],
+        vec![],
+        40,
+        40,
+        terrain_tiles,
+        5,
+        5,
+        Decor::new(vec![], vec![]),
+        flags,
+    )
+}
+
+/// Runner in battle phase on a short grass map
+pub fn runner(soldiers: Vec<Soldier>) -> Runner {
+    runner_on(map(|_, _| "ShortGrass"), soldiers, vec![], HashMap::new())
+}
+
+/// Runner in battle phase on given map
+pub fn runner_on(
+    map: Map,
+    soldiers: Vec<Soldier>,
+    vehicles: Vec<Vehicle>,
+    soldier_on_board: SoldiersOnBoard,
+) -> Runner {
+    let mut battle_state = BattleState::new(
+        0,
+        map,
+        soldiers,
+        vehicles,
+        soldier_on_board,
+        Phase::Battle,
+        FlagsOwnership::empty(),
+    );
+    battle_state.resolve();
+
+    let (_, input) = crossbeam_channel::unbounded();
+    let (output, _) = crossbeam_channel::unbounded();
+    Runner::new(
+        ServerConfig::default(),
+        input,
+        output,
+        Arc::new(AtomicBool::new(false)),
+        battle_state,
+    )
+    .seed(0)
+}
@@ -1,4 +1,5 @@
 use battle_core::state::battle::message::BattleStateMessage;
+use battle_core::types::{Distance, WorldPoint};
 
 use crate::runner::message::RunnerMessage;
 
@@ -9,11 +10,19 @@ impl Runner {
         let frame_i = self.battle_state.frame_i();
         puffin::profile_scope!("tick", format!("frame {frame_i}"));
         self.inputs()?;
+        if !self.lockstep_ready() {
+            return Ok(());
+        }
+        self.lockstep_turn()?;
 
         let mut messages = vec![RunnerMessage::BattleState(
             BattleStateMessage::IncrementFrameI,
         )];
         messages.extend(self.tick_phase());
+        messages.extend(self.tick_environment());
+        messages.extend(self.tick_ais());
+        messages.extend(self.tick_bounding_overwatches());
+        messages.extend(self.tick_ambushes());
         messages.extend(self.tick_morale());
         messages.extend(self.tick_victory());
         messages.extend(self.tick_flags());
@@ -26,10 +35,50 @@ impl Runner {
         self.clean();
 
         self.outputs(&messages)?;
+        self.lockstep_checksum()?;
         Ok(())
     }
 
     pub fn clean(&mut self) {
         self.battle_state.clean(None);
+
+        // Visibilities depend on clouds, sight lines crossing appeared, changed or disappeared
+        // clouds must be computed again
+        let clouds_stamp = self.current_clouds_stamp();
+        let dirty_areas: Vec<(WorldPoint, Distance)> = self
+            .clouds_stamp
+            .iter()
+            .filter(|stamp| !clouds_stamp.contains(stamp))
+            .chain(
+                clouds_stamp
+                    .iter()
+                    .filter(|stamp| !self.clouds_stamp.contains(stamp)),
+            )
+            .map(|stamp| (stamp.point, stamp.rayon))
+            .collect();
+        self.visibility_dirty_areas().extend(dirty_areas);
+        self.clouds_stamp = clouds_stamp;
+    }
+
+    fn current_clouds_stamp(&self) -> Vec<CloudStamp> {
+        self.battle_state
+            .clouds()
+            .iter()
+            .map(|cloud| CloudStamp {
+                start: cloud.start(),
+                step: cloud.step(),
+                point: *cloud.point(),
+                rayon: *cloud.rayon(),
+            })
+            .collect()
     }
 }
+
+/// Cloud properties which visibilities depend on
+#[derive(Clone, PartialEq)]
+pub struct CloudStamp {
+    start: u64,
+    step: u64,
+    point: WorldPoint,
+    rayon: Distance,
+}
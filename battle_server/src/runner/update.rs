@@ -55,6 +55,8 @@ impl Runner {
                 vec![]
             }
             Behavior::EngageSoldier(target) => self.engage_update(&soldier_index, target),
+            Behavior::EngageVehicle(target) => self.engage_vehicle_update(&soldier_index, target),
+            Behavior::ThrowSmoke(point) => self.throw_smoke_update(&soldier_index, point),
             Behavior::Dead => vec![],
             Behavior::Unconscious => vec![],
         });
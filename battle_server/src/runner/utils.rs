@@ -26,7 +26,8 @@ impl Runner {
             Behavior::DriveTo(_) => None,
             Behavior::RotateTo(_) => None,
             Behavior::SuppressFire(point) => Some(angle(point, reference_point)),
-            Behavior::EngageSoldier(_) => None,
+            Behavior::ThrowSmoke(point) => Some(angle(point, reference_point)),
+            Behavior::EngageSoldier(_) | Behavior::EngageVehicle(_) => None,
             // TODO: keep angle for dead/unconscious soldiers
             Behavior::Dead | Behavior::Unconscious => None,
         }
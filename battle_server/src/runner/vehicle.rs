@@ -5,6 +5,7 @@ use battle_core::{
         VEHICLE_DRIVE_ORIENTATION_ADVANCE_TOLERANCE_DIFF,
         VEHICLE_DRIVE_ORIENTATION_TARGET_TOLERANCE_COEFFICIENT,
     },
+    graphics::vehicle::VehicleGraphicInfos,
     state::battle::message::{BattleStateMessage, SoldierMessage, VehicleMessage},
     types::*,
     utils::{angle, short_angle, short_angle_way, AngleWay},
@@ -70,6 +71,7 @@ impl Runner {
                 vehicle_index,
                 VehicleMessage::SetWorldPosition(new_point),
             )));
+            messages.extend(self.crush_update(vehicle_index, &new_point));
         }
 
         // Next point reached ?
@@ -93,6 +95,55 @@ impl Runner {
         messages
     }
 
+    /// Vehicle destroy crushable tiles under its chassis
+    fn crush_update(&self, vehicle_index: VehicleIndex, point: &WorldPoint) -> Vec<RunnerMessage> {
+        let map = self.battle_state.map();
+        let vehicle = self.battle_state.vehicle(vehicle_index);
+        let chassis = VehicleGraphicInfos::from_type(vehicle.type_())
+            .chassis_physics()
+            .from_point(*point)
+            .centered()
+            .rotate(vehicle.chassis_orientation());
+        let corners: Vec<GridPoint> = [
+            chassis.top_left,
+            chassis.top_right,
+            chassis.bottom_right,
+            chassis.bottom_left,
+        ]
+        .iter()
+        .map(|corner| map.grid_point_from_world_point(corner))
+        .collect();
+        let (min_x, max_x) = (
+            corners.iter().map(|p| p.x).min().unwrap_or_default(),
+            corners.iter().map(|p| p.x).max().unwrap_or_default(),
+        );
+        let (min_y, max_y) = (
+            corners.iter().map(|p| p.y).min().unwrap_or_default(),
+            corners.iter().map(|p| p.y).max().unwrap_or_default(),
+        );
+
+        let mut messages = vec![];
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let grid_point = GridPoint::new(x, y);
+                if !chassis.contains(&map.world_point_from_grid_point(grid_point)) {
+                    continue;
+                }
+                if let Some(tile) = map.terrain_tile(&grid_point) {
+                    if let (true, Some(degraded)) =
+                        (tile.type_().crushable(), tile.type_().degraded())
+                    {
+                        messages.push(RunnerMessage::BattleState(BattleStateMessage::SetTileType(
+                            grid_point, degraded,
+                        )));
+                    }
+                }
+            }
+        }
+
+        messages
+    }
+
     pub fn rotate_update(&self, soldier_index: SoldierIndex, angle: &Angle) -> Vec<RunnerMessage> {
         let vehicle_index = self
             .battle_state
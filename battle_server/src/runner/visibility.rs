@@ -1,21 +1,50 @@
 use rayon::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::MutexGuard;
 
 use battle_core::{
     audio::Sound,
+    behavior::Behavior,
+    config::{ServerConfig, VISIBILITY_INDEX_CELL_SIZE},
     entity::soldier::Soldier,
     game::Side,
     order::Order,
-    physics::visibility::Visibility,
+    physics::{
+        spatial::SpatialIndex,
+        utils::{distance_between_points, nearest_point_on_segment},
+        visibility::Visibility,
+    },
     state::{
         battle::message::{BattleStateMessage, SoldierMessage},
         client::ClientStateMessage,
     },
-    types::SoldierIndex,
+    types::{Distance, SoldierIndex, WorldPoint},
 };
 
 use super::{message::RunnerMessage, Runner};
 
+/// Soldier properties which its visibilities depend on. Visibilities of a soldier are
+/// computed again only when they change.
+#[derive(Clone, PartialEq)]
+pub struct VisibilityStamp {
+    point: WorldPoint,
+    behavior: Behavior,
+    can_seek: bool,
+    recently_shot: bool,
+}
+
+impl VisibilityStamp {
+    pub fn new(frame_i: u64, config: &ServerConfig, soldier: &Soldier) -> Self {
+        Self {
+            point: soldier.world_point(),
+            behavior: soldier.behavior().clone(),
+            can_seek: soldier.can_seek(),
+            recently_shot: soldier.last_shoot_frame_i() + config.visibility_by_last_frame_shoot
+                >= frame_i,
+        }
+    }
+}
+
 impl Runner {
     pub fn tick_visibilities(&self) -> Vec<RunnerMessage> {
         puffin::profile_scope!("tick_visibilities");
@@ -32,72 +61,125 @@ impl Runner {
         messages
     }
 
+    /// Compute visibilities of pairs of soldiers near enough where at least one of them changed
+    /// since last computing, where sight line cross a changed cloud (or which are not known
+    /// yet). Only changed and removed pairs are sent.
     pub fn update_visibilities(&self) -> Vec<RunnerMessage> {
-        let side_a_soldiers: Vec<&Soldier> = self
-            .battle_state
-            .soldiers()
+        let frame_i = *self.battle_state.frame_i();
+        let soldiers = self.battle_state.soldiers();
+        let maximum_distance = self.config.visibility_maximum_distance;
+        let dirty_areas = std::mem::take(&mut *self.visibility_dirty_areas());
+        let stamps: HashMap<SoldierIndex, VisibilityStamp> = soldiers
             .iter()
-            .filter(|s| s.side() == &Side::A)
+            .map(|soldier| {
+                (
+                    soldier.uuid(),
+                    VisibilityStamp::new(frame_i, &self.config, soldier),
+                )
+            })
             .collect();
-        let side_b_soldiers: Vec<&Soldier> = self
-            .battle_state
-            .soldiers()
+        let changed: HashSet<SoldierIndex> = {
+            let previous = self.visibility_stamps();
+            stamps
+                .iter()
+                .filter(|(soldier_index, stamp)| previous.get(soldier_index) != Some(stamp))
+                .map(|(soldier_index, _)| *soldier_index)
+                .collect()
+        };
+
+        let side_a_index = SpatialIndex::from_soldiers(
+            VISIBILITY_INDEX_CELL_SIZE,
+            soldiers.iter().filter(|s| s.side() == &Side::A),
+        );
+        let side_b_index = SpatialIndex::from_soldiers(
+            VISIBILITY_INDEX_CELL_SIZE,
+            soldiers.iter().filter(|s| s.side() == &Side::B),
+        );
+
+        let visibilities = self.battle_state.visibilities();
+        let changed_visibilities: HashMap<(SoldierIndex, SoldierIndex), Visibility> = soldiers
             .iter()
-            .filter(|s| s.side() == &Side::B)
+            .filter(|s| s.can_seek())
+            .map(|s| s.uuid())
+            .collect::<Vec<SoldierIndex>>()
+            .into_par_iter()
+            .flat_map(|soldier_index| {
+                let soldier = self.battle_state.soldier(soldier_index);
+                let opponents = match soldier.side() {
+                    Side::A => &side_b_index,
+                    Side::B => &side_a_index,
+                    Side::All => return vec![],
+                };
+                let opponents = match &maximum_distance {
+                    Some(distance) => opponents.around(&soldier.world_point(), distance),
+                    None => opponents.all(),
+                };
+                opponents
+                    .into_iter()
+                    .filter(|other_index| {
+                        changed.contains(&soldier_index)
+                            || changed.contains(other_index)
+                            || visibilities.get(&(soldier_index, *other_index)).is_none()
+                            || crosses_areas(
+                                &dirty_areas,
+                                &soldier.world_point(),
+                                &self.battle_state.soldier(*other_index).world_point(),
+                            )
+                    })
+                    .map(|other_index| {
+                        (
+                            (soldier_index, other_index),
+                            Visibility::between_soldiers(
+                                frame_i,
+                                &self.config,
+                                soldier,
+                                self.battle_state.soldier(other_index),
+                                self.battle_state.map(),
+                                self.battle_state.clouds(),
+                            ),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
             .collect();
 
-        let from_side_a_visibilities: HashMap<(SoldierIndex, SoldierIndex), Visibility> =
-            side_a_soldiers
-                .iter()
-                .map(|s| s.uuid())
-                .collect::<Vec<SoldierIndex>>()
-                .into_par_iter()
-                .flat_map(|i| self.soldier_visibilities(i, &side_b_soldiers))
-                .collect();
-        let from_side_b_visibilities: HashMap<(SoldierIndex, SoldierIndex), Visibility> =
-            side_b_soldiers
-                .iter()
-                .map(|s| s.uuid())
-                .collect::<Vec<SoldierIndex>>()
-                .into_par_iter()
-                .flat_map(|i| self.soldier_visibilities(i, &side_a_soldiers))
-                .collect();
-
-        let visibilities = from_side_a_visibilities
-            .into_iter()
-            .chain(from_side_b_visibilities)
+        let removed: Vec<(SoldierIndex, SoldierIndex)> = visibilities
+            .all()
+            .keys()
+            .filter(|(from, to)| {
+                let from = self.battle_state.soldier(*from);
+                let to = self.battle_state.soldier(*to);
+                !from.can_seek()
+                    || maximum_distance.is_some_and(|maximum_distance| {
+                        distance_between_points(&from.world_point(), &to.world_point())
+                            > maximum_distance
+                    })
+            })
+            .copied()
             .collect();
+
+        *self.visibility_stamps() = stamps;
+        if changed_visibilities.is_empty() && removed.is_empty() {
+            return vec![];
+        }
+
         vec![RunnerMessage::BattleState(
-            BattleStateMessage::SetVisibilities(visibilities),
+            BattleStateMessage::UpdateVisibilities(changed_visibilities, removed),
         )]
     }
 
-    pub fn soldier_visibilities(
-        &self,
-        soldier_index: SoldierIndex,
-        other_soldiers: &Vec<&Soldier>,
-    ) -> HashMap<(SoldierIndex, SoldierIndex), Visibility> {
-        let mut visibilities = HashMap::new();
-        let soldier = self.battle_state.soldier(soldier_index);
-
-        if !soldier.can_seek() {
-            return visibilities;
-        }
-
-        for other_soldier in other_soldiers {
-            visibilities.insert(
-                (soldier.uuid(), other_soldier.uuid()),
-                Visibility::between_soldiers(
-                    *self.battle_state.frame_i(),
-                    &self.config,
-                    soldier,
-                    other_soldier,
-                    self.battle_state.map(),
-                ),
-            );
-        }
+    /// Soldiers properties used at last visibilities computing
+    pub fn visibility_stamps(&self) -> MutexGuard<'_, HashMap<SoldierIndex, VisibilityStamp>> {
+        self.visibility_stamps
+            .lock()
+            .expect("Runner visibility stamps mutex must not be poisoned")
+    }
 
-        visibilities
+    /// Areas (point and rayon) where clouds changed since last visibilities computing
+    pub fn visibility_dirty_areas(&self) -> MutexGuard<'_, Vec<(WorldPoint, Distance)>> {
+        self.visibility_dirty_areas
+            .lock()
+            .expect("Runner visibility dirty areas mutex must not be poisoned")
     }
 
     fn update_orders_due_to_visibilities(&self) -> Vec<RunnerMessage> {
@@ -112,7 +194,8 @@ impl Runner {
                     | Order::SneakTo(_, _)
                     | Order::Defend(_)
                     | Order::Hide(_)
-                    | Order::SuppressFire(_) => {}
+                    | Order::SuppressFire(_)
+                    | Order::SmokeScreen(_) => {}
                     Order::EngageSquad(squad_uuid) => {
                         let engaged_squad = self.battle_state.squad(*squad_uuid);
                         if !engaged_squad
@@ -141,3 +224,10 @@ impl Runner {
         messages
     }
 }
+
+/// True if segment between points cross one of areas
+fn crosses_areas(areas: &[(WorldPoint, Distance)], from: &WorldPoint, to: &WorldPoint) -> bool {
+    areas.iter().any(|(point, rayon)| {
+        &distance_between_points(point, &nearest_point_on_segment(point, from, to)) <= rayon
+    })
+}
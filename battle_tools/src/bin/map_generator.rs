@@ -0,0 +1,50 @@
+use std::{path::PathBuf, process};
+
+use battle_tools::{generator::Generator, lint};
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+#[structopt(name = "map_generator")]
+pub struct Opt {
+    map_name: String,
+    #[structopt(long, default_value = "0")]
+    seed: u64,
+    #[structopt(long, default_value = "200")]
+    width: u32,
+    #[structopt(long, default_value = "200")]
+    height: u32,
+    #[structopt(long, default_value = "0.2")]
+    forest_density: f32,
+    #[structopt(long, default_value = "2")]
+    villages: u32,
+    #[structopt(long, default_value = "2")]
+    roads: u32,
+    #[structopt(long, default_value = "1")]
+    rivers: u32,
+    #[structopt(long, default_value = "./resources", parse(from_os_str))]
+    resources: PathBuf,
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    let generator = Generator::new(&opt.map_name, opt.seed)
+        .size(opt.width, opt.height)
+        .forest_density(opt.forest_density)
+        .villages(opt.villages)
+        .roads(opt.roads)
+        .rivers(opt.rivers);
+
+    if let Err(error) = generator.write(&opt.resources) {
+        eprintln!("Map generation failed : {}", error);
+        process::exit(1)
+    }
+
+    let problems = lint::lint_map(&opt.map_name, &opt.resources);
+    for problem in &problems {
+        println!("{}", problem);
+    }
+    if problems.iter().any(|p| p.is_error()) {
+        process::exit(1)
+    }
+    println!("Map {} generated", opt.map_name);
+}
@@ -0,0 +1,32 @@
+use std::{path::PathBuf, process};
+
+use battle_tools::lint;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+#[structopt(name = "map_lint")]
+pub struct Opt {
+    map_name: String,
+    #[structopt(long, default_value = "./resources", parse(from_os_str))]
+    resources: PathBuf,
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    let problems = lint::lint_map(&opt.map_name, &opt.resources);
+
+    for problem in &problems {
+        println!("{}", problem);
+    }
+
+    let errors = problems.iter().filter(|p| p.is_error()).count();
+    println!(
+        "{} : {} error(s), {} warning(s)",
+        opt.map_name,
+        errors,
+        problems.len() - errors
+    );
+    if errors > 0 {
+        process::exit(1)
+    }
+}
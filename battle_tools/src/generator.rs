@@ -0,0 +1,948 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::{Display, Write},
+    fs,
+    path::Path,
+};
+
+use battle_core::map::terrain::TileType;
+use image::{imageops::FilterType, Rgba, RgbaImage};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use strum::IntoEnumIterator;
+
+use crate::lint::HD_RATIO;
+
+const TILE_SIZE: u32 = 5;
+// Trees decor tileset, copied from map1
+const TREES_TILESET: &str = "trees_64x64";
+const TREES_TILESET_MAP: &str = "map1";
+const TREES_TILE_COUNT: u32 = 9;
+// Minimum distance (in tiles) between two trees
+const TREES_SPACING: i32 = 5;
+// Side (in tiles) of noise cells
+const GRASS_NOISE_CELL: u32 = 12;
+const FOREST_NOISE_CELL: u32 = 18;
+const VILLAGE_RADIUS: i32 = 18;
+const VILLAGE_HOUSES: (u32, u32) = (2, 5);
+const HOUSE_WIDTH: (i32, i32) = (8, 15);
+const HOUSE_HEIGHT: (i32, i32) = (6, 11);
+const HOUSE_MARGIN: i32 = 2;
+const ROAD_WIDTH: i32 = 2;
+const RIVER_WIDTH: i32 = 3;
+// Map sides (in tiles) must contain at least one village
+const MINIMUM_SIZE: u32 = (VILLAGE_RADIUS * 2 + HOUSE_WIDTH.1) as u32;
+
+#[derive(Debug)]
+pub enum GeneratorError {
+    Io(std::io::Error),
+    Image(image::ImageError),
+    InvalidSize(u32, u32),
+}
+
+impl From<std::io::Error> for GeneratorError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<image::ImageError> for GeneratorError {
+    fn from(error: image::ImageError) -> Self {
+        Self::Image(error)
+    }
+}
+
+impl Display for GeneratorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GeneratorError::Io(error) => f.write_str(&format!("Io error : {}", error)),
+            GeneratorError::Image(error) => f.write_str(&format!("Image error : {}", error)),
+            GeneratorError::InvalidSize(width, height) => f.write_str(&format!(
+                "Invalid size {}x{} : minimum is {}x{}",
+                width, height, MINIMUM_SIZE, MINIMUM_SIZE
+            )),
+        }
+    }
+}
+
+/// House rectangle (in tiles) with its openings
+struct House {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    floors: u8,
+    door: Vec<(i32, i32)>,
+    windows: Vec<(i32, i32)>,
+}
+
+impl House {
+    fn contains(&self, x: i32, y: i32, margin: i32) -> bool {
+        x >= self.x - margin
+            && x < self.x + self.width + margin
+            && y >= self.y - margin
+            && y < self.y + self.height + margin
+    }
+
+    fn is_wall(&self, x: i32, y: i32) -> bool {
+        self.contains(x, y, 0)
+            && (x == self.x
+                || y == self.y
+                || x == self.x + self.width - 1
+                || y == self.y + self.height - 1)
+    }
+}
+
+struct Village {
+    center: (i32, i32),
+    houses: Vec<House>,
+}
+
+/// Generate complete map directory (tmx, tilesets and images) from a seed
+pub struct Generator {
+    name: String,
+    seed: u64,
+    width: u32,
+    height: u32,
+    forest_density: f32,
+    villages: u32,
+    roads: u32,
+    rivers: u32,
+}
+
+/// Generated terrain, before being written
+struct Generated {
+    tiles: Vec<TileType>,
+    trees: Vec<(i32, i32, u32)>,
+    villages: Vec<Village>,
+}
+
+impl Generator {
+    pub fn new(name: &str, seed: u64) -> Self {
+        Self {
+            name: name.to_string(),
+            seed,
+            width: 200,
+            height: 200,
+            forest_density: 0.2,
+            villages: 2,
+            roads: 2,
+            rivers: 1,
+        }
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn forest_density(mut self, forest_density: f32) -> Self {
+        self.forest_density = forest_density.clamp(0., 1.);
+        self
+    }
+
+    pub fn villages(mut self, villages: u32) -> Self {
+        self.villages = villages;
+        self
+    }
+
+    pub fn roads(mut self, roads: u32) -> Self {
+        self.roads = roads;
+        self
+    }
+
+    pub fn rivers(mut self, rivers: u32) -> Self {
+        self.rivers = rivers;
+        self
+    }
+
+    /// Write map in `resources`/maps/`name` directory
+    pub fn write(&self, resources: &Path) -> Result<(), GeneratorError> {
+        if self.width < MINIMUM_SIZE || self.height < MINIMUM_SIZE {
+            return Err(GeneratorError::InvalidSize(self.width, self.height));
+        }
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let generated = self.generate(&mut rng);
+
+        let output = resources.join("maps").join(&self.name);
+        fs::create_dir_all(&output)?;
+
+        let trees = resources.join("maps").join(TREES_TILESET_MAP);
+        for file_name in [
+            format!("{}.tsx", TREES_TILESET),
+            format!("{}.png", TREES_TILESET),
+            format!("{}__HD.png", TREES_TILESET),
+        ] {
+            fs::copy(trees.join(&file_name), output.join(&file_name))?;
+        }
+
+        fs::write(output.join("terrain.tsx"), self.terrain_tileset())?;
+        self.terrain_image().save(output.join("terrain.png"))?;
+        let background = self.background_image(&generated, &mut rng);
+        background.save(output.join(format!("{}.png", self.name)))?;
+        hd(&background).save(output.join(format!("{}__HD.png", self.name)))?;
+        let interiors = self.interiors_image(&generated);
+        interiors.save(output.join("interiors.png"))?;
+        hd(&interiors).save(output.join("interiors__HD.png"))?;
+        fs::write(
+            output.join(format!("{}.tmx", self.name)),
+            self.tmx(&generated),
+        )?;
+
+        Ok(())
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        Some((y * self.width as i32 + x) as usize)
+    }
+
+    fn generate(&self, rng: &mut StdRng) -> Generated {
+        let grass = noise(rng, self.width, self.height, GRASS_NOISE_CELL);
+        let mut tiles: Vec<TileType> = grass
+            .iter()
+            .map(|value| match value {
+                value if *value < 0.4 => TileType::ShortGrass,
+                value if *value < 0.75 => TileType::MiddleGrass,
+                _ => TileType::HighGrass,
+            })
+            .collect();
+
+        for _ in 0..self.rivers {
+            self.river(rng, &mut tiles);
+        }
+        let villages = (0..self.villages)
+            .map(|i| self.village(rng, &mut tiles, i == 0))
+            .collect::<Vec<Village>>();
+        for i in 0..self.roads {
+            self.road(rng, &mut tiles, &villages, i);
+        }
+        let trees = self.forest(rng, &mut tiles);
+
+        Generated {
+            tiles,
+            trees,
+            villages,
+        }
+    }
+
+    fn set(&self, tiles: &mut [TileType], x: i32, y: i32, type_: TileType) {
+        if let Some(i) = self.index(x, y) {
+            tiles[i] = type_;
+        }
+    }
+
+    /// River cross the map from an edge to the opposite one, with mud banks
+    fn river(&self, rng: &mut StdRng, tiles: &mut [TileType]) {
+        let vertical = rng.gen_bool(0.5);
+        let (length, breadth) = if vertical {
+            (self.height as i32, self.width as i32)
+        } else {
+            (self.width as i32, self.height as i32)
+        };
+        let mut lateral = rng.gen_range(breadth / 4..breadth * 3 / 4);
+
+        for along in 0..length {
+            lateral = (lateral + rng.gen_range(-1..=1)).clamp(RIVER_WIDTH, breadth - RIVER_WIDTH);
+            for offset in -RIVER_WIDTH..=RIVER_WIDTH {
+                let type_ = match offset.abs() {
+                    0 => TileType::DeepWater,
+                    offset if offset < RIVER_WIDTH => TileType::Water,
+                    _ => TileType::Mud,
+                };
+                let (x, y) = if vertical {
+                    (lateral + offset, along)
+                } else {
+                    (along, lateral + offset)
+                };
+                if type_ == TileType::Mud
+                    && self
+                        .index(x, y)
+                        .map(|i| tiles[i] != TileType::Mud && !is_grass(&tiles[i]))
+                        .unwrap_or(true)
+                {
+                    continue;
+                }
+                self.set(tiles, x, y, type_);
+            }
+        }
+    }
+
+    /// Group of houses around a center. First village is near map center.
+    fn village(&self, rng: &mut StdRng, tiles: &mut [TileType], central: bool) -> Village {
+        let (width, height) = (self.width as i32, self.height as i32);
+        let center = if central {
+            (
+                rng.gen_range(width * 2 / 5..width * 3 / 5),
+                rng.gen_range(height * 2 / 5..height * 3 / 5),
+            )
+        } else {
+            (
+                rng.gen_range(width / 5..width * 4 / 5),
+                rng.gen_range(height / 5..height * 4 / 5),
+            )
+        };
+        let count = rng.gen_range(VILLAGE_HOUSES.0..=VILLAGE_HOUSES.1);
+        let mut houses: Vec<House> = vec![];
+
+        for _ in 0..count * 10 {
+            if houses.len() as u32 >= count {
+                break;
+            }
+            let house_width = rng.gen_range(HOUSE_WIDTH.0..=HOUSE_WIDTH.1);
+            let house_height = rng.gen_range(HOUSE_HEIGHT.0..=HOUSE_HEIGHT.1);
+            let x = center.0 + rng.gen_range(-VILLAGE_RADIUS..VILLAGE_RADIUS) - house_width / 2;
+            let y = center.1 + rng.gen_range(-VILLAGE_RADIUS..VILLAGE_RADIUS) - house_height / 2;
+            let mut house = House {
+                x,
+                y,
+                width: house_width,
+                height: house_height,
+                floors: rng.gen_range(1..=2),
+                door: vec![],
+                windows: vec![],
+            };
+
+            // Village center stay free for roads
+            if house.contains(center.0, center.1, ROAD_WIDTH + 1) {
+                continue;
+            }
+            let free = (x - HOUSE_MARGIN..x + house_width + HOUSE_MARGIN).all(|tx| {
+                (y - HOUSE_MARGIN..y + house_height + HOUSE_MARGIN).all(|ty| {
+                    self.index(tx, ty)
+                        .map(|i| is_grass(&tiles[i]))
+                        .unwrap_or(false)
+                        && !houses.iter().any(|other| other.contains(tx, ty, 0))
+                })
+            });
+            if !free {
+                continue;
+            }
+
+            // One door (two tiles wide) and one window on each other side
+            let door_side = rng.gen_range(0..4);
+            for side in 0..4 {
+                let (middle_x, middle_y) = match side {
+                    0 => (x + house_width / 2, y),
+                    1 => (x + house_width - 1, y + house_height / 2),
+                    2 => (x + house_width / 2, y + house_height - 1),
+                    _ => (x, y + house_height / 2),
+                };
+                if side == door_side {
+                    house.door.push((middle_x, middle_y));
+                    // Top and bottom walls are horizontal
+                    if matches!(side, 0 | 2) {
+                        house.door.push((middle_x + 1, middle_y));
+                    } else {
+                        house.door.push((middle_x, middle_y + 1));
+                    }
+                } else {
+                    house.windows.push((middle_x, middle_y));
+                }
+            }
+
+            for tx in x - 1..=x + house_width {
+                for ty in y - 1..=y + house_height {
+                    let type_ = if house.door.contains(&(tx, ty)) {
+                        TileType::Concrete
+                    } else if house.is_wall(tx, ty) {
+                        TileType::BrickWall
+                    } else if house.contains(tx, ty, 0) {
+                        TileType::Concrete
+                    } else {
+                        TileType::Dirt
+                    };
+                    self.set(tiles, tx, ty, type_);
+                }
+            }
+            houses.push(house);
+        }
+
+        Village { center, houses }
+    }
+
+    /// Road cross the map from an edge to the opposite one, passing by a village
+    fn road(&self, rng: &mut StdRng, tiles: &mut [TileType], villages: &[Village], i: u32) {
+        let (width, height) = (self.width as i32, self.height as i32);
+        // Alternate west to east and north to south roads
+        let (start, end) = if i % 2 == 1 {
+            (
+                (rng.gen_range(width / 5..width * 4 / 5), 0),
+                (rng.gen_range(width / 5..width * 4 / 5), height - 1),
+            )
+        } else {
+            (
+                (0, rng.gen_range(height / 5..height * 4 / 5)),
+                (width - 1, rng.gen_range(height / 5..height * 4 / 5)),
+            )
+        };
+        let mut waypoints = vec![start];
+        if !villages.is_empty() {
+            waypoints.push(villages[i as usize % villages.len()].center);
+        }
+        waypoints.push(end);
+        let houses = villages
+            .iter()
+            .flat_map(|village| &village.houses)
+            .collect::<Vec<&House>>();
+
+        for segment in waypoints.windows(2) {
+            for (x, y) in self.road_points(segment[0], segment[1], &houses) {
+                for offset_x in 0..ROAD_WIDTH {
+                    for offset_y in 0..ROAD_WIDTH {
+                        let (x, y) = (x + offset_x, y + offset_y);
+                        if let Some(i) = self.index(x, y) {
+                            tiles[i] = match tiles[i] {
+                                // Bridges
+                                TileType::Water | TileType::DeepWater => TileType::Concrete,
+                                TileType::BrickWall | TileType::Concrete => continue,
+                                _ => TileType::Dirt,
+                            };
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn road_blocked(&self, houses: &[&House], x: i32, y: i32) -> bool {
+        (0..ROAD_WIDTH).any(|offset_x| {
+            (0..ROAD_WIDTH).any(|offset_y| {
+                houses
+                    .iter()
+                    .any(|house| house.contains(x + offset_x, y + offset_y, 0))
+            })
+        })
+    }
+
+    /// Straight line between points, or shortest detour around houses if line cross one
+    fn road_points(&self, from: (i32, i32), to: (i32, i32), houses: &[&House]) -> Vec<(i32, i32)> {
+        let ((from_x, from_y), (to_x, to_y)) = (from, to);
+        let steps = (to_x - from_x).abs().max((to_y - from_y).abs()).max(1);
+        let line = (0..=steps)
+            .map(|step| {
+                (
+                    from_x + (to_x - from_x) * step / steps,
+                    from_y + (to_y - from_y) * step / steps,
+                )
+            })
+            .collect::<Vec<(i32, i32)>>();
+        if !line.iter().any(|(x, y)| self.road_blocked(houses, *x, *y)) {
+            return line;
+        }
+
+        let (start, end) = match (self.index(from_x, from_y), self.index(to_x, to_y)) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return vec![],
+        };
+        let mut previous: Vec<Option<usize>> = vec![None; (self.width * self.height) as usize];
+        previous[start] = Some(start);
+        let mut queue = VecDeque::from([(from_x, from_y)]);
+        while let Some((x, y)) = queue.pop_front() {
+            if (x, y) == to {
+                break;
+            }
+            for (mod_x, mod_y) in [
+                (0, -1),
+                (1, 0),
+                (0, 1),
+                (-1, 0),
+                (1, -1),
+                (1, 1),
+                (-1, 1),
+                (-1, -1),
+            ] {
+                let (next_x, next_y) = (x + mod_x, y + mod_y);
+                if let Some(next) = self.index(next_x, next_y) {
+                    if previous[next].is_none() && !self.road_blocked(houses, next_x, next_y) {
+                        previous[next] = self.index(x, y);
+                        queue.push_back((next_x, next_y));
+                    }
+                }
+            }
+        }
+
+        // No detour (a house lies on the road end) : only draw around houses
+        if previous[end].is_none() {
+            return line
+                .into_iter()
+                .filter(|(x, y)| !self.road_blocked(houses, *x, *y))
+                .collect();
+        }
+
+        let mut points = vec![to];
+        let mut current = end;
+        while current != start {
+            current = previous[current].expect("Path is built from start");
+            points.push((
+                (current as u32 % self.width) as i32,
+                (current as u32 / self.width) as i32,
+            ));
+        }
+        points.reverse();
+        points
+    }
+
+    /// Underbrush on grass where forest noise is low enough, with trees
+    fn forest(&self, rng: &mut StdRng, tiles: &mut [TileType]) -> Vec<(i32, i32, u32)> {
+        let values = noise(rng, self.width, self.height, FOREST_NOISE_CELL);
+        let mut sorted = values.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).expect("Noise values are numbers"));
+        let threshold = match ((sorted.len() as f32 * self.forest_density) as usize).checked_sub(1)
+        {
+            Some(i) => sorted[i],
+            None => return vec![],
+        };
+
+        let mut trees: Vec<(i32, i32, u32)> = vec![];
+        for (i, value) in values.iter().enumerate() {
+            if *value > threshold || !is_grass(&tiles[i]) {
+                continue;
+            }
+            tiles[i] = if *value < threshold * 0.7 {
+                TileType::Underbrush
+            } else {
+                TileType::LightUnderbrush
+            };
+
+            let (x, y) = (
+                (i as u32 % self.width) as i32,
+                (i as u32 / self.width) as i32,
+            );
+            let spaced = trees.iter().all(|(tree_x, tree_y, _)| {
+                (tree_x - x).abs() >= TREES_SPACING || (tree_y - y).abs() >= TREES_SPACING
+            });
+            if spaced && rng.gen_bool(0.3) {
+                tiles[i] = TileType::Trunk;
+                trees.push((x, y, rng.gen_range(0..TREES_TILE_COUNT)));
+            }
+        }
+
+        trees
+    }
+
+    fn terrain_tileset(&self) -> String {
+        let types = TileType::iter().collect::<Vec<TileType>>();
+        let mut tileset = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<tileset version=\"1.5\" tiledversion=\"1.6.0\" name=\"terrain\" tilewidth=\"{size}\" tileheight=\"{size}\" tilecount=\"{count}\" columns=\"{count}\">\n <image source=\"terrain.png\" width=\"{width}\" height=\"{size}\"/>\n",
+            size = TILE_SIZE,
+            count = types.len(),
+            width = TILE_SIZE as usize * types.len(),
+        );
+        for (i, type_) in types.iter().enumerate() {
+            let _ = write!(
+                tileset,
+                " <tile id=\"{}\">\n  <properties>\n   <property name=\"ID\" value=\"{}\"/>\n  </properties>\n </tile>\n",
+                i, type_
+            );
+        }
+        tileset.push_str("</tileset>\n");
+        tileset
+    }
+
+    fn terrain_image(&self) -> RgbaImage {
+        let types = TileType::iter().collect::<Vec<TileType>>();
+        RgbaImage::from_fn(TILE_SIZE * types.len() as u32, TILE_SIZE, |x, _| {
+            color(&types[(x / TILE_SIZE) as usize])
+        })
+    }
+
+    fn background_image(&self, generated: &Generated, rng: &mut StdRng) -> RgbaImage {
+        RgbaImage::from_fn(self.width * TILE_SIZE, self.height * TILE_SIZE, |x, y| {
+            let (tile_x, tile_y) = ((x / TILE_SIZE) as i32, (y / TILE_SIZE) as i32);
+            let roof = generated
+                .villages
+                .iter()
+                .flat_map(|village| &village.houses)
+                .any(|house| house.contains(tile_x, tile_y, 0));
+            let Rgba([r, g, b, a]) = if roof {
+                Rgba([120, 62, 48, 255])
+            } else {
+                color(&generated.tiles[(tile_y * self.width as i32 + tile_x) as usize])
+            };
+            let grain: i16 = rng.gen_range(-8..=8);
+            let grain = |channel: u8| (channel as i16 + grain).clamp(0, 255) as u8;
+            Rgba([grain(r), grain(g), grain(b), a])
+        })
+    }
+
+    /// Houses floor, walls and openings, transparent elsewhere
+    fn interiors_image(&self, generated: &Generated) -> RgbaImage {
+        let houses = generated
+            .villages
+            .iter()
+            .flat_map(|village| &village.houses)
+            .collect::<Vec<&House>>();
+        RgbaImage::from_fn(self.width * TILE_SIZE, self.height * TILE_SIZE, |x, y| {
+            let (tile_x, tile_y) = ((x / TILE_SIZE) as i32, (y / TILE_SIZE) as i32);
+            match houses
+                .iter()
+                .find(|house| house.contains(tile_x, tile_y, 0))
+            {
+                Some(house) if house.door.contains(&(tile_x, tile_y)) => Rgba([110, 80, 50, 255]),
+                Some(house) if house.windows.contains(&(tile_x, tile_y)) => {
+                    Rgba([160, 200, 220, 255])
+                }
+                Some(house) if house.is_wall(tile_x, tile_y) => Rgba([70, 70, 70, 255]),
+                Some(_) => Rgba([190, 170, 140, 255]),
+                None => Rgba([0, 0, 0, 0]),
+            }
+        })
+    }
+
+    fn tmx(&self, generated: &Generated) -> String {
+        let types = TileType::iter().collect::<Vec<TileType>>();
+        let trees_first_gid = types.len() + 1;
+        let (pixel_width, pixel_height) = (self.width * TILE_SIZE, self.height * TILE_SIZE);
+        let mut object_id = 1;
+
+        let terrain = self.csv(|x, y| {
+            let type_ = &generated.tiles[(y * self.width as i32 + x) as usize];
+            1 + types
+                .iter()
+                .position(|t| t == type_)
+                .expect("Tile types come from iter")
+        });
+        let trees: HashMap<(i32, i32), u32> = generated
+            .trees
+            .iter()
+            .map(|(x, y, id)| ((*x, *y), *id))
+            .collect();
+        let decor = self.csv(|x, y| {
+            trees
+                .get(&(x, y))
+                .map(|id| trees_first_gid + *id as usize)
+                .unwrap_or(0)
+        });
+
+        let mut interiors_zones = String::new();
+        let mut interiors_openings = String::new();
+        let houses = generated
+            .villages
+            .iter()
+            .flat_map(|village| &village.houses);
+        for house in houses {
+            let _ = writeln!(
+                interiors_zones,
+                "  <object id=\"{}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\">\n   <properties>\n    <property name=\"FLOORS\" type=\"int\" value=\"{}\"/>\n   </properties>\n  </object>",
+                object_id,
+                house.x * TILE_SIZE as i32,
+                house.y * TILE_SIZE as i32,
+                house.width * TILE_SIZE as i32,
+                house.height * TILE_SIZE as i32,
+                house.floors,
+            );
+            object_id += 1;
+            let openings = house
+                .door
+                .iter()
+                .map(|point| ("Door", point))
+                .chain(house.windows.iter().map(|point| ("Window", point)));
+            for (name, (x, y)) in openings {
+                let _ = writeln!(
+                    interiors_openings,
+                    "  <object id=\"{}\" name=\"{}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>",
+                    object_id,
+                    name,
+                    x * TILE_SIZE as i32,
+                    y * TILE_SIZE as i32,
+                    TILE_SIZE,
+                    TILE_SIZE,
+                );
+                object_id += 1;
+            }
+        }
+
+        // Eight spawn zones around the map center
+        let mut spawn_zones = String::new();
+        let (third_width, third_height) = (pixel_width / 3, pixel_height / 3);
+        for (name, column, row) in [
+            ("NW", 0, 0),
+            ("N", 1, 0),
+            ("NE", 2, 0),
+            ("W", 0, 1),
+            ("E", 2, 1),
+            ("SW", 0, 2),
+            ("S", 1, 2),
+            ("SE", 2, 2),
+        ] {
+            let width = if column == 2 {
+                pixel_width - 2 * third_width
+            } else {
+                third_width
+            };
+            let height = if row == 2 {
+                pixel_height - 2 * third_height
+            } else {
+                third_height
+            };
+            let _ = writeln!(
+                spawn_zones,
+                "  <object id=\"{}\" name=\"{}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>",
+                object_id,
+                name,
+                column * third_width,
+                row * third_height,
+                width,
+                height,
+            );
+            object_id += 1;
+        }
+
+        // One flag by village, kept inside one spawn zone (or map center)
+        let mut flags = String::new();
+        for (i, village) in generated.villages.iter().enumerate() {
+            let column = (village.center.0 as u32 * TILE_SIZE / third_width).min(2);
+            let row = (village.center.1 as u32 * TILE_SIZE / third_height).min(2);
+            let (cell_x, cell_y) = (column * third_width, row * third_height);
+            let cell_right = if column == 2 {
+                pixel_width
+            } else {
+                cell_x + third_width
+            };
+            let cell_bottom = if row == 2 {
+                pixel_height
+            } else {
+                cell_y + third_height
+            };
+            let (mut left, mut top, mut right, mut bottom) = (
+                village.center.0 - VILLAGE_RADIUS,
+                village.center.1 - VILLAGE_RADIUS,
+                village.center.0 + VILLAGE_RADIUS,
+                village.center.1 + VILLAGE_RADIUS,
+            );
+            for house in &village.houses {
+                left = left.min(house.x);
+                top = top.min(house.y);
+                right = right.max(house.x + house.width);
+                bottom = bottom.max(house.y + house.height);
+            }
+            let left = (left.max(0) as u32 * TILE_SIZE).clamp(cell_x, cell_right);
+            let top = (top.max(0) as u32 * TILE_SIZE).clamp(cell_y, cell_bottom);
+            let right = (right.max(0) as u32 * TILE_SIZE).clamp(cell_x, cell_right);
+            let bottom = (bottom.max(0) as u32 * TILE_SIZE).clamp(cell_y, cell_bottom);
+            let _ = writeln!(
+                flags,
+                "  <object id=\"{}\" name=\"Village {}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>",
+                object_id,
+                i + 1,
+                left,
+                top,
+                right - left,
+                bottom - top,
+            );
+            object_id += 1;
+        }
+        // Without village, map center is the objective
+        if generated.villages.is_empty() {
+            let _ = writeln!(
+                flags,
+                "  <object id=\"{}\" name=\"Center\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>",
+                object_id,
+                third_width + third_width / 4,
+                third_height + third_height / 4,
+                third_width / 2,
+                third_height / 2,
+            );
+            object_id += 1;
+        }
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" tiledversion="1.10.0" orientation="orthogonal" renderorder="right-down" width="{width}" height="{height}" tilewidth="{tile}" tileheight="{tile}" infinite="0" nextlayerid="9" nextobjectid="{object_id}">
+ <tileset firstgid="1" source="terrain.tsx"/>
+ <tileset firstgid="{trees_first_gid}" source="{trees}.tsx"/>
+ <layer id="1" name="terrain" width="{width}" height="{height}">
+  <data encoding="csv">
+{terrain}</data>
+ </layer>
+ <layer id="2" name="decor" width="{width}" height="{height}">
+  <data encoding="csv">
+{decor}</data>
+ </layer>
+ <imagelayer id="3" name="background_image">
+  <image source="{name}.png" width="{pixel_width}" height="{pixel_height}"/>
+ </imagelayer>
+ <imagelayer id="4" name="interiors_image" visible="0">
+  <image source="interiors.png" width="{pixel_width}" height="{pixel_height}"/>
+ </imagelayer>
+ <objectgroup id="5" name="interiors_zones" visible="0">
+{interiors_zones} </objectgroup>
+ <objectgroup id="6" name="interiors_openings" visible="0">
+{interiors_openings} </objectgroup>
+ <objectgroup id="7" name="spawn_zones">
+{spawn_zones} </objectgroup>
+ <objectgroup id="8" name="flags">
+{flags} </objectgroup>
+</map>
+"#,
+            width = self.width,
+            height = self.height,
+            tile = TILE_SIZE,
+            trees = TREES_TILESET,
+            name = self.name,
+        )
+    }
+
+    /// Tiled csv layer data
+    fn csv(&self, gid: impl Fn(i32, i32) -> usize) -> String {
+        (0..self.height as i32)
+            .map(|y| {
+                (0..self.width as i32)
+                    .map(|x| gid(x, y).to_string())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            })
+            .collect::<Vec<String>>()
+            .join(",\n")
+            + "\n"
+    }
+}
+
+fn is_grass(type_: &TileType) -> bool {
+    matches!(
+        type_,
+        TileType::ShortGrass | TileType::MiddleGrass | TileType::HighGrass
+    )
+}
+
+fn color(type_: &TileType) -> Rgba<u8> {
+    match type_ {
+        TileType::ShortGrass => Rgba([118, 146, 74, 255]),
+        TileType::MiddleGrass => Rgba([104, 134, 64, 255]),
+        TileType::HighGrass => Rgba([92, 120, 56, 255]),
+        TileType::Dirt => Rgba([150, 126, 90, 255]),
+        TileType::Mud => Rgba([112, 94, 66, 255]),
+        TileType::Concrete => Rgba([160, 160, 155, 255]),
+        TileType::BrickWall => Rgba([140, 80, 60, 255]),
+        TileType::Trunk => Rgba([60, 80, 40, 255]),
+        TileType::Water => Rgba([70, 110, 140, 255]),
+        TileType::DeepWater => Rgba([50, 86, 120, 255]),
+        TileType::Underbrush => Rgba([66, 94, 48, 255]),
+        TileType::LightUnderbrush => Rgba([80, 108, 54, 255]),
+        TileType::MiddleWoodLogs => Rgba([110, 84, 56, 255]),
+        TileType::Hedge => Rgba([58, 88, 42, 255]),
+        TileType::MiddleRock => Rgba([128, 124, 118, 255]),
+        TileType::Crater => Rgba([90, 76, 60, 255]),
+    }
+}
+
+fn hd(image: &RgbaImage) -> RgbaImage {
+    image::imageops::resize(
+        image,
+        image.width() * HD_RATIO,
+        image.height() * HD_RATIO,
+        FilterType::Nearest,
+    )
+}
+
+/// Smooth value noise in [0, 1] by tile, from random values at cells corners
+fn noise(rng: &mut StdRng, width: u32, height: u32, cell: u32) -> Vec<f32> {
+    let columns = width / cell + 2;
+    let rows = height / cell + 2;
+    let corners: Vec<f32> = (0..columns * rows).map(|_| rng.gen()).collect();
+    let corner = |x: u32, y: u32| corners[(y * columns + x) as usize];
+    let smooth = |t: f32| t * t * (3. - 2. * t);
+
+    let mut values = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let (cell_x, cell_y) = (x / cell, y / cell);
+            let tx = smooth((x % cell) as f32 / cell as f32);
+            let ty = smooth((y % cell) as f32 / cell as f32);
+            let top = corner(cell_x, cell_y) * (1. - tx) + corner(cell_x + 1, cell_y) * tx;
+            let bottom =
+                corner(cell_x, cell_y + 1) * (1. - tx) + corner(cell_x + 1, cell_y + 1) * tx;
+            values.push(top * (1. - ty) + bottom * ty);
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(0)]
+    #[case(42)]
+    fn generation_is_deterministic(#[case] seed: u64) {
+        // Given
+        let generator = Generator::new("Test", seed).size(80, 60);
+
+        // When
+        let first = generator.generate(&mut StdRng::seed_from_u64(seed));
+        let second = generator.generate(&mut StdRng::seed_from_u64(seed));
+
+        // Then
+        assert_eq!(first.tiles, second.tiles);
+        assert_eq!(first.trees, second.trees);
+    }
+
+    #[rstest]
+    #[case(0, 0)]
+    #[case(10, 10)]
+    #[case(MINIMUM_SIZE - 1, 200)]
+    #[case(200, MINIMUM_SIZE - 1)]
+    fn too_small_size_is_refused(#[case] width: u32, #[case] height: u32) {
+        // Given
+        let generator = Generator::new("Test", 0).size(width, height);
+
+        // When
+        let result = generator.write(&std::env::temp_dir().join("oc_generator_test"));
+
+        // Then
+        assert!(matches!(result, Err(GeneratorError::InvalidSize(_, _))));
+    }
+
+    #[rstest]
+    #[case(MINIMUM_SIZE, MINIMUM_SIZE)]
+    #[case(MINIMUM_SIZE, 300)]
+    #[case(300, MINIMUM_SIZE)]
+    fn minimum_size_is_generated(#[case] width: u32, #[case] height: u32) {
+        // Given
+        let generator = Generator::new("Test", 0)
+            .size(width, height)
+            .villages(3)
+            .roads(3)
+            .rivers(2);
+
+        for seed in 0..20 {
+            // When
+            let generated = generator.generate(&mut StdRng::seed_from_u64(seed));
+
+            // Then
+            assert_eq!(generated.tiles.len(), (width * height) as usize);
+        }
+    }
+
+    #[rstest]
+    fn road_goes_around_houses() {
+        // Given a house between road ends
+        let generator = Generator::new("Test", 0).size(60, 60);
+        let house = House {
+            x: 20,
+            y: 20,
+            width: 10,
+            height: 10,
+            floors: 1,
+            door: vec![],
+            windows: vec![],
+        };
+
+        // When
+        let points = generator.road_points((0, 25), (59, 25), &[&house]);
+
+        // Then
+        assert_eq!(points.first(), Some(&(0, 25)));
+        assert_eq!(points.last(), Some(&(59, 25)));
+        assert!(points
+            .iter()
+            .all(|(x, y)| !generator.road_blocked(&[&house], *x, *y)));
+    }
+}
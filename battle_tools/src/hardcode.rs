@@ -2,6 +2,7 @@ use battle_core::{
     deployment::{Deployment, SoldierDeployment, VehicleDeployment},
     entity::vehicle::{OnBoardPlace, VehicleType},
     game::{
+        role::SoldierRole,
         weapon::{Magazine, Weapon},
         Side,
     },
@@ -21,6 +22,12 @@ pub fn demo1_deployment() -> Deployment {
         for y in 0..5 {
             // let x: f32 = rng.gen_range(0.0..800.0);
             // let y: f32 = rng.gen_range(0.0..800.0);
+            // First squad members are a scout and a sniper
+            let role = match (x, y) {
+                (0, 3) => SoldierRole::Scout,
+                (0, 4) => SoldierRole::Sniper,
+                _ => SoldierRole::Rifleman,
+            };
             let soldier = SoldierDeployment::new(
                 SoldierIndex(soldiers_index),
                 Side::A,
@@ -34,7 +41,8 @@ pub fn demo1_deployment() -> Deployment {
                     Magazine::full(Magazine::MosinNagant(0)),
                     Magazine::full(Magazine::MosinNagant(0)),
                 ],
-            );
+            )
+            .with_role(role);
             soldiers.push(soldier);
             soldiers_index += 1;
         }
@@ -45,20 +53,37 @@ pub fn demo1_deployment() -> Deployment {
         for y in 0..5 {
             // let x: f32 = rng.gen_range(0.0..800.0);
             // let y: f32 = rng.gen_range(0.0..800.0);
-            let soldier = SoldierDeployment::new(
-                SoldierIndex(soldiers_index),
-                Side::B,
-                WorldPoint::from(Vec2::new(x as f32 * 10. + 550., y as f32 * 10. + 250.)),
-                SquadUuid(squad),
-                Some(Weapon::MosinNagantM1924(
-                    false,
-                    Some(Magazine::full(Magazine::MosinNagant(0))),
-                )),
-                vec![
-                    Magazine::full(Magazine::MosinNagant(0)),
-                    Magazine::full(Magazine::MosinNagant(0)),
-                ],
-            );
+            let point = WorldPoint::from(Vec2::new(x as f32 * 10. + 550., y as f32 * 10. + 250.));
+            // Last squad member is an anti-tank rifleman
+            let soldier = if y == 4 {
+                SoldierDeployment::new(
+                    SoldierIndex(soldiers_index),
+                    Side::B,
+                    point,
+                    SquadUuid(squad),
+                    Some(Weapon::Ptrd41(
+                        false,
+                        Some(Magazine::full(Magazine::Ptrd(0))),
+                    )),
+                    vec![Magazine::full(Magazine::Ptrd(0)); 6],
+                )
+                .with_role(SoldierRole::AntiTank)
+            } else {
+                SoldierDeployment::new(
+                    SoldierIndex(soldiers_index),
+                    Side::B,
+                    point,
+                    SquadUuid(squad),
+                    Some(Weapon::MosinNagantM1924(
+                        false,
+                        Some(Magazine::full(Magazine::MosinNagant(0))),
+                    )),
+                    vec![
+                        Magazine::full(Magazine::MosinNagant(0)),
+                        Magazine::full(Magazine::MosinNagant(0)),
+                    ],
+                )
+            };
             soldiers.push(soldier);
             soldiers_index += 1;
         }
@@ -1 +1,3 @@
+pub mod generator;
 pub mod hardcode;
+pub mod lint;
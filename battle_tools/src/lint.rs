@@ -0,0 +1,400 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Display,
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use battle_core::{
+    entity::vehicle::VehicleType,
+    graphics::vehicle::VehicleGraphicInfos,
+    map::{
+        reader::{MapReader, MapReaderError},
+        Map,
+    },
+    physics::path::{Direction, PathMode},
+    types::GridPoint,
+    utils::WorldShape,
+};
+use oc_core::spawn::SpawnZoneName;
+
+// HD images are drawn with this zoom factor (see battle_gui `Zoom::In`)
+pub const HD_RATIO: u32 = 3;
+const HD_SUFFIX: &str = "__HD";
+const SPAWN_ZONE_NAMES: [SpawnZoneName; 8] = [
+    SpawnZoneName::North,
+    SpawnZoneName::NorthEst,
+    SpawnZoneName::Est,
+    SpawnZoneName::SouthEst,
+    SpawnZoneName::South,
+    SpawnZoneName::SouthWest,
+    SpawnZoneName::West,
+    SpawnZoneName::NorthWest,
+];
+
+pub enum Problem {
+    Error(String),
+    Warning(String),
+}
+
+impl Problem {
+    pub fn is_error(&self) -> bool {
+        matches!(self, Problem::Error(_))
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Problem::Error(message) => f.write_str(&format!("error : {}", message)),
+            Problem::Warning(message) => f.write_str(&format!("warning : {}", message)),
+        }
+    }
+}
+
+/// Load map like the game does and report its problems. Missing layers don't prevent
+/// other checks : map is read leniently to report as many problems as possible.
+pub fn lint_map(name: &str, resources: &Path) -> Vec<Problem> {
+    let mut problems = vec![];
+
+    let reader = match MapReader::new(name, resources) {
+        Ok(reader) => reader.lenient(),
+        Err(error) => return vec![Problem::Error(error.to_string())],
+    };
+
+    let missing_layers = reader.missing_layers();
+    for layer in &missing_layers {
+        problems.push(Problem::Error(format!("Missing layer '{}'", layer)));
+    }
+
+    match reader.unknown_terrain_ids() {
+        Ok(unknown) => {
+            for (tile_id, error) in unknown {
+                problems.push(Problem::Error(format!(
+                    "Terrain tileset tile {} : {}",
+                    tile_id, error
+                )))
+            }
+        }
+        // Already reported as missing layer
+        Err(MapReaderError::LayerNotFound(_)) => {}
+        Err(error) => problems.push(Problem::Error(error.to_string())),
+    }
+
+    let map = match reader.build() {
+        Ok(map) => map,
+        Err(MapReaderError::LayerNotFound(_)) => return problems,
+        Err(error) => {
+            problems.push(Problem::Error(error.to_string()));
+            return problems;
+        }
+    };
+
+    problems.extend(spawn_zones_problems(&map));
+    problems.extend(reachability_problems(&map));
+    problems.extend(hd_images_problems(&map, resources));
+    problems
+}
+
+fn intersects(a: &WorldShape, b: &WorldShape) -> bool {
+    a.top_left.x < b.bottom_right.x
+        && b.top_left.x < a.bottom_right.x
+        && a.top_left.y < b.bottom_right.y
+        && b.top_left.y < a.bottom_right.y
+}
+
+/// Flags ownership is computed from spawn zones (see `Map::one_of_spawn_zone_contains_flag`)
+fn spawn_zones_problems(map: &Map) -> Vec<Problem> {
+    let mut problems = vec![];
+    let spawn_zones = map.find_spawn_zones(&[SpawnZoneName::All]);
+
+    for name in SPAWN_ZONE_NAMES {
+        let count = spawn_zones.iter().filter(|s| s.name() == &name).count();
+        if count == 0 {
+            problems.push(Problem::Warning(format!(
+                "No spawn zone {:?} : choosing it for a battle will crash",
+                name
+            )))
+        } else if count > 1 {
+            problems.push(Problem::Warning(format!(
+                "{} spawn zones {:?} : only first one is used for flags ownership",
+                count, name
+            )))
+        }
+    }
+
+    for flag in map.flags() {
+        let mut containers = vec![];
+        for spawn_zone in &spawn_zones {
+            if spawn_zone.contains(&flag.shape()) {
+                containers.push(spawn_zone.name());
+            } else if intersects(&spawn_zone.shape(), &flag.shape()) {
+                problems.push(Problem::Error(format!(
+                    "Flag '{}' partially overlaps spawn zone {:?} : it will not be owned by this zone",
+                    flag.name().0,
+                    spawn_zone.name()
+                )))
+            }
+        }
+        if containers.len() > 1 {
+            problems.push(Problem::Warning(format!(
+                "Flag '{}' is in several spawn zones ({:?}) : it will be owned by both sides if they choose them",
+                flag.name().0,
+                containers
+            )))
+        }
+    }
+
+    problems
+}
+
+fn passable(map: &Map, grid_point: &GridPoint, path_mode: &PathMode) -> bool {
+    match path_mode {
+        PathMode::Walk => !map.closed_wall(grid_point),
+        PathMode::Drive(size) => map.point_allow_vehicle(grid_point, size),
+    }
+}
+
+/// Connected parts of the map for given path mode, by grid point
+fn components(map: &Map, path_mode: &PathMode) -> HashMap<GridPoint, usize> {
+    let mut components = HashMap::new();
+    let mut component = 0;
+
+    for y in 0..map.height() as i32 {
+        for x in 0..map.width() as i32 {
+            let start = GridPoint::new(x, y);
+            if components.contains_key(&start) || !passable(map, &start, path_mode) {
+                continue;
+            }
+
+            component += 1;
+            let mut queue = VecDeque::from([start]);
+            components.insert(start, component);
+            while let Some(grid_point) = queue.pop_front() {
+                for ((next, _), _) in map.successors(&(grid_point, Direction::North), path_mode) {
+                    if map.contains(&next) && !components.contains_key(&next) {
+                        components.insert(next, component);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+    }
+
+    components
+}
+
+/// Biggest connected part
+fn main_component(components: &HashMap<GridPoint, usize>) -> Option<usize> {
+    let mut sizes: HashMap<usize, usize> = HashMap::new();
+    for component in components.values() {
+        *sizes.entry(*component).or_default() += 1;
+    }
+    sizes
+        .iter()
+        .max_by_key(|(component, size)| (**size, *component))
+        .map(|(component, _)| *component)
+}
+
+fn shape_grid_points(map: &Map, shape: &WorldShape) -> Vec<GridPoint> {
+    let from = map.grid_point_from_world_point(&shape.top_left);
+    let to = map.grid_point_from_world_point(&shape.bottom_right);
+    let mut grid_points = vec![];
+    for x in from.x..=to.x {
+        for y in from.y..=to.y {
+            let grid_point = GridPoint::new(x, y);
+            if map.contains(&grid_point) {
+                grid_points.push(grid_point);
+            }
+        }
+    }
+    grid_points
+}
+
+fn shape_components(
+    map: &Map,
+    components: &HashMap<GridPoint, usize>,
+    shape: &WorldShape,
+) -> HashSet<usize> {
+    shape_grid_points(map, shape)
+        .iter()
+        .filter_map(|grid_point| components.get(grid_point).copied())
+        .collect()
+}
+
+/// Spawn zones and flags must be reachable from the main part of the map, on foot and
+/// by vehicles for spawn zones
+fn reachability_problems(map: &Map) -> Vec<Problem> {
+    let mut problems = vec![];
+    let walk_components = components(map, &PathMode::Walk);
+    let main = match main_component(&walk_components) {
+        Some(main) => main,
+        None => return problems,
+    };
+    let vehicle_size = *VehicleGraphicInfos::from_type(&VehicleType::T26).size();
+    let drive_components = components(map, &PathMode::Drive(vehicle_size));
+    let drive_main = main_component(&drive_components);
+
+    for spawn_zone in map.find_spawn_zones(&[SpawnZoneName::All]) {
+        let shape = spawn_zone.shape();
+        if !shape_components(map, &walk_components, &shape).contains(&main) {
+            problems.push(Problem::Error(format!(
+                "Spawn zone {:?} is unreachable on foot",
+                spawn_zone.name()
+            )));
+            continue;
+        }
+
+        let stuck = shape_grid_points(map, &shape)
+            .iter()
+            .filter(|grid_point| {
+                walk_components
+                    .get(grid_point)
+                    .map(|component| *component != main)
+                    .unwrap_or(false)
+            })
+            .count();
+        if stuck > 0 {
+            problems.push(Problem::Warning(format!(
+                "{} tiles of spawn zone {:?} are unreachable on foot : soldiers deployed there will be stuck",
+                stuck,
+                spawn_zone.name()
+            )))
+        }
+
+        if let Some(drive_main) = drive_main {
+            if !shape_components(map, &drive_components, &shape).contains(&drive_main) {
+                problems.push(Problem::Warning(format!(
+                    "Spawn zone {:?} is unreachable by vehicles",
+                    spawn_zone.name()
+                )))
+            }
+        }
+    }
+
+    for flag in map.flags() {
+        if !shape_components(map, &walk_components, &flag.shape()).contains(&main) {
+            problems.push(Problem::Error(format!(
+                "Flag '{}' is unreachable on foot",
+                flag.name().0
+            )))
+        }
+    }
+
+    problems
+}
+
+fn png_size(path: &Path) -> Result<(u32, u32), String> {
+    let mut header = [0; 24];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .map_err(|error| format!("Unable to read {} : {}", path.display(), error))?;
+    if &header[1..4] != b"PNG" {
+        return Err(format!("{} is not a png image", path.display()));
+    }
+
+    Ok((
+        u32::from_be_bytes([header[16], header[17], header[18], header[19]]),
+        u32::from_be_bytes([header[20], header[21], header[22], header[23]]),
+    ))
+}
+
+fn hd_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!("{}{}.png", stem, HD_SUFFIX))
+}
+
+/// HD images must exist and be exactly `HD_RATIO` times bigger
+fn hd_images_problems(map: &Map, resources: &Path) -> Vec<Problem> {
+    let mut problems = vec![];
+    let mut image_paths = vec![
+        map.background_image_path().clone(),
+        map.interiors_image_path().clone(),
+    ];
+    image_paths.extend(map.decor().image_paths().clone());
+
+    for image_path in image_paths {
+        let path = resources.join(image_path.strip_prefix("/").unwrap_or(&image_path));
+        let hd_path = hd_path(&path);
+        if !hd_path.exists() {
+            problems.push(Problem::Warning(format!(
+                "Missing HD image {}",
+                hd_path.display()
+            )));
+            continue;
+        }
+
+        match (png_size(&path), png_size(&hd_path)) {
+            (Ok((width, height)), Ok((hd_width, hd_height))) => {
+                if hd_width != width * HD_RATIO || hd_height != height * HD_RATIO {
+                    problems.push(Problem::Error(format!(
+                        "HD image {} is {}x{} but should be {}x{}",
+                        hd_path.display(),
+                        hd_width,
+                        hd_height,
+                        width * HD_RATIO,
+                        height * HD_RATIO,
+                    )))
+                }
+            }
+            (Err(error), _) | (_, Err(error)) => problems.push(Problem::Error(error)),
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use super::*;
+
+    #[fixture]
+    fn resources() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/resources")
+    }
+
+    #[rstest]
+    #[case("error : Missing layer 'flags'")]
+    #[case("error : Missing layer 'decor'")]
+    #[case("warning : 2 spawn zones North : only first one is used for flags ownership")]
+    #[case("error : Spawn zone NorthWest is unreachable on foot")]
+    #[case("warning : 24 tiles of spawn zone North are unreachable on foot : soldiers deployed there will be stuck")]
+    #[case("warning : Spawn zone SouthEst is unreachable by vehicles")]
+    fn lint_reports_all_problems(resources: PathBuf, #[case] expected: &str) {
+        // Given a map without flags and decor layers, with a closed house and a deep water
+        // moat (see tests/resources/maps/Lint1)
+        // When
+        let problems: Vec<String> = lint_map("Lint1", &resources)
+            .iter()
+            .map(|problem| problem.to_string())
+            .collect();
+
+        // Then
+        assert!(
+            problems.contains(&expected.to_string()),
+            "{:?} not in {:?}",
+            expected,
+            problems
+        );
+    }
+
+    #[rstest]
+    fn lint_reports_unreachable_zone_once(resources: PathBuf) {
+        // Given a spawn zone enclosed by house walls (see tests/resources/maps/Lint1)
+        // When
+        let problems = lint_map("Lint1", &resources);
+
+        // Then
+        let north_west = problems
+            .iter()
+            .filter(|problem| problem.to_string().contains("NorthWest"))
+            .count();
+        assert_eq!(north_west, 1);
+    }
+}
@@ -0,0 +1,19 @@
+<?xml version="1.0" encoding="UTF-8"?>
+<tileset version="1.5" tiledversion="1.6.0" name="terrain" tilewidth="5" tileheight="5" tilecount="3" columns="3">
+ <image source="terrain.png" width="15" height="5"/>
+ <tile id="0">
+  <properties>
+   <property name="ID" value="ShortGrass"/>
+  </properties>
+ </tile>
+ <tile id="1">
+  <properties>
+   <property name="ID" value="BrickWall"/>
+  </properties>
+ </tile>
+ <tile id="2">
+  <properties>
+   <property name="ID" value="DeepWater"/>
+  </properties>
+ </tile>
+</tileset>
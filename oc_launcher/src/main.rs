@@ -88,21 +88,14 @@ impl Launcher {
             "Demo1",
             "assets/demo1_deployment.json",
             vec!["W", "NW", "SW"],
-            vec!["ALL"],
         )?;
         Ok(())
     }
 
-    fn launch(
-        &self,
-        map_name: &str,
-        deployment: &str,
-        side_a_controls: Vec<&str>,
-        side_b_controls: Vec<&str>,
-    ) -> Result<()> {
+    fn launch(&self, map_name: &str, deployment: &str, controls: Vec<&str>) -> Result<()> {
+        // Opponent side controls the whole map when not proposed in lobby
         BattleLauncher::new(map_name, &Path::new(deployment).to_path_buf(), "a")?
-            .side_a_controls(side_a_controls.into_iter().map(String::from).collect())
-            .side_b_controls(side_b_controls.into_iter().map(String::from).collect())
+            .controls(controls.into_iter().map(String::from).collect())
             .launch()?;
         Ok(())
     }
@@ -10,8 +10,7 @@ pub struct BattleLauncher {
     server_rep_address: String,
     server_bind_address: String,
     side: String,
-    side_a_controls: Vec<String>,
-    side_b_controls: Vec<String>,
+    controls: Vec<String>,
 }
 
 impl BattleLauncher {
@@ -28,8 +27,7 @@ impl BattleLauncher {
             server_rep_address: "tcp://0.0.0.0:4255".to_string(),
             server_bind_address: "tcp://0.0.0.0:4256".to_string(),
             side: side.to_string(),
-            side_a_controls: vec![],
-            side_b_controls: vec![],
+            controls: vec![],
         })
     }
 
@@ -68,13 +66,8 @@ impl BattleLauncher {
         self
     }
 
-    pub fn side_a_controls(mut self, value: Vec<String>) -> Self {
-        self.side_a_controls = value;
-        self
-    }
-
-    pub fn side_b_controls(mut self, value: Vec<String>) -> Self {
-        self.side_b_controls = value;
+    pub fn controls(mut self, value: Vec<String>) -> Self {
+        self.controls = value;
         self
     }
 
@@ -87,15 +80,10 @@ impl BattleLauncher {
         let server_rep_address = &format!("--server-rep-address={}", self.server_rep_address);
         let server_bind_address = &format!("--server-bind-address={}", self.server_bind_address);
         let side = &format!("--side={}", self.side);
-        let side_a_control = self
-            .side_a_controls
-            .iter()
-            .map(|c| format!("--side-a-control={}", c))
-            .collect::<Vec<String>>();
-        let side_b_control = self
-            .side_b_controls
+        let controls = self
+            .controls
             .iter()
-            .map(|c| format!("--side-b-control={}", c))
+            .map(|c| format!("--control={}", c))
             .collect::<Vec<String>>();
 
         let mut command =
@@ -107,8 +95,7 @@ impl BattleLauncher {
             .arg(server_rep_address)
             .arg(server_bind_address)
             .arg(side)
-            .args(side_a_control)
-            .args(side_b_control);
+            .args(controls);
 
         let command_line = format!("{:?}", command);
         command